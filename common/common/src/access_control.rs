@@ -0,0 +1,95 @@
+extern crate alloc;
+use crate::{
+    errors::Errors,
+    events::{emit, Events},
+    keys::ROLES_DICT,
+};
+use alloc::string::{String, ToString};
+use casper_contract::contract_api::runtime;
+use casper_types::Key;
+use casperlabs_contract_utils::{ContractContext, ContractStorage, Dict};
+
+pub const ADMIN_ROLE: &str = "admin";
+pub const PARAMETER_MANAGER_ROLE: &str = "parameter_manager";
+pub const PAUSER_ROLE: &str = "pauser";
+pub const ORACLE_FEEDER_ROLE: &str = "oracle_feeder";
+pub const REFERRER_MANAGER_ROLE: &str = "referrer_manager";
+
+pub struct Roles {
+    dict: Dict,
+}
+impl Roles {
+    pub fn instance() -> Roles {
+        Roles {
+            dict: Dict::instance(ROLES_DICT),
+        }
+    }
+    pub fn init() {
+        Dict::init(ROLES_DICT)
+    }
+    pub fn get(&self, role: &str, account: &Key) -> bool {
+        self.dict
+            .get_by_values((role.to_string(), account))
+            .unwrap_or_default()
+    }
+    pub fn set(&self, role: &str, account: &Key, value: bool) {
+        self.dict.set_by_values((role.to_string(), account), value);
+    }
+}
+
+pub trait ACCESSCONTROL<Storage: ContractStorage>: ContractContext<Storage> {
+    fn init(&self, admin: Key) {
+        Roles::init();
+        self._grant_role(ADMIN_ROLE, admin);
+    }
+
+    fn has_role(&self, role: String, account: Key) -> bool {
+        Roles::instance().get(&role, &account)
+    }
+
+    fn only_role(&self, role: &str) {
+        if !Roles::instance().get(role, &self.get_caller()) {
+            runtime::revert(Errors::MissingRole);
+        }
+    }
+
+    /// @notice grants `role` to `account`, caller must be an admin
+    fn grant_role(&self, role: String, account: Key) {
+        self.only_role(ADMIN_ROLE);
+        self._grant_role(&role, account);
+    }
+
+    /// @notice revokes `role` from `account`, caller must be an admin
+    fn revoke_role(&self, role: String, account: Key) {
+        self.only_role(ADMIN_ROLE);
+        self._revoke_role(&role, account);
+    }
+
+    /// @notice allows caller to give up a role it holds
+    fn renounce_role(&self, role: String) {
+        self.only_role(&role);
+        self._revoke_role(&role, self.get_caller());
+    }
+
+    fn _grant_role(&self, role: &str, account: Key) {
+        if !Roles::instance().get(role, &account) {
+            Roles::instance().set(role, &account, true);
+            emit(&Events::RoleGranted {
+                role: role.to_string(),
+                account,
+                sender: self.get_caller(),
+            });
+        }
+    }
+
+    fn _revoke_role(&self, role: &str, account: Key) {
+        if Roles::instance().get(role, &account) {
+            Roles::instance().set(role, &account, false);
+            emit(&Events::RoleRevoked {
+                role: role.to_string(),
+                account,
+                sender: self.get_caller(),
+            });
+        }
+    }
+}
@@ -81,6 +81,43 @@ pub enum Errors {
     DivisionByZero11,
     DivisionByZero12,
     DivisionByZero13,
+    LiquidityBelowMinimum,
+    InvalidWithdrawAmount,
+    MissingRole,
+    InvalidParameter,
+    NoPendingParameter,
+    TimelockNotExpired,
+    OperationPaused,
+    OperationNotPaused,
+    InvalidOperation,
+    UnsupportedSchemaVersion,
+    MissingMigration,
+    InvalidReferralCode,
+    ReferralCodeTaken,
+    ReferralCodeAlreadyClaimed,
+    UnknownReferralCode,
+    NoReferralCode,
+    InvalidCursor,
+    InvalidSnapshotRange,
+    MultiplicationOverflow13,
+    DivisionByZero14,
+    MultiplicationOverflow14,
+    DivisionByZero15,
+    AdditionOverflow19,
+    InsufficientBalance,
+    InsufficientAllowance,
+    InsufficientOutputAmount,
+    InvalidPath,
+    MultiplicationOverflow15,
+    DivisionByZero16,
+    MultiplicationOverflow16,
+    DivisionByZero17,
+    SubtractionUnderflow8,
+    MultiplicationOverflow17,
+    SubtractionUnderflow9,
+    MultiplicationOverflow18,
+    SubtractionUnderflow10,
+    AdditionOverflow20,
 }
 
 impl From<Errors> for ApiError {
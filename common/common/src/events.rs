@@ -1,14 +1,14 @@
 extern crate alloc;
-use crate::functions::package_hash;
-use alloc::{
-    collections::BTreeMap,
-    format,
-    string::{String, ToString},
-    vec::Vec,
+use crate::keys::EVENTS_SCHEMA;
+use alloc::{string::String, vec::Vec};
+use casper_event_standard::{Event, Schemas};
+use casper_types::{
+    bytesrepr::{self, ToBytes},
+    Key, U128, U256,
 };
-use casper_contract::contract_api::storage;
-use casper_types::{Key, URef, U128, U256};
+use casperlabs_contract_utils::set_key;
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Events {
     StakeStart {
         stake_id: Vec<u32>,
@@ -68,77 +68,512 @@ pub enum Events {
     LiquidityGuardStatus {
         is_active: bool,
     },
+    RoleGranted {
+        role: String,
+        account: Key,
+        sender: Key,
+    },
+    RoleRevoked {
+        role: String,
+        account: Key,
+        sender: Key,
+    },
+    ParameterProposed {
+        name: String,
+        value: U256,
+        eta: U256,
+    },
+    ParameterUpdated {
+        name: String,
+        old_value: U256,
+        new_value: U256,
+    },
+    ParameterCancelled {
+        name: String,
+    },
+    Paused {
+        operation: String,
+        account: Key,
+    },
+    Unpaused {
+        operation: String,
+        account: Key,
+    },
+    Migrated {
+        contract_hash: Key,
+        from_version: u32,
+        to_version: u32,
+    },
+    ReferralLinkCreated {
+        referrer: Key,
+        referral_id: Vec<u32>,
+        staker: Key,
+        stake_id: Vec<u32>,
+        referrer_shares: U256,
+    },
+    CriticalMassActivated {
+        referrer: Key,
+        total_amount: U256,
+        activation_day: U256,
+    },
+    CriticalMassReset {
+        referrer: Key,
+        total_amount: U256,
+    },
+    DailySnapshot {
+        day: U256,
+        total_shares: U256,
+        inflation_amount: U256,
+        scheduled_to_end: U256,
+        referral_total_shares: U256,
+        referral_inflation_amount: U256,
+        referral_scheduled_to_end: U256,
+        liquidity_total_shares: U256,
+        liquidity_inflation_amount: U256,
+    },
+    LiquidityRatesAdjusted {
+        liquidity_rate: u32,
+        inflation_rate: u32,
+        is_liquidity_guard_active: bool,
+    },
+    ReferralCodeClaimed {
+        code: String,
+        owner: Key,
+    },
+    ReferralCodeTransferred {
+        code: String,
+        from: Key,
+        to: Key,
+    },
+    ReferralCodeReleased {
+        code: String,
+        owner: Key,
+    },
+    TierLinkCreated {
+        upline: Key,
+        tier_link_id: Vec<u32>,
+        staker: Key,
+        stake_id: Vec<u32>,
+        tier: u8,
+        tier_shares: U256,
+    },
+    TierReferralCollected {
+        staker: Key,
+        stake_id: Vec<u32>,
+        upline: Key,
+        tier_link_id: Vec<u32>,
+        reward_amount: U256,
+    },
+    ReferralInterestCollectedAll {
+        referrer: Key,
+        from_cursor: U256,
+        next_cursor: U256,
+        links_collected: U256,
+        reward_amount: U256,
+    },
+    ReferrerListUpdated {
+        referrer: Key,
+        list: String,
+        listed: bool,
+        sender: Key,
+    },
+    ReferrerIgnored {
+        staker: Key,
+        referrer: Key,
+    },
+    InflationAssigned {
+        sender: Key,
+    },
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct StakeStart {
+    pub stake_id: Vec<u32>,
+    pub staker_address: Key,
+    pub referral_address: Key,
+    pub staked_amount: U256,
+    pub stakes_shares: U256,
+    pub referral_shares: U256,
+    pub start_day: U256,
+    pub lock_days: U256,
+    pub dai_equivalent: U256,
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct StakeEnd {
+    pub stake_id: Vec<u32>,
+    pub staker_address: Key,
+    pub referral_address: Key,
+    pub staked_amount: U256,
+    pub stakes_shares: U256,
+    pub referral_shares: U256,
+    pub reward_amount: U256,
+    pub close_day: U256,
+    pub penalty_amount: U256,
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct InterestScraped {
+    pub stake_id: Vec<u32>,
+    pub staker_address: Key,
+    pub scrape_amount: U256,
+    pub scrape_day: U256,
+    pub stakers_penalty: U256,
+    pub referrer_penalty: U256,
+    pub current_stakeable_day: U256,
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct ReferralCollected {
+    pub staker: Key,
+    pub stake_id: Vec<u32>,
+    pub referrer: Key,
+    pub referrer_id: Vec<u32>,
+    pub reward_amount: U256,
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct NewGlobals {
+    pub total_shares: U256,
+    pub total_staked: U256,
+    pub share_rate: U256,
+    pub referral_shares: U256,
+    pub current_stakeable_day: U256,
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct NewSharePrice {
+    pub new_share_price: U256,
+    pub old_share_price: U256,
+    pub current_stakeable_day: u64,
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct UniswapReserves {
+    pub reserve_a: U128,
+    pub reserve_b: U128,
+    pub block_timestamp_last: u64,
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct LiquidityGuardStatus {
+    pub is_active: bool,
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct RoleGranted {
+    pub role: String,
+    pub account: Key,
+    pub sender: Key,
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct RoleRevoked {
+    pub role: String,
+    pub account: Key,
+    pub sender: Key,
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct ParameterProposed {
+    pub name: String,
+    pub value: U256,
+    pub eta: U256,
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct ParameterUpdated {
+    pub name: String,
+    pub old_value: U256,
+    pub new_value: U256,
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct ParameterCancelled {
+    pub name: String,
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct Paused {
+    pub operation: String,
+    pub account: Key,
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct Unpaused {
+    pub operation: String,
+    pub account: Key,
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct Migrated {
+    pub contract_hash: Key,
+    pub from_version: u32,
+    pub to_version: u32,
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct ReferralLinkCreated {
+    pub referrer: Key,
+    pub referral_id: Vec<u32>,
+    pub staker: Key,
+    pub stake_id: Vec<u32>,
+    pub referrer_shares: U256,
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct CriticalMassActivated {
+    pub referrer: Key,
+    pub total_amount: U256,
+    pub activation_day: U256,
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct CriticalMassReset {
+    pub referrer: Key,
+    pub total_amount: U256,
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct DailySnapshot {
+    pub day: U256,
+    pub total_shares: U256,
+    pub inflation_amount: U256,
+    pub scheduled_to_end: U256,
+    pub referral_total_shares: U256,
+    pub referral_inflation_amount: U256,
+    pub referral_scheduled_to_end: U256,
+    pub liquidity_total_shares: U256,
+    pub liquidity_inflation_amount: U256,
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct LiquidityRatesAdjusted {
+    pub liquidity_rate: u32,
+    pub inflation_rate: u32,
+    pub is_liquidity_guard_active: bool,
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct ReferralCodeClaimed {
+    pub code: String,
+    pub owner: Key,
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct ReferralCodeTransferred {
+    pub code: String,
+    pub from: Key,
+    pub to: Key,
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct ReferralCodeReleased {
+    pub code: String,
+    pub owner: Key,
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct TierLinkCreated {
+    pub upline: Key,
+    pub tier_link_id: Vec<u32>,
+    pub staker: Key,
+    pub stake_id: Vec<u32>,
+    pub tier: u8,
+    pub tier_shares: U256,
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct TierReferralCollected {
+    pub staker: Key,
+    pub stake_id: Vec<u32>,
+    pub upline: Key,
+    pub tier_link_id: Vec<u32>,
+    pub reward_amount: U256,
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct ReferralInterestCollectedAll {
+    pub referrer: Key,
+    pub from_cursor: U256,
+    pub next_cursor: U256,
+    pub links_collected: U256,
+    pub reward_amount: U256,
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct ReferrerListUpdated {
+    pub referrer: Key,
+    pub list: String,
+    pub listed: bool,
+    pub sender: Key,
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct ReferrerIgnored {
+    pub staker: Key,
+    pub referrer: Key,
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct InflationAssigned {
+    pub sender: Key,
 }
 
-impl Events {
-    pub fn type_name(&self) -> String {
+fn schemas() -> Schemas {
+    Schemas::new()
+        .with::<StakeStart>()
+        .with::<StakeEnd>()
+        .with::<InterestScraped>()
+        .with::<ReferralCollected>()
+        .with::<NewGlobals>()
+        .with::<NewSharePrice>()
+        .with::<UniswapReserves>()
+        .with::<LiquidityGuardStatus>()
+        .with::<RoleGranted>()
+        .with::<RoleRevoked>()
+        .with::<ParameterProposed>()
+        .with::<ParameterUpdated>()
+        .with::<ParameterCancelled>()
+        .with::<Paused>()
+        .with::<Unpaused>()
+        .with::<Migrated>()
+        .with::<ReferralLinkCreated>()
+        .with::<CriticalMassActivated>()
+        .with::<CriticalMassReset>()
+        .with::<DailySnapshot>()
+        .with::<LiquidityRatesAdjusted>()
+        .with::<ReferralCodeClaimed>()
+        .with::<ReferralCodeTransferred>()
+        .with::<ReferralCodeReleased>()
+        .with::<TierLinkCreated>()
+        .with::<TierReferralCollected>()
+        .with::<ReferralInterestCollectedAll>()
+        .with::<ReferrerListUpdated>()
+        .with::<ReferrerIgnored>()
+        .with::<InflationAssigned>()
+}
+
+/// @notice registers the schema of every event, must run once in the contract context
+pub fn init_events() {
+    casper_event_standard::init(schemas());
+}
+
+/// @notice replaces the registered schemas after events were added
+pub fn update_event_schemas() {
+    set_key(EVENTS_SCHEMA, schemas());
+}
+
+/// One variant per event struct, so the conversion from `Events` stays free of host calls
+#[derive(Debug, PartialEq, Eq)]
+pub enum CesEvent {
+    StakeStart(StakeStart),
+    StakeEnd(StakeEnd),
+    InterestScraped(InterestScraped),
+    ReferralCollected(ReferralCollected),
+    NewGlobals(NewGlobals),
+    NewSharePrice(NewSharePrice),
+    UniswapReserves(UniswapReserves),
+    LiquidityGuardStatus(LiquidityGuardStatus),
+    RoleGranted(RoleGranted),
+    RoleRevoked(RoleRevoked),
+    ParameterProposed(ParameterProposed),
+    ParameterUpdated(ParameterUpdated),
+    ParameterCancelled(ParameterCancelled),
+    Paused(Paused),
+    Unpaused(Unpaused),
+    Migrated(Migrated),
+    ReferralLinkCreated(ReferralLinkCreated),
+    CriticalMassActivated(CriticalMassActivated),
+    CriticalMassReset(CriticalMassReset),
+    DailySnapshot(DailySnapshot),
+    LiquidityRatesAdjusted(LiquidityRatesAdjusted),
+    ReferralCodeClaimed(ReferralCodeClaimed),
+    ReferralCodeTransferred(ReferralCodeTransferred),
+    ReferralCodeReleased(ReferralCodeReleased),
+    TierLinkCreated(TierLinkCreated),
+    TierReferralCollected(TierReferralCollected),
+    ReferralInterestCollectedAll(ReferralInterestCollectedAll),
+    ReferrerListUpdated(ReferrerListUpdated),
+    ReferrerIgnored(ReferrerIgnored),
+    InflationAssigned(InflationAssigned),
+}
+
+impl CesEvent {
+    /// @notice bytes of the event struct as stored in the `__events` dictionary
+    pub fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
         match self {
-            Events::StakeStart {
-                stake_id: _,
-                staker_address: _,
-                referral_address: _,
-                staked_amount: _,
-                stakes_shares: _,
-                referral_shares: _,
-                start_day: _,
-                lock_days: _,
-                dai_equivalent: _,
-            } => "stake_start",
-            Events::StakeEnd {
-                stake_id: _,
-                staker_address: _,
-                referral_address: _,
-                staked_amount: _,
-                stakes_shares: _,
-                referral_shares: _,
-                reward_amount: _,
-                close_day: _,
-                penalty_amount: _,
-            } => "stake_end",
-            Events::InterestScraped {
-                stake_id: _,
-                staker_address: _,
-                scrape_amount: _,
-                scrape_day: _,
-                stakers_penalty: _,
-                referrer_penalty: _,
-                current_stakeable_day: _,
-            } => "interest_scraped",
-            Events::ReferralCollected {
-                staker: _,
-                stake_id: _,
-                referrer: _,
-                referrer_id: _,
-                reward_amount: _,
-            } => "referral_collected",
-            Events::NewGlobals {
-                total_shares: _,
-                total_staked: _,
-                share_rate: _,
-                referral_shares: _,
-                current_stakeable_day: _,
-            } => "new_globals",
-            Events::NewSharePrice {
-                new_share_price: _,
-                old_share_price: _,
-                current_stakeable_day: _,
-            } => "new_share_price",
-            Events::UniswapReserves {
-                reserve_a: _,
-                reserve_b: _,
-                block_timestamp_last: _,
-            } => "uniswap_reserves",
-            Events::LiquidityGuardStatus { is_active: _ } => "liquidity_guard_status",
+            CesEvent::StakeStart(event) => event.to_bytes(),
+            CesEvent::StakeEnd(event) => event.to_bytes(),
+            CesEvent::InterestScraped(event) => event.to_bytes(),
+            CesEvent::ReferralCollected(event) => event.to_bytes(),
+            CesEvent::NewGlobals(event) => event.to_bytes(),
+            CesEvent::NewSharePrice(event) => event.to_bytes(),
+            CesEvent::UniswapReserves(event) => event.to_bytes(),
+            CesEvent::LiquidityGuardStatus(event) => event.to_bytes(),
+            CesEvent::RoleGranted(event) => event.to_bytes(),
+            CesEvent::RoleRevoked(event) => event.to_bytes(),
+            CesEvent::ParameterProposed(event) => event.to_bytes(),
+            CesEvent::ParameterUpdated(event) => event.to_bytes(),
+            CesEvent::ParameterCancelled(event) => event.to_bytes(),
+            CesEvent::Paused(event) => event.to_bytes(),
+            CesEvent::Unpaused(event) => event.to_bytes(),
+            CesEvent::Migrated(event) => event.to_bytes(),
+            CesEvent::ReferralLinkCreated(event) => event.to_bytes(),
+            CesEvent::CriticalMassActivated(event) => event.to_bytes(),
+            CesEvent::CriticalMassReset(event) => event.to_bytes(),
+            CesEvent::DailySnapshot(event) => event.to_bytes(),
+            CesEvent::LiquidityRatesAdjusted(event) => event.to_bytes(),
+            CesEvent::ReferralCodeClaimed(event) => event.to_bytes(),
+            CesEvent::ReferralCodeTransferred(event) => event.to_bytes(),
+            CesEvent::ReferralCodeReleased(event) => event.to_bytes(),
+            CesEvent::TierLinkCreated(event) => event.to_bytes(),
+            CesEvent::TierReferralCollected(event) => event.to_bytes(),
+            CesEvent::ReferralInterestCollectedAll(event) => event.to_bytes(),
+            CesEvent::ReferrerListUpdated(event) => event.to_bytes(),
+            CesEvent::ReferrerIgnored(event) => event.to_bytes(),
+            CesEvent::InflationAssigned(event) => event.to_bytes(),
+        }
+    }
+
+    pub fn emit(self) {
+        match self {
+            CesEvent::StakeStart(event) => casper_event_standard::emit(event),
+            CesEvent::StakeEnd(event) => casper_event_standard::emit(event),
+            CesEvent::InterestScraped(event) => casper_event_standard::emit(event),
+            CesEvent::ReferralCollected(event) => casper_event_standard::emit(event),
+            CesEvent::NewGlobals(event) => casper_event_standard::emit(event),
+            CesEvent::NewSharePrice(event) => casper_event_standard::emit(event),
+            CesEvent::UniswapReserves(event) => casper_event_standard::emit(event),
+            CesEvent::LiquidityGuardStatus(event) => casper_event_standard::emit(event),
+            CesEvent::RoleGranted(event) => casper_event_standard::emit(event),
+            CesEvent::RoleRevoked(event) => casper_event_standard::emit(event),
+            CesEvent::ParameterProposed(event) => casper_event_standard::emit(event),
+            CesEvent::ParameterUpdated(event) => casper_event_standard::emit(event),
+            CesEvent::ParameterCancelled(event) => casper_event_standard::emit(event),
+            CesEvent::Paused(event) => casper_event_standard::emit(event),
+            CesEvent::Unpaused(event) => casper_event_standard::emit(event),
+            CesEvent::Migrated(event) => casper_event_standard::emit(event),
+            CesEvent::ReferralLinkCreated(event) => casper_event_standard::emit(event),
+            CesEvent::CriticalMassActivated(event) => casper_event_standard::emit(event),
+            CesEvent::CriticalMassReset(event) => casper_event_standard::emit(event),
+            CesEvent::DailySnapshot(event) => casper_event_standard::emit(event),
+            CesEvent::LiquidityRatesAdjusted(event) => casper_event_standard::emit(event),
+            CesEvent::ReferralCodeClaimed(event) => casper_event_standard::emit(event),
+            CesEvent::ReferralCodeTransferred(event) => casper_event_standard::emit(event),
+            CesEvent::ReferralCodeReleased(event) => casper_event_standard::emit(event),
+            CesEvent::TierLinkCreated(event) => casper_event_standard::emit(event),
+            CesEvent::TierReferralCollected(event) => casper_event_standard::emit(event),
+            CesEvent::ReferralInterestCollectedAll(event) => casper_event_standard::emit(event),
+            CesEvent::ReferrerListUpdated(event) => casper_event_standard::emit(event),
+            CesEvent::ReferrerIgnored(event) => casper_event_standard::emit(event),
+            CesEvent::InflationAssigned(event) => casper_event_standard::emit(event),
         }
-        .to_string()
     }
 }
 
-pub fn emit(stakeable_event: &Events) {
-    let mut events = Vec::new();
-    let formatted_package_hash = package_hash().to_formatted_string();
-    let package_hash_arr: Vec<&str> = formatted_package_hash.split('-').collect();
-    let package_hash: String = package_hash_arr[1].to_string();
+/// @notice the Casper Event Standard struct `emit` writes for `stakeable_event`
+pub fn ces_event(stakeable_event: &Events) -> CesEvent {
     match stakeable_event {
         Events::StakeStart {
             stake_id,
@@ -150,21 +585,17 @@ pub fn emit(stakeable_event: &Events) {
             start_day,
             lock_days,
             dai_equivalent,
-        } => {
-            let mut event = BTreeMap::new();
-            event.insert("contract_package_hash", package_hash);
-            event.insert("event_type", stakeable_event.type_name());
-            event.insert("stake_id", format!("{:?}", stake_id));
-            event.insert("staker_address", staker_address.to_string());
-            event.insert("referral_address", referral_address.to_string());
-            event.insert("staked_amount", staked_amount.to_string());
-            event.insert("stakes_shares", stakes_shares.to_string());
-            event.insert("referral_shares", referral_shares.to_string());
-            event.insert("start_day", start_day.to_string());
-            event.insert("lock_days", lock_days.to_string());
-            event.insert("dai_equivalent", dai_equivalent.to_string());
-            events.push(event)
-        }
+        } => CesEvent::StakeStart(StakeStart {
+            stake_id: stake_id.clone(),
+            staker_address: *staker_address,
+            referral_address: *referral_address,
+            staked_amount: *staked_amount,
+            stakes_shares: *stakes_shares,
+            referral_shares: *referral_shares,
+            start_day: *start_day,
+            lock_days: *lock_days,
+            dai_equivalent: *dai_equivalent,
+        }),
         Events::StakeEnd {
             stake_id,
             staker_address,
@@ -175,21 +606,17 @@ pub fn emit(stakeable_event: &Events) {
             reward_amount,
             close_day,
             penalty_amount,
-        } => {
-            let mut event = BTreeMap::new();
-            event.insert("contract_package_hash", package_hash);
-            event.insert("event_type", stakeable_event.type_name());
-            event.insert("stake_id", format!("{:?}", stake_id));
-            event.insert("staker_address", staker_address.to_string());
-            event.insert("referral_address", referral_address.to_string());
-            event.insert("staked_amount", staked_amount.to_string());
-            event.insert("stakes_shares", stakes_shares.to_string());
-            event.insert("referral_shares", referral_shares.to_string());
-            event.insert("reward_amount", reward_amount.to_string());
-            event.insert("close_day", close_day.to_string());
-            event.insert("penalty_amount", penalty_amount.to_string());
-            events.push(event)
-        }
+        } => CesEvent::StakeEnd(StakeEnd {
+            stake_id: stake_id.clone(),
+            staker_address: *staker_address,
+            referral_address: *referral_address,
+            staked_amount: *staked_amount,
+            stakes_shares: *stakes_shares,
+            referral_shares: *referral_shares,
+            reward_amount: *reward_amount,
+            close_day: *close_day,
+            penalty_amount: *penalty_amount,
+        }),
         Events::InterestScraped {
             stake_id,
             staker_address,
@@ -198,88 +625,260 @@ pub fn emit(stakeable_event: &Events) {
             stakers_penalty,
             referrer_penalty,
             current_stakeable_day,
-        } => {
-            let mut event = BTreeMap::new();
-            event.insert("contract_package_hash", package_hash);
-            event.insert("event_type", stakeable_event.type_name());
-            event.insert("stake_id", format!("{:?}", stake_id));
-            event.insert("staker_address", staker_address.to_string());
-            event.insert("scrape_amount", scrape_amount.to_string());
-            event.insert("scrape_day", scrape_day.to_string());
-            event.insert("stakers_penalty", stakers_penalty.to_string());
-            event.insert("referrer_penalty", referrer_penalty.to_string());
-            event.insert("current_stakeable_day", current_stakeable_day.to_string());
-            events.push(event)
-        }
+        } => CesEvent::InterestScraped(InterestScraped {
+            stake_id: stake_id.clone(),
+            staker_address: *staker_address,
+            scrape_amount: *scrape_amount,
+            scrape_day: *scrape_day,
+            stakers_penalty: *stakers_penalty,
+            referrer_penalty: *referrer_penalty,
+            current_stakeable_day: *current_stakeable_day,
+        }),
         Events::ReferralCollected {
             staker,
             stake_id,
             referrer,
             referrer_id,
             reward_amount,
-        } => {
-            let mut event = BTreeMap::new();
-            event.insert("contract_package_hash", package_hash);
-            event.insert("event_type", stakeable_event.type_name());
-            event.insert("staker", staker.to_string());
-            event.insert("stake_id", format!("{:?}", stake_id));
-            event.insert("referrer", referrer.to_string());
-            event.insert("referrer_id", format!("{:?}", referrer_id));
-            event.insert("reward_amount", reward_amount.to_string());
-            events.push(event);
-        }
+        } => CesEvent::ReferralCollected(ReferralCollected {
+            staker: *staker,
+            stake_id: stake_id.clone(),
+            referrer: *referrer,
+            referrer_id: referrer_id.clone(),
+            reward_amount: *reward_amount,
+        }),
         Events::NewGlobals {
             total_shares,
             total_staked,
             share_rate,
             referral_shares,
             current_stakeable_day,
-        } => {
-            let mut event = BTreeMap::new();
-            event.insert("contract_package_hash", package_hash);
-            event.insert("event_type", stakeable_event.type_name());
-            event.insert("total_shares", total_shares.to_string());
-            event.insert("total_staked", total_staked.to_string());
-            event.insert("share_rate", share_rate.to_string());
-            event.insert("referrer_shares", referral_shares.to_string());
-            event.insert("current_stakeable_day", current_stakeable_day.to_string());
-            events.push(event);
-        }
+        } => CesEvent::NewGlobals(NewGlobals {
+            total_shares: *total_shares,
+            total_staked: *total_staked,
+            share_rate: *share_rate,
+            referral_shares: *referral_shares,
+            current_stakeable_day: *current_stakeable_day,
+        }),
         Events::NewSharePrice {
             new_share_price,
             old_share_price,
             current_stakeable_day,
-        } => {
-            let mut event = BTreeMap::new();
-            event.insert("contract_package_hash", package_hash);
-            event.insert("event_type", stakeable_event.type_name());
-            event.insert("new_share_price", new_share_price.to_string());
-            event.insert("old_share_price", old_share_price.to_string());
-            event.insert("current_stakeable_day", current_stakeable_day.to_string());
-            events.push(event);
-        }
+        } => CesEvent::NewSharePrice(NewSharePrice {
+            new_share_price: *new_share_price,
+            old_share_price: *old_share_price,
+            current_stakeable_day: *current_stakeable_day,
+        }),
         Events::UniswapReserves {
             reserve_a,
             reserve_b,
             block_timestamp_last,
-        } => {
-            let mut event = BTreeMap::new();
-            event.insert("contract_package_hash", package_hash);
-            event.insert("event_type", stakeable_event.type_name());
-            event.insert("reserve_a", reserve_a.to_string());
-            event.insert("reserve_b", reserve_b.to_string());
-            event.insert("block_timestamp_last", block_timestamp_last.to_string());
-            events.push(event);
-        }
+        } => CesEvent::UniswapReserves(UniswapReserves {
+            reserve_a: *reserve_a,
+            reserve_b: *reserve_b,
+            block_timestamp_last: *block_timestamp_last,
+        }),
         Events::LiquidityGuardStatus { is_active } => {
-            let mut event = BTreeMap::new();
-            event.insert("contract_package_hash", package_hash);
-            event.insert("event_type", stakeable_event.type_name());
-            event.insert("is_active", is_active.to_string());
-            events.push(event);
+            CesEvent::LiquidityGuardStatus(LiquidityGuardStatus {
+                is_active: *is_active,
+            })
+        }
+        Events::RoleGranted {
+            role,
+            account,
+            sender,
+        } => CesEvent::RoleGranted(RoleGranted {
+            role: role.clone(),
+            account: *account,
+            sender: *sender,
+        }),
+        Events::RoleRevoked {
+            role,
+            account,
+            sender,
+        } => CesEvent::RoleRevoked(RoleRevoked {
+            role: role.clone(),
+            account: *account,
+            sender: *sender,
+        }),
+        Events::ParameterProposed { name, value, eta } => {
+            CesEvent::ParameterProposed(ParameterProposed {
+                name: name.clone(),
+                value: *value,
+                eta: *eta,
+            })
+        }
+        Events::ParameterUpdated {
+            name,
+            old_value,
+            new_value,
+        } => CesEvent::ParameterUpdated(ParameterUpdated {
+            name: name.clone(),
+            old_value: *old_value,
+            new_value: *new_value,
+        }),
+        Events::ParameterCancelled { name } => {
+            CesEvent::ParameterCancelled(ParameterCancelled { name: name.clone() })
+        }
+        Events::Paused { operation, account } => CesEvent::Paused(Paused {
+            operation: operation.clone(),
+            account: *account,
+        }),
+        Events::Unpaused { operation, account } => CesEvent::Unpaused(Unpaused {
+            operation: operation.clone(),
+            account: *account,
+        }),
+        Events::Migrated {
+            contract_hash,
+            from_version,
+            to_version,
+        } => CesEvent::Migrated(Migrated {
+            contract_hash: *contract_hash,
+            from_version: *from_version,
+            to_version: *to_version,
+        }),
+        Events::ReferralLinkCreated {
+            referrer,
+            referral_id,
+            staker,
+            stake_id,
+            referrer_shares,
+        } => CesEvent::ReferralLinkCreated(ReferralLinkCreated {
+            referrer: *referrer,
+            referral_id: referral_id.clone(),
+            staker: *staker,
+            stake_id: stake_id.clone(),
+            referrer_shares: *referrer_shares,
+        }),
+        Events::CriticalMassActivated {
+            referrer,
+            total_amount,
+            activation_day,
+        } => CesEvent::CriticalMassActivated(CriticalMassActivated {
+            referrer: *referrer,
+            total_amount: *total_amount,
+            activation_day: *activation_day,
+        }),
+        Events::CriticalMassReset {
+            referrer,
+            total_amount,
+        } => CesEvent::CriticalMassReset(CriticalMassReset {
+            referrer: *referrer,
+            total_amount: *total_amount,
+        }),
+        Events::DailySnapshot {
+            day,
+            total_shares,
+            inflation_amount,
+            scheduled_to_end,
+            referral_total_shares,
+            referral_inflation_amount,
+            referral_scheduled_to_end,
+            liquidity_total_shares,
+            liquidity_inflation_amount,
+        } => CesEvent::DailySnapshot(DailySnapshot {
+            day: *day,
+            total_shares: *total_shares,
+            inflation_amount: *inflation_amount,
+            scheduled_to_end: *scheduled_to_end,
+            referral_total_shares: *referral_total_shares,
+            referral_inflation_amount: *referral_inflation_amount,
+            referral_scheduled_to_end: *referral_scheduled_to_end,
+            liquidity_total_shares: *liquidity_total_shares,
+            liquidity_inflation_amount: *liquidity_inflation_amount,
+        }),
+        Events::LiquidityRatesAdjusted {
+            liquidity_rate,
+            inflation_rate,
+            is_liquidity_guard_active,
+        } => CesEvent::LiquidityRatesAdjusted(LiquidityRatesAdjusted {
+            liquidity_rate: *liquidity_rate,
+            inflation_rate: *inflation_rate,
+            is_liquidity_guard_active: *is_liquidity_guard_active,
+        }),
+        Events::ReferralCodeClaimed { code, owner } => {
+            CesEvent::ReferralCodeClaimed(ReferralCodeClaimed {
+                code: code.clone(),
+                owner: *owner,
+            })
+        }
+        Events::ReferralCodeTransferred { code, from, to } => {
+            CesEvent::ReferralCodeTransferred(ReferralCodeTransferred {
+                code: code.clone(),
+                from: *from,
+                to: *to,
+            })
+        }
+        Events::ReferralCodeReleased { code, owner } => {
+            CesEvent::ReferralCodeReleased(ReferralCodeReleased {
+                code: code.clone(),
+                owner: *owner,
+            })
+        }
+        Events::TierLinkCreated {
+            upline,
+            tier_link_id,
+            staker,
+            stake_id,
+            tier,
+            tier_shares,
+        } => CesEvent::TierLinkCreated(TierLinkCreated {
+            upline: *upline,
+            tier_link_id: tier_link_id.clone(),
+            staker: *staker,
+            stake_id: stake_id.clone(),
+            tier: *tier,
+            tier_shares: *tier_shares,
+        }),
+        Events::TierReferralCollected {
+            staker,
+            stake_id,
+            upline,
+            tier_link_id,
+            reward_amount,
+        } => CesEvent::TierReferralCollected(TierReferralCollected {
+            staker: *staker,
+            stake_id: stake_id.clone(),
+            upline: *upline,
+            tier_link_id: tier_link_id.clone(),
+            reward_amount: *reward_amount,
+        }),
+        Events::ReferralInterestCollectedAll {
+            referrer,
+            from_cursor,
+            next_cursor,
+            links_collected,
+            reward_amount,
+        } => CesEvent::ReferralInterestCollectedAll(ReferralInterestCollectedAll {
+            referrer: *referrer,
+            from_cursor: *from_cursor,
+            next_cursor: *next_cursor,
+            links_collected: *links_collected,
+            reward_amount: *reward_amount,
+        }),
+        Events::ReferrerListUpdated {
+            referrer,
+            list,
+            listed,
+            sender,
+        } => CesEvent::ReferrerListUpdated(ReferrerListUpdated {
+            referrer: *referrer,
+            list: list.clone(),
+            listed: *listed,
+            sender: *sender,
+        }),
+        Events::ReferrerIgnored { staker, referrer } => {
+            CesEvent::ReferrerIgnored(ReferrerIgnored {
+                staker: *staker,
+                referrer: *referrer,
+            })
+        }
+        Events::InflationAssigned { sender } => {
+            CesEvent::InflationAssigned(InflationAssigned { sender: *sender })
         }
-    };
-    for event in events {
-        let _: URef = storage::new_uref(event);
     }
 }
+
+pub fn emit(stakeable_event: &Events) {
+    ces_event(stakeable_event).emit();
+}
@@ -9,6 +9,8 @@ pub const SESSION_WASM_LIQUIDITY_TRANSFORMER: &str = "session-code-lt.wasm";
 pub const SESSION_WASM_STAKEABLE: &str = "session-code-stakeable.wasm";
 pub const SESSION_WASM_TRANSFER_HELPER: &str = "session-code-transfer-helper.wasm";
 pub const SESSION_WASM_LIQUIDITY_GUARD: &str = "session-code-liquidity-guard.wasm";
+pub const SESSION_WASM_MOCKS: &str = "session-code-mocks.wasm";
+pub const SET_LIQUIDITY_TRANSFOMER: &str = "set_liquidity_transfomer";
 pub const GET_INFLATION: &str = "get_inflation";
 pub const GET_STABLE_USD_EQUIVALENT: &str = "get_stable_usd_equivalent";
 pub const FORWARD_FUNDS: &str = "forward_funds";
@@ -18,14 +20,44 @@ pub const CREATE_STAKE_WITH_CSPR: &str = "create_stake_with_cspr";
 pub const TRANSFER: &str = "transfer";
 pub const TRANSFER_FROM: &str = "transfer_from";
 pub const CHECK_REFERRALS_BY_ID: &str = "check_referrals_by_id";
+pub const REFERRAL_VIEW_V1: &str = "referral_view_v1";
 pub const CREATE_STAKE: &str = "create_stake";
 pub const END_STAKE: &str = "end_stake";
 pub const SCRAPE_INTEREST: &str = "scrape_interest";
 pub const CHECK_MATURE_STAKE: &str = "check_mature_stake";
 pub const CHECK_STAKE_BY_ID: &str = "check_stake_by_id";
+pub const STAKE_VIEW_V1: &str = "stake_view_v1";
 pub const CREATE_LIQUIDITY_STAKE: &str = "create_liquidity_stake";
 pub const END_LIQUIDITY_STAKE: &str = "end_liquidity_stake";
+pub const WITHDRAW_LIQUIDITY_PARTIAL: &str = "withdraw_liquidity_partial";
 pub const CHECK_LIQUIDITY_STAKE_BY_ID: &str = "check_liquidity_stake_by_id";
+pub const LIQUIDITY_STAKE_VIEW_V1: &str = "liquidity_stake_view_v1";
+pub const HAS_ROLE: &str = "has_role";
+pub const GET_PARAMETER: &str = "get_parameter";
+pub const IS_PAUSED: &str = "is_paused";
+pub const CREATE_STAKE_BY_CODE: &str = "create_stake_by_code";
+pub const CREATE_STAKE_WITH_CSPR_BY_CODE: &str = "create_stake_with_cspr_by_code";
+pub const RESOLVE_REFERRAL_CODE: &str = "resolve_referral_code";
+pub const CHECK_TIER_LINK_BY_ID: &str = "check_tier_link_by_id";
+pub const COLLECT_ALL_REFERRAL_INTEREST: &str = "collect_all_referral_interest";
+pub const REFERRER_SUMMARY: &str = "referrer_summary";
+pub const IS_ELIGIBLE_REFERRER: &str = "is_eligible_referrer";
+pub const DECAYED_CRITICAL_MASS: &str = "decayed_critical_mass";
+pub const QUOTE_STAKE: &str = "quote_stake";
+pub const PORTFOLIO: &str = "portfolio";
+pub const SNAPSHOT_RANGE: &str = "snapshot_range";
+pub const RSNAPSHOT_RANGE: &str = "rsnapshot_range";
+pub const LSNAPSHOT_RANGE: &str = "lsnapshot_range";
+pub const ZAP_LIQUIDITY_STAKE_WITH_CSPR: &str = "zap_liquidity_stake_with_cspr";
+
+// access control
+pub const ROLES_DICT: &str = "roles_dict";
+
+// pausable
+pub const PAUSED_DICT: &str = "paused_dict";
+
+// casper event standard
+pub const EVENTS_SCHEMA: &str = "__events_schema";
 
 // global
 pub const GLOBALS: &str = "globals";
@@ -48,23 +80,65 @@ pub const REFERRAL_COUNT_DICT: &str = "referral_count_dict";
 pub const LIQUIDITY_STAKE_COUNT_DICT: &str = "liquidity_stake_count_dict";
 pub const CRITICAL_MASS_DICT: &str = "critical_mass_dict";
 pub const SCRAPES_DICT: &str = "scrapes_dict";
-pub const STAKES_DICT: &str = "stakes_dict";
+// stakes up to schema version 10 stay in the first dictionary in their old layout
+pub const LEGACY_STAKES_DICT: &str = "stakes_dict";
+pub const STAKES_DICT: &str = "stakes_v11_dict";
 pub const REFERRER_LINKS_DICT: &str = "referrer_links_dict";
 pub const LIQUIDITY_STAKES_DICT: &str = "liquidity_stakes_dict";
+pub const LIQUIDITY_SCRAPES_DICT: &str = "liquidity_scrapes_dict";
 pub const SCHEDULED_TO_END_DICT: &str = "scheduled_to_end_dict";
 pub const REFERRAL_SHARES_TO_END_DICT: &str = "referral_shares_to_end_dict";
 pub const TOTAL_PENALTIES_DICT: &str = "total_penalties_dict";
+pub const PARAMETERS_DICT: &str = "parameters_dict";
+pub const PENDING_PARAMETERS_DICT: &str = "pending_parameters_dict";
+pub const REFERRAL_CODES_DICT: &str = "referral_codes_dict";
+pub const REFERRER_CODES_DICT: &str = "referrer_codes_dict";
+pub const UPLINES_DICT: &str = "uplines_dict";
+pub const TIER_LINK_COUNT_DICT: &str = "tier_link_count_dict";
+pub const TIER_LINKS_DICT: &str = "tier_links_dict";
+pub const STAKE_TIER_SHARES_DICT: &str = "stake_tier_shares_dict";
+pub const STAKE_TIER_LINKS_DICT: &str = "stake_tier_links_dict";
+pub const TIER_SHARES_TO_END_DICT: &str = "tier_shares_to_end_dict";
+pub const TIER_SHARES: &str = "tier_shares";
+pub const REFERRAL_CURSORS_DICT: &str = "referral_cursors_dict";
+pub const REFERRER_SUMMARIES_DICT: &str = "referrer_summaries_dict";
+pub const REFERRER_SHARES_STARTING_DICT: &str = "referrer_shares_starting_dict";
+pub const REFERRER_SHARES_ENDING_DICT: &str = "referrer_shares_ending_dict";
+pub const SUMMARY_COUNTED_DICT: &str = "summary_counted_dict";
+pub const ALLOWED_REFERRERS_DICT: &str = "allowed_referrers_dict";
+pub const DENIED_REFERRERS_DICT: &str = "denied_referrers_dict";
+pub const ACCOUNT_STAKED_DICT: &str = "account_staked_dict";
+pub const FIRST_STAKE_DAY_DICT: &str = "first_stake_day_dict";
+pub const REFERRER_COOLDOWN_END_DICT: &str = "referrer_cooldown_end_dict";
+pub const DECAYING_CRITICAL_MASS_DICT: &str = "decaying_critical_mass_dict";
+pub const DECAYING_MASS_ENDING_DICT: &str = "decaying_mass_ending_dict";
+pub const DECAYING_MASS_RECORDED_DICT: &str = "decaying_mass_recorded_dict";
 
 // snapshot
 pub const SNAPSHOTS_DICT: &str = "snapshots_dict";
 pub const RSNAPSHOTS_DICT: &str = "rsnapshots_dict";
 pub const LSNAPSHOTS_DICT: &str = "lsnapshots_dict";
+pub const TSNAPSHOTS_DICT: &str = "tsnapshots_dict";
 
 // liquidity_transformer
 pub const LIQUIDITY_TRANSFORMER: &str = "liquidity_transformer";
 pub const LIQUIDITY_TRANSFORMER_PURSE: &str = "liquidity_transformer_purse";
 pub const TRANSFORMER_GATE_KEEPER: &str = "transformer_gate_keeper";
+pub const SCHEMA_VERSION: &str = "schema_version";
 
 // liquidity guard
 pub const INFLATION_LN: &str = "inflation_ln";
 pub const IS_READY: &str = "is_ready";
+
+// mocks
+pub const MOCK_BALANCES_DICT: &str = "balances";
+pub const MOCK_ALLOWANCES_DICT: &str = "allowances";
+pub const MOCK_TOTAL_SUPPLY: &str = "total_supply";
+pub const MOCK_PRICES_DICT: &str = "prices_dict";
+pub const MOCK_PAIRS_DICT: &str = "pairs_dict";
+pub const MOCK_INFLATIONS_DICT: &str = "inflations_dict";
+pub const MOCK_RESERVE0: &str = "reserve0";
+pub const MOCK_RESERVE1: &str = "reserve1";
+pub const MOCK_BLOCK_TIMESTAMP_LAST: &str = "block_timestamp_last";
+pub const MOCK_TOKEN0: &str = "token0";
+pub const MOCK_TOKEN1: &str = "token1";
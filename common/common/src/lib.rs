@@ -1,6 +1,9 @@
 #![no_std]
 
+pub mod access_control;
 pub mod errors;
 pub mod events;
 pub mod functions;
 pub mod keys;
+pub mod pausable;
+pub mod views;
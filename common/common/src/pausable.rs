@@ -0,0 +1,107 @@
+extern crate alloc;
+use crate::{
+    access_control::{ACCESSCONTROL, PAUSER_ROLE},
+    errors::Errors,
+    events::{emit, Events},
+    keys::PAUSED_DICT,
+};
+use alloc::{string::String, vec::Vec};
+use casper_contract::contract_api::runtime;
+use casperlabs_contract_utils::{ContractContext, ContractStorage, Dict};
+
+pub const STAKE_OPERATION: &str = "stake";
+pub const END_OPERATION: &str = "end";
+pub const SCRAPE_OPERATION: &str = "scrape";
+pub const REFERRAL_OPERATION: &str = "referral";
+pub const LIQUIDITY_OPERATION: &str = "liquidity";
+pub const CSPR_OPERATION: &str = "cspr";
+
+pub const OPERATIONS: [&str; 6] = [
+    STAKE_OPERATION,
+    END_OPERATION,
+    SCRAPE_OPERATION,
+    REFERRAL_OPERATION,
+    LIQUIDITY_OPERATION,
+    CSPR_OPERATION,
+];
+
+pub struct Paused {
+    dict: Dict,
+}
+impl Paused {
+    pub fn instance() -> Paused {
+        Paused {
+            dict: Dict::instance(PAUSED_DICT),
+        }
+    }
+    pub fn init() {
+        Dict::init(PAUSED_DICT)
+    }
+    pub fn get(&self, key: &str) -> bool {
+        self.dict.get(key).unwrap_or_default()
+    }
+    pub fn set(&self, key: &str, value: bool) {
+        self.dict.set(key, value);
+    }
+}
+
+pub trait PAUSABLE<Storage: ContractStorage>:
+    ContractContext<Storage> + ACCESSCONTROL<Storage>
+{
+    fn init(&self) {
+        Paused::init();
+    }
+
+    fn is_paused(&self, operation: String) -> bool {
+        Paused::instance().get(&operation)
+    }
+
+    fn when_not_paused(&self, operation: &str) {
+        if Paused::instance().get(operation) {
+            runtime::revert(Errors::OperationPaused);
+        }
+    }
+
+    fn when_paused(&self, operation: &str) {
+        if !Paused::instance().get(operation) {
+            runtime::revert(Errors::OperationNotPaused);
+        }
+    }
+
+    /// @notice stops the given operations, caller must be a pauser
+    fn pause(&self, operations: Vec<String>) {
+        self.only_role(PAUSER_ROLE);
+        for operation in operations {
+            self._set_paused(operation, true);
+        }
+    }
+
+    /// @notice resumes the given operations, caller must be a pauser
+    fn unpause(&self, operations: Vec<String>) {
+        self.only_role(PAUSER_ROLE);
+        for operation in operations {
+            self._set_paused(operation, false);
+        }
+    }
+
+    fn _set_paused(&self, operation: String, paused: bool) {
+        if !OPERATIONS.contains(&operation.as_str()) {
+            runtime::revert(Errors::InvalidOperation);
+        }
+        if Paused::instance().get(&operation) == paused {
+            return;
+        }
+        Paused::instance().set(&operation, paused);
+        if paused {
+            emit(&Events::Paused {
+                operation,
+                account: self.get_caller(),
+            });
+        } else {
+            emit(&Events::Unpaused {
+                operation,
+                account: self.get_caller(),
+            });
+        }
+    }
+}
@@ -0,0 +1,123 @@
+extern crate alloc;
+use alloc::vec::Vec;
+use casper_types::{Key, U256};
+use casper_types_derive::{CLTyped, FromBytes, ToBytes};
+
+// results of the versioned `*_view_v1` entry points, a layout change gets a new
+// entry point version so existing clients keep decoding the old one
+
+#[derive(Debug, Clone, CLTyped, ToBytes, FromBytes, PartialEq, Eq)]
+pub struct StakeView {
+    pub staker: Key,
+    pub stake_id: Vec<u32>,
+    pub start_day: u64,
+    pub lock_days: u64,
+    pub final_day: u64,
+    pub close_day: u64,
+    pub scrape_day: U256,
+    pub staked_amount: U256,
+    pub stakes_shares: U256,
+    pub referrer_shares: U256,
+    pub referrer: Key,
+    pub reward_amount: U256,
+    pub penalty_amount: U256,
+    pub is_active: bool,
+    pub is_mature: bool,
+}
+
+#[derive(Debug, Clone, CLTyped, ToBytes, FromBytes, PartialEq, Eq)]
+pub struct ReferralView {
+    pub referrer: Key,
+    pub referral_id: Vec<u32>,
+    pub staker: Key,
+    pub stake_id: Vec<u32>,
+    pub referrer_shares: U256,
+    pub referral_interest: U256,
+    pub is_active_referral: bool,
+    pub is_active_stake: bool,
+    pub is_mature_stake: bool,
+    pub is_ended_stake: bool,
+}
+
+/// Running referral totals of `referrer_summary`, links created before the summaries
+/// existed are not counted
+#[derive(Default, Debug, Clone, CLTyped, ToBytes, FromBytes, PartialEq, Eq)]
+pub struct ReferrerSummaryView {
+    pub total_collected: U256,
+    pub claimable: U256,
+    pub active_links: U256,
+    pub ended_links: U256,
+    pub critical_mass: U256,
+    pub activation_day: U256,
+    pub threshold_distance: U256,
+}
+
+#[derive(Debug, Clone, CLTyped, ToBytes, FromBytes, PartialEq, Eq)]
+pub struct LiquidityStakeView {
+    pub staker: Key,
+    pub liquidity_stake_id: Vec<u32>,
+    pub start_day: u64,
+    pub close_day: u64,
+    pub staked_amount: U256,
+    pub reward_amount: U256,
+    pub is_active: bool,
+}
+
+/// Projection of `quote_stake`, the reward range spans `sampled_days` recent snapshots
+#[derive(Default, Debug, Clone, CLTyped, ToBytes, FromBytes, PartialEq, Eq)]
+pub struct StakeQuote {
+    pub stakes_shares: U256,
+    pub referrer_shares: U256,
+    pub bonus_a: U256,
+    pub bonus_b: U256,
+    pub referral_bonus: U256,
+    pub total_bonus: U256,
+    pub share_price: U256,
+    pub min_reward: U256,
+    pub average_reward: U256,
+    pub max_reward: U256,
+    pub sampled_days: U256,
+}
+
+#[derive(Default, Debug, Clone, CLTyped, ToBytes, FromBytes, PartialEq, Eq)]
+pub struct PortfolioStake {
+    pub stake_id: Vec<u32>,
+    pub staked_amount: U256,
+    pub stakes_shares: U256,
+    pub start_day: u64,
+    pub final_day: u64,
+    pub close_day: u64,
+    pub is_active: bool,
+    pub is_mature: bool,
+    pub reward_amount: U256,
+    pub penalty_amount: U256,
+}
+
+#[derive(Default, Debug, Clone, CLTyped, ToBytes, FromBytes, PartialEq, Eq)]
+pub struct PortfolioLiquidityStake {
+    pub liquidity_stake_id: Vec<u32>,
+    pub staked_amount: U256,
+    pub reward_amount: U256,
+    pub start_day: u64,
+    pub close_day: u64,
+    pub is_active: bool,
+}
+
+/// One page of an account's holdings, `total_*` fields sum up the returned page only,
+/// stakes and liquidity stakes page with their own cursors
+#[derive(Default, Debug, Clone, CLTyped, ToBytes, FromBytes, PartialEq, Eq)]
+pub struct Portfolio {
+    pub balance: U256,
+    pub stakes: Vec<PortfolioStake>,
+    pub liquidity_stakes: Vec<PortfolioLiquidityStake>,
+    pub claimable_referral_interest: U256,
+    pub total_staked: U256,
+    pub total_rewards: U256,
+    pub total_penalties: U256,
+    pub total_liquidity_staked: U256,
+    pub total_liquidity_rewards: U256,
+    pub stake_count: U256,
+    pub liquidity_stake_count: U256,
+    pub next_stake_cursor: U256,
+    pub next_liquidity_cursor: U256,
+}
@@ -234,9 +234,41 @@ pub fn deploy_stakeable(
     amount: U512,
     time: u64,
 ) -> TestContract {
-    TestContract::new(
+    deploy_stakeable_wasm(
         env,
         "stakeable-token.wasm",
+        owner,
+        stable_usd,
+        scspr,
+        wcspr,
+        uniswap_router,
+        uniswap_factory,
+        uniswap_pair,
+        liquidity_guard,
+        amount,
+        time,
+    )
+}
+
+/// Same as `deploy_stakeable` from another build of the contract, e.g. a previous release
+#[allow(clippy::too_many_arguments)]
+pub fn deploy_stakeable_wasm(
+    env: &TestEnv,
+    wasm: &str,
+    owner: AccountHash,
+    stable_usd: &TestContract,
+    scspr: &TestContract,
+    wcspr: &TestContract,
+    uniswap_router: &TestContract,
+    uniswap_factory: &TestContract,
+    uniswap_pair: &TestContract,
+    liquidity_guard: &TestContract,
+    amount: U512,
+    time: u64,
+) -> TestContract {
+    TestContract::new(
+        env,
+        wasm,
         "stakeable-token",
         owner,
         runtime_args! {
@@ -270,3 +302,52 @@ pub fn deploy_transfer_helper(
         time,
     )
 }
+
+pub fn deploy_mock_token(
+    env: &TestEnv,
+    owner: AccountHash,
+    contract_name: &str,
+    time: u64,
+) -> TestContract {
+    TestContract::new(
+        env,
+        "mock-token.wasm",
+        contract_name,
+        owner,
+        runtime_args! {},
+        time,
+    )
+}
+
+pub fn deploy_mock_factory(env: &TestEnv, owner: AccountHash, time: u64) -> TestContract {
+    TestContract::new(
+        env,
+        "mock-factory.wasm",
+        "mock-factory",
+        owner,
+        runtime_args! {},
+        time,
+    )
+}
+
+pub fn deploy_mock_router(env: &TestEnv, owner: AccountHash, time: u64) -> TestContract {
+    TestContract::new(
+        env,
+        "mock-router.wasm",
+        "mock-router",
+        owner,
+        runtime_args! {},
+        time,
+    )
+}
+
+pub fn deploy_mock_liquidity_guard(env: &TestEnv, owner: AccountHash, time: u64) -> TestContract {
+    TestContract::new(
+        env,
+        "mock-liquidity-guard.wasm",
+        "mock-liquidity-guard",
+        owner,
+        runtime_args! {},
+        time,
+    )
+}
@@ -1,5 +1,7 @@
 pub mod deploys;
 pub mod helpers;
+pub mod mocks;
+pub mod scenario;
 
 pub use common::*;
 pub use global::*;
@@ -0,0 +1,128 @@
+use crate::{deploys::*, helpers::*};
+use casper_types::{account::AccountHash, runtime_args, Key, RuntimeArgs, U128, U256};
+use casperlabs_test_env::{TestContract, TestEnv};
+use common::keys::*;
+pub use mocks_crate::data::{DEFAULT_INFLATION, PRICE_PRECISION};
+
+/// Stand-ins for the DEX and the liquidity guard, every price, reserve and inflation
+/// the stakeable token reads from them is scripted through the helpers below
+pub struct Mocks {
+    pub owner: AccountHash,
+    pub stable_usd: TestContract,
+    pub scspr: TestContract,
+    pub wcspr: TestContract,
+    pub router: TestContract,
+    pub factory: TestContract,
+    pub pair: TestContract,
+    pub liquidity_guard: TestContract,
+}
+
+impl Mocks {
+    pub fn deploy(env: &TestEnv, owner: AccountHash, time: u64) -> Mocks {
+        Mocks {
+            owner,
+            stable_usd: deploy_mock_token(env, owner, "mock-stable-usd", time),
+            scspr: deploy_mock_token(env, owner, "mock-scspr", time),
+            wcspr: deploy_mock_token(env, owner, "mock-wcspr", time),
+            router: deploy_mock_router(env, owner, time),
+            factory: deploy_mock_factory(env, owner, time),
+            pair: deploy_mock_token(env, owner, "mock-pair", time),
+            liquidity_guard: deploy_mock_liquidity_guard(env, owner, time),
+        }
+    }
+
+    /// Deploys the stakeable token against the mocks and makes `owner` its transformer,
+    /// so tests can `mint_supply` without running the liquidity transformer
+    pub fn deploy_stakeable(&self, env: &TestEnv, time: u64) -> TestContract {
+        self.deploy_stakeable_wasm(env, "stakeable-token.wasm", time)
+    }
+
+    /// Same as `deploy_stakeable` from another build of the contract, e.g. a previous release
+    pub fn deploy_stakeable_wasm(&self, env: &TestEnv, wasm: &str, time: u64) -> TestContract {
+        let stakeable = deploy_stakeable_wasm(
+            env,
+            wasm,
+            self.owner,
+            &self.stable_usd,
+            &self.scspr,
+            &self.wcspr,
+            &self.router,
+            &self.factory,
+            &self.pair,
+            &self.liquidity_guard,
+            STAKEABLE_AMOUNT,
+            time,
+        );
+        self.pair.call_contract(
+            self.owner,
+            "set_tokens",
+            runtime_args! {
+                "token0" => Key::Hash(stakeable.package_hash()),
+                "token1" => Key::Hash(self.scspr.package_hash())
+            },
+            time,
+        );
+        call(
+            env,
+            self.owner,
+            SESSION_WASM_MOCKS,
+            runtime_args! {
+                ENTRYPOINT => SET_LIQUIDITY_TRANSFOMER,
+                PACKAGE_HASH => Key::Hash(stakeable.package_hash()),
+                "immutable_transformer" => Key::Account(self.owner)
+            },
+            time,
+        );
+        stakeable
+    }
+
+    pub fn set_price(&self, token_in: Key, token_out: Key, price: U256, time: u64) {
+        self.router.call_contract(
+            self.owner,
+            "set_price",
+            runtime_args! {
+                "token_in" => token_in,
+                "token_out" => token_out,
+                "price" => price
+            },
+            time,
+        );
+    }
+
+    /// @notice reserve0 belongs to the stakeable token, reserve1 to scspr
+    pub fn set_reserves(&self, reserve0: U128, reserve1: U128, time: u64) {
+        self.pair.call_contract(
+            self.owner,
+            "set_reserves",
+            runtime_args! {
+                "reserve0" => reserve0,
+                "reserve1" => reserve1
+            },
+            time,
+        );
+    }
+
+    pub fn set_inflation(&self, amount: u32, inflation: U256, time: u64) {
+        self.liquidity_guard.call_contract(
+            self.owner,
+            "set_inflation",
+            runtime_args! {
+                "amount" => amount,
+                "inflation" => inflation
+            },
+            time,
+        );
+    }
+
+    pub fn mint(&self, token: &TestContract, to: Key, amount: U256, time: u64) {
+        token.call_contract(
+            self.owner,
+            "mint",
+            runtime_args! {
+                "to" => to,
+                "amount" => amount
+            },
+            time,
+        );
+    }
+}
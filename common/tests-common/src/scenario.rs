@@ -0,0 +1,293 @@
+use crate::{helpers::*, keys::*};
+use casper_types::{
+    account::AccountHash, bytesrepr::FromBytes, runtime_args, CLTyped, Key, RuntimeArgs, U256,
+};
+use casperlabs_test_env::{TestContract, TestEnv};
+use global::data::Globals;
+use std::collections::BTreeMap;
+
+/// Scripted walk through the life of stakes against one stakeable deployment.
+///
+/// Steps read as the story of the test, accounts and stakes are named instead of
+/// passed around and time only moves through `advance_days`:
+///
+/// ```ignore
+/// Scenario::new(&env, &wise, owner, time)
+///     .account("alice", ONEHUNDRED_CSPR)
+///     .stake("alice", "first", ONEHUNDRED_CSPR, 20)
+///     .advance_days(21)
+///     .end_stake("first")
+///     .expect_globals(|globals| assert_eq!(globals.total_staked, ZERO));
+/// ```
+///
+/// Every step is added to a trace that is printed as it runs and attached to any
+/// failing expectation, run with `--nocapture` to follow a passing scenario.
+pub struct Scenario<'a> {
+    env: &'a TestEnv,
+    wise: &'a TestContract,
+    time: u64,
+    days: u64,
+    accounts: BTreeMap<String, AccountHash>,
+    stakes: BTreeMap<String, (String, Vec<u32>)>,
+    last_reward: U256,
+    trace: Vec<String>,
+}
+
+impl<'a> Scenario<'a> {
+    /// `owner` holds the supply the other accounts are funded from and is named "owner"
+    pub fn new(env: &'a TestEnv, wise: &'a TestContract, owner: AccountHash, time: u64) -> Self {
+        let mut accounts = BTreeMap::new();
+        accounts.insert("owner".to_string(), owner);
+        Scenario {
+            env,
+            wise,
+            time,
+            days: 0,
+            accounts,
+            stakes: BTreeMap::new(),
+            last_reward: 0.into(),
+            trace: Vec::new(),
+        }
+    }
+
+    pub fn time(&self) -> u64 {
+        self.time
+    }
+
+    pub fn trace(&self) -> &[String] {
+        &self.trace
+    }
+
+    /// Reward of the latest end, scrape or referral collect
+    pub fn last_reward(&self) -> U256 {
+        self.last_reward
+    }
+
+    pub fn address(&self, name: &str) -> AccountHash {
+        *self
+            .accounts
+            .get(name)
+            .unwrap_or_else(|| self.fail(&format!("unknown account {}", name)))
+    }
+
+    pub fn stake_id(&self, label: &str) -> Vec<u32> {
+        self.staker_of(label).1
+    }
+
+    pub fn globals(&self) -> Globals {
+        self.wise.query_named_key(GLOBALS.into())
+    }
+
+    pub fn balance(&self, name: &str) -> U256 {
+        self.wise
+            .query_dictionary("balances", key_to_str(&Key::Account(self.address(name))))
+            .unwrap_or_default()
+    }
+
+    /// Creates a fresh account and sends it `amount` tokens of the owner
+    pub fn account(&mut self, name: &str, amount: U256) -> &mut Self {
+        let address: AccountHash = self.env.next_user();
+        self.accounts.insert(name.to_string(), address);
+        if amount > 0.into() {
+            self.wise.call_contract(
+                self.address("owner"),
+                "transfer",
+                runtime_args! {
+                    "recipient" => Key::Account(address),
+                    "amount" => amount
+                },
+                self.time,
+            );
+        }
+        self.log(format!("{} joins with {}", name, amount))
+    }
+
+    /// Moves the clock by whole stakeable days, snapshots are left to the next call
+    pub fn advance_days(&mut self, days: u64) -> &mut Self {
+        self.time += days * MILLI_SECONDS_IN_DAY;
+        self.days += days;
+        self.log(format!("{} days pass", days))
+    }
+
+    /// Takes the snapshots of the days passed so far
+    pub fn snapshot(&mut self) -> &mut Self {
+        self.wise.call_contract(
+            self.address("owner"),
+            "manual_daily_snapshot",
+            runtime_args! {},
+            self.time,
+        );
+        let day: U256 = self.globals().current_stakeable_day;
+        self.log(format!("snapshots taken up to stakeable day {}", day))
+    }
+
+    pub fn stake(&mut self, name: &str, label: &str, amount: U256, lock_days: u64) -> &mut Self {
+        self._stake(name, label, amount, lock_days, None)
+    }
+
+    pub fn stake_referred(
+        &mut self,
+        name: &str,
+        label: &str,
+        amount: U256,
+        lock_days: u64,
+        referrer: &str,
+    ) -> &mut Self {
+        self._stake(name, label, amount, lock_days, Some(referrer))
+    }
+
+    pub fn end_stake(&mut self, label: &str) -> &mut Self {
+        let (name, stake_id) = self.staker_of(label);
+        self.last_reward = self.session(
+            &name,
+            END_STAKE,
+            runtime_args! {
+                "stake_id" => stake_id
+            },
+        );
+        self.log(format!(
+            "{} ends {} for a reward of {}",
+            name, label, self.last_reward
+        ))
+    }
+
+    /// `scrape_days` of 0 scrapes every day up to today
+    pub fn scrape(&mut self, label: &str, scrape_days: u64) -> &mut Self {
+        let (name, stake_id) = self.staker_of(label);
+        let ret: Vec<String> = self.session(
+            &name,
+            SCRAPE_INTEREST,
+            runtime_args! {
+                "stake_id" => stake_id,
+                "scrape_days" => scrape_days
+            },
+        );
+        self.last_reward = U256::from_dec_str(&ret[1]).unwrap();
+        self.log(format!(
+            "{} scrapes {} for {}",
+            name, label, self.last_reward
+        ))
+    }
+
+    pub fn collect_referrals(&mut self, name: &str) -> &mut Self {
+        let (_, reward_amount): (U256, U256) = self.session(
+            name,
+            COLLECT_ALL_REFERRAL_INTEREST,
+            runtime_args! {
+                "cursor" => None::<U256>,
+                "max_links" => U256::from(0)
+            },
+        );
+        self.last_reward = reward_amount;
+        self.log(format!(
+            "{} collects referral interest of {}",
+            name, self.last_reward
+        ))
+    }
+
+    /// Runs `check` on the current globals, a failing assertion reports the trace
+    pub fn expect_globals(&mut self, check: impl FnOnce(&Globals)) -> &mut Self {
+        let globals: Globals = self.globals();
+        self.expect("globals", || check(&globals));
+        self.log(format!("globals checked: {:?}", globals))
+    }
+
+    pub fn expect_balance(&mut self, name: &str, amount: U256) -> &mut Self {
+        let balance: U256 = self.balance(name);
+        if balance != amount {
+            self.fail(&format!(
+                "balance of {} is {}, expected {}",
+                name, balance, amount
+            ));
+        }
+        self.log(format!("{} holds {}", name, balance))
+    }
+
+    pub fn expect_last_reward(&mut self, check: impl FnOnce(U256)) -> &mut Self {
+        let reward: U256 = self.last_reward;
+        self.expect("last reward", || check(reward));
+        self
+    }
+
+    fn _stake(
+        &mut self,
+        name: &str,
+        label: &str,
+        amount: U256,
+        lock_days: u64,
+        referrer: Option<&str>,
+    ) -> &mut Self {
+        let referrer_key: Key = match referrer {
+            Some(referrer) => Key::Account(self.address(referrer)),
+            None => account_zero_address(),
+        };
+        let (stake_id, _, _): (Vec<u32>, U256, Vec<u32>) = self.session(
+            name,
+            CREATE_STAKE,
+            runtime_args! {
+                "staked_amount" => amount,
+                "lock_days" => lock_days,
+                "referrer" => referrer_key
+            },
+        );
+        self.stakes
+            .insert(label.to_string(), (name.to_string(), stake_id));
+        match referrer {
+            Some(referrer) => self.log(format!(
+                "{} stakes {} for {} days as {}, referred by {}",
+                name, amount, lock_days, label, referrer
+            )),
+            None => self.log(format!(
+                "{} stakes {} for {} days as {}",
+                name, amount, lock_days, label
+            )),
+        }
+    }
+
+    fn staker_of(&self, label: &str) -> (String, Vec<u32>) {
+        self.stakes
+            .get(label)
+            .cloned()
+            .unwrap_or_else(|| self.fail(&format!("unknown stake {}", label)))
+    }
+
+    fn session<T: CLTyped + FromBytes>(
+        &self,
+        name: &str,
+        entrypoint: &str,
+        mut args: RuntimeArgs,
+    ) -> T {
+        let sender: AccountHash = self.address(name);
+        args.insert(ENTRYPOINT, entrypoint.to_string()).unwrap();
+        args.insert(PACKAGE_HASH, Key::Hash(self.wise.package_hash()))
+            .unwrap();
+        call(self.env, sender, SESSION_WASM_STAKEABLE, args, self.time);
+        result_key(self.env, sender, entrypoint)
+    }
+
+    fn expect(&self, what: &str, check: impl FnOnce()) {
+        if let Err(panic) = std::panic::catch_unwind(std::panic::AssertUnwindSafe(check)) {
+            let message: String = panic
+                .downcast_ref::<String>()
+                .cloned()
+                .or_else(|| {
+                    panic
+                        .downcast_ref::<&str>()
+                        .map(|message| message.to_string())
+                })
+                .unwrap_or_default();
+            self.fail(&format!("{} check failed: {}", what, message));
+        }
+    }
+
+    fn log(&mut self, step: String) -> &mut Self {
+        let line: String = format!("[day +{}] {}", self.days, step);
+        println!("{}", line);
+        self.trace.push(line);
+        self
+    }
+
+    fn fail(&self, message: &str) -> ! {
+        panic!("{}\nscenario so far:\n{}", message, self.trace.join("\n"))
+    }
+}
@@ -1,32 +1,58 @@
+use casper_contract::contract_api::runtime;
 use casper_types::{Key, U256};
 use casper_types_derive::{CLTyped, FromBytes, ToBytes};
 use casperlabs_contract_utils::{get_key, set_key, Dict};
 use common::{
+    errors::Errors,
     functions::{account_zero_address, package_hash, zero_address},
     keys::*,
 };
+// the formulas and their constants live in the host side `economics` crate
+pub use economics::{
+    MathError, MathResult, DAILY_BONUS_A, DAILY_BONUS_B, FORMULA_DAY, INFLATION_RATE_MAX,
+    MAX_BONUS_DAYS_A, MAX_BONUS_DAYS_B, MIN_REFERRAL_DAYS, PRECISION_RATE, REFERRALS_RATE,
+    SHARE_PRICE_CAP, TIER_RATE_PRECISION,
+};
 
 pub const DECIMALS: U256 = U256([9, 0, 0, 0]);
 pub const YODAS_PER_STAKEABLE: U256 = U256([1_000_000_000, 0, 0, 0]); // 10 ** DECIMALS
 
 pub const MILLI_SECONDS_IN_DAY: u32 = 86400000;
 pub const MIN_LOCK_DAYS: u16 = 1;
-pub const FORMULA_DAY: u16 = 25;
 pub const MAX_LOCK_DAYS: u16 = 15330;
-pub const MAX_BONUS_DAYS_A: u16 = 1825;
-pub const MAX_BONUS_DAYS_B: u16 = 13505;
-pub const MIN_REFERRAL_DAYS: u16 = 365;
 
 pub const MIN_STAKE_AMOUNT: u32 = 1_000; // E6 <=> E3
-pub const REFERRALS_RATE: u32 = 366_816_973;
-pub const INFLATION_RATE_MAX: u32 = 103000;
-
-pub const PRECISION_RATE: u64 = 1_000_000_000; // 1E9
 
 pub const THRESHOLD_LIMIT: u128 = 10_000_000_000_000; // 10000E9 // $10,000
 
-pub const DAILY_BONUS_A: u128 = 13698630136986302; // 25%:1825 = 0.01369863013 per day;
-pub const DAILY_BONUS_B: u128 = 370233246945575; // 5%:13505 = 0.00037023324 per day;
+pub const MIN_REFERRER_STAKE: u128 = 0; // referrers need no own stake until raised
+pub const REFERRER_COOLDOWN: u64 = 0; // days after the first own stake before referral links count
+pub const REFERRER_ALLOWLIST: u8 = 0; // 1 restricts referring to allowlisted accounts
+pub const CRITICAL_MASS_DECAY: u8 = 0; // 1 weights referred stakes by their remaining lock days
+pub const DECAY_PRECISION: u128 = 1_000_000_000;
+pub const QUOTE_SNAPSHOT_DAYS: u64 = 30; // recent snapshots used to project rewards
+pub const MAX_COLLECT_LINKS: u64 = 50; // referral links visited by one collect call
+pub const MAX_REFERRAL_TIERS: u8 = 3;
+pub const REFERRAL_TIER_DEPTH: u8 = 1; // only the direct referrer earns until raised
+pub const REFERRAL_TIER_2_RATE: u32 = 2_000; // 20% of referrer shares
+pub const REFERRAL_TIER_3_RATE: u32 = 1_000; // 10% of referrer shares
+pub const MIN_REFERRAL_CODE_LENGTH: usize = 3;
+pub const MAX_REFERRAL_CODE_LENGTH: usize = 32;
+pub const PARAMETER_TIMELOCK_DELAY: u64 = 604_800_000; // 7 days in ms
+
+// names of parameters governed through the timelocked registry
+pub const MIN_STAKE_AMOUNT_PARAM: &str = "min_stake_amount";
+pub const THRESHOLD_LIMIT_PARAM: &str = "threshold_limit";
+pub const REFERRALS_RATE_PARAM: &str = "referrals_rate";
+pub const FORMULA_DAY_PARAM: &str = "formula_day";
+pub const SHARE_PRICE_CAP_PARAM: &str = "share_price_cap";
+pub const REFERRAL_TIER_DEPTH_PARAM: &str = "referral_tier_depth";
+pub const REFERRAL_TIER_2_RATE_PARAM: &str = "referral_tier_2_rate";
+pub const REFERRAL_TIER_3_RATE_PARAM: &str = "referral_tier_3_rate";
+pub const MIN_REFERRER_STAKE_PARAM: &str = "min_referrer_stake";
+pub const REFERRER_COOLDOWN_PARAM: &str = "referrer_cooldown";
+pub const REFERRER_ALLOWLIST_PARAM: &str = "referrer_allowlist";
+pub const CRITICAL_MASS_DECAY_PARAM: &str = "critical_mass_decay";
 
 #[derive(Debug, Clone, Copy, CLTyped, ToBytes, FromBytes)]
 pub struct Stake {
@@ -42,6 +68,7 @@ pub struct Stake {
     pub referrer_shares: U256,
     pub referrer: Key,
     pub is_active: bool,
+    pub tier_shares: U256,
 }
 impl Default for Stake {
     fn default() -> Self {
@@ -58,10 +85,49 @@ impl Default for Stake {
             referrer_shares: Default::default(),
             referrer: account_zero_address(),
             is_active: Default::default(),
+            tier_shares: Default::default(),
+        }
+    }
+}
+impl Stake {
+    /// @notice converts a stake stored up to schema version 10, its tier shares were kept
+    ///     in `StakeTierShares`
+    pub fn from_legacy(stake: LegacyStake, tier_shares: U256) -> Stake {
+        Stake {
+            stakes_shares: stake.stakes_shares,
+            staked_amount: stake.staked_amount,
+            reward_amount: stake.reward_amount,
+            start_day: stake.start_day,
+            lock_days: stake.lock_days,
+            final_day: stake.final_day,
+            close_day: stake.close_day,
+            scrape_day: stake.scrape_day,
+            dai_equivalent: stake.dai_equivalent,
+            referrer_shares: stake.referrer_shares,
+            referrer: stake.referrer,
+            is_active: stake.is_active,
+            tier_shares,
         }
     }
 }
 
+/// Layout of `Stake` up to schema version 10, only read to convert stored stakes
+#[derive(Debug, Clone, Copy, CLTyped, ToBytes, FromBytes)]
+pub struct LegacyStake {
+    pub stakes_shares: U256,
+    pub staked_amount: U256,
+    pub reward_amount: U256,
+    pub start_day: u64,
+    pub lock_days: u64,
+    pub final_day: u64,
+    pub close_day: u64,
+    pub scrape_day: U256,
+    pub dai_equivalent: U256,
+    pub referrer_shares: U256,
+    pub referrer: Key,
+    pub is_active: bool,
+}
+
 #[derive(Debug, Clone, CLTyped, ToBytes, FromBytes)]
 pub struct ReferrerLink {
     pub staker: Key,
@@ -82,6 +148,30 @@ impl Default for ReferrerLink {
     }
 }
 
+#[derive(Debug, Clone, CLTyped, ToBytes, FromBytes)]
+pub struct TierLink {
+    pub staker: Key,
+    pub stake_id: Vec<u32>,
+    pub tier: u8,
+    pub tier_shares: U256,
+    pub reward_amount: U256,
+    pub processed_days: U256,
+    pub is_active: bool,
+}
+impl Default for TierLink {
+    fn default() -> Self {
+        Self {
+            staker: account_zero_address(),
+            stake_id: Default::default(),
+            tier: Default::default(),
+            tier_shares: Default::default(),
+            reward_amount: Default::default(),
+            processed_days: Default::default(),
+            is_active: Default::default(),
+        }
+    }
+}
+
 #[derive(Default, Debug, Clone, Copy, CLTyped, ToBytes, FromBytes)]
 pub struct LiquidityStake {
     pub staked_amount: U256,
@@ -97,6 +187,30 @@ pub struct CriticalMass_ {
     pub activation_day: U256,
 }
 
+#[derive(Default, Debug, Clone, Copy, CLTyped, ToBytes, FromBytes)]
+pub struct ReferrerSummary {
+    pub total_collected: U256,
+    pub accrued_amount: U256,
+    pub active_shares: U256,
+    pub pending_shares: U256,
+    pub checkpoint_day: U256,
+    pub active_links: U256,
+    pub ended_links: U256,
+}
+
+#[derive(Default, Debug, Clone, Copy, CLTyped, ToBytes, FromBytes)]
+pub struct DecayingMass {
+    pub weight: U256,
+    pub weighted_end: U256,
+    pub checkpoint_day: U256,
+}
+
+#[derive(Default, Debug, Clone, Copy, CLTyped, ToBytes, FromBytes)]
+pub struct ParameterProposal {
+    pub value: U256,
+    pub eta: U256,
+}
+
 pub struct StakeCount {
     dict: Dict,
 }
@@ -209,12 +323,44 @@ impl Stakes {
     pub fn init() {
         Dict::init(STAKES_DICT)
     }
+    /// @notice stakes not written since schema version 11 are converted from the legacy
+    ///     dictionary, they are stored in the new layout by the next `set`
     pub fn get(&self, key0: &Key, key1: &Vec<u32>) -> Stake {
-        self.dict.get_by_values((key0, key1)).unwrap_or_default()
+        self.dict
+            .get_by_values((key0, key1))
+            .or_else(|| self.legacy(key0, key1))
+            .unwrap_or_default()
     }
     pub fn set(&self, key0: &Key, key1: &Vec<u32>, value: Stake) {
         self.dict.set_by_values((key0, key1), value);
     }
+    /// @notice whether the stake is still only stored in the legacy layout
+    pub fn is_legacy(&self, key0: &Key, key1: &Vec<u32>) -> bool {
+        let stake: Option<Stake> = self.dict.get_by_values((key0, key1));
+        stake.is_none() && self.legacy(key0, key1).is_some()
+    }
+    fn legacy(&self, key0: &Key, key1: &Vec<u32>) -> Option<Stake> {
+        LegacyStakes::instance()
+            .get(key0, key1)
+            .map(|stake| Stake::from_legacy(stake, StakeTierShares::instance().get(key0, key1)))
+    }
+}
+
+pub struct LegacyStakes {
+    dict: Dict,
+}
+impl LegacyStakes {
+    pub fn instance() -> LegacyStakes {
+        LegacyStakes {
+            dict: Dict::instance(LEGACY_STAKES_DICT),
+        }
+    }
+    pub fn init() {
+        Dict::init(LEGACY_STAKES_DICT)
+    }
+    pub fn get(&self, key0: &Key, key1: &Vec<u32>) -> Option<LegacyStake> {
+        self.dict.get_by_values((key0, key1))
+    }
 }
 
 pub struct ReferrerLinks {
@@ -257,6 +403,28 @@ impl LiquidityStakes {
     }
 }
 
+// last day rewards of a liquidity stake were settled up to by a partial withdrawal, kept
+// apart so the stored `LiquidityStake` layout stays the same
+pub struct LiquidityScrapes {
+    dict: Dict,
+}
+impl LiquidityScrapes {
+    pub fn instance() -> LiquidityScrapes {
+        LiquidityScrapes {
+            dict: Dict::instance(LIQUIDITY_SCRAPES_DICT),
+        }
+    }
+    pub fn init() {
+        Dict::init(LIQUIDITY_SCRAPES_DICT)
+    }
+    pub fn get(&self, key0: &Key, key1: &Vec<u32>) -> u64 {
+        self.dict.get_by_values((key0, key1)).unwrap_or_default()
+    }
+    pub fn set(&self, key0: &Key, key1: &Vec<u32>, value: u64) {
+        self.dict.set_by_values((key0, key1), value);
+    }
+}
+
 pub struct ScheduledToEnd {
     dict: Dict,
 }
@@ -317,6 +485,563 @@ impl TotalPenalties {
     }
 }
 
+pub struct Parameters {
+    dict: Dict,
+}
+impl Parameters {
+    pub fn instance() -> Parameters {
+        Parameters {
+            dict: Dict::instance(PARAMETERS_DICT),
+        }
+    }
+    pub fn init() {
+        Dict::init(PARAMETERS_DICT)
+    }
+    pub fn get(&self, key: &str) -> Option<U256> {
+        self.dict.get(key)
+    }
+    pub fn set(&self, key: &str, value: U256) {
+        self.dict.set(key, value);
+    }
+}
+
+pub struct PendingParameters {
+    dict: Dict,
+}
+impl PendingParameters {
+    pub fn instance() -> PendingParameters {
+        PendingParameters {
+            dict: Dict::instance(PENDING_PARAMETERS_DICT),
+        }
+    }
+    pub fn init() {
+        Dict::init(PENDING_PARAMETERS_DICT)
+    }
+    pub fn get(&self, key: &str) -> ParameterProposal {
+        self.dict.get(key).unwrap_or_default()
+    }
+    pub fn set(&self, key: &str, value: ParameterProposal) {
+        self.dict.set(key, value);
+    }
+}
+
+pub struct Uplines {
+    dict: Dict,
+}
+impl Uplines {
+    pub fn instance() -> Uplines {
+        Uplines {
+            dict: Dict::instance(UPLINES_DICT),
+        }
+    }
+    pub fn init() {
+        Dict::init(UPLINES_DICT)
+    }
+    pub fn get(&self, key: &Key) -> Key {
+        self.dict
+            .get_by_key(key)
+            .unwrap_or_else(account_zero_address)
+    }
+    pub fn set(&self, key: &Key, value: Key) {
+        self.dict.set_by_key(key, value);
+    }
+}
+
+pub struct TierLinkCount {
+    dict: Dict,
+}
+impl TierLinkCount {
+    pub fn instance() -> TierLinkCount {
+        TierLinkCount {
+            dict: Dict::instance(TIER_LINK_COUNT_DICT),
+        }
+    }
+    pub fn init() {
+        Dict::init(TIER_LINK_COUNT_DICT)
+    }
+    pub fn get(&self, key: &Key) -> U256 {
+        self.dict.get_by_key(key).unwrap_or_default()
+    }
+    pub fn set(&self, key: &Key, value: U256) {
+        self.dict.set_by_key(key, value);
+    }
+}
+
+pub struct TierLinks {
+    dict: Dict,
+}
+impl TierLinks {
+    pub fn instance() -> TierLinks {
+        TierLinks {
+            dict: Dict::instance(TIER_LINKS_DICT),
+        }
+    }
+    pub fn init() {
+        Dict::init(TIER_LINKS_DICT)
+    }
+    pub fn get(&self, key0: &Key, key1: &Vec<u32>) -> TierLink {
+        self.dict.get_by_values((key0, key1)).unwrap_or_default()
+    }
+    pub fn set(&self, key0: &Key, key1: &Vec<u32>, value: TierLink) {
+        self.dict.set_by_values((key0, key1), value);
+    }
+}
+
+pub struct StakeTierShares {
+    dict: Dict,
+}
+impl StakeTierShares {
+    pub fn instance() -> StakeTierShares {
+        StakeTierShares {
+            dict: Dict::instance(STAKE_TIER_SHARES_DICT),
+        }
+    }
+    pub fn init() {
+        Dict::init(STAKE_TIER_SHARES_DICT)
+    }
+    pub fn get(&self, key0: &Key, key1: &Vec<u32>) -> U256 {
+        self.dict.get_by_values((key0, key1)).unwrap_or_default()
+    }
+    pub fn set(&self, key0: &Key, key1: &Vec<u32>, value: U256) {
+        self.dict.set_by_values((key0, key1), value);
+    }
+}
+
+// upline and tier link id of each tier link of a stake, by staker and stake id
+pub struct StakeTierLinks {
+    dict: Dict,
+}
+impl StakeTierLinks {
+    pub fn instance() -> StakeTierLinks {
+        StakeTierLinks {
+            dict: Dict::instance(STAKE_TIER_LINKS_DICT),
+        }
+    }
+    pub fn init() {
+        Dict::init(STAKE_TIER_LINKS_DICT)
+    }
+    pub fn get(&self, key0: &Key, key1: &Vec<u32>) -> Vec<(Key, Vec<u32>)> {
+        self.dict.get_by_values((key0, key1)).unwrap_or_default()
+    }
+    pub fn set(&self, key0: &Key, key1: &Vec<u32>, value: Vec<(Key, Vec<u32>)>) {
+        self.dict.set_by_values((key0, key1), value);
+    }
+}
+
+pub struct TierSharesToEnd {
+    dict: Dict,
+}
+impl TierSharesToEnd {
+    pub fn instance() -> TierSharesToEnd {
+        TierSharesToEnd {
+            dict: Dict::instance(TIER_SHARES_TO_END_DICT),
+        }
+    }
+    pub fn init() {
+        Dict::init(TIER_SHARES_TO_END_DICT)
+    }
+    pub fn get(&self, key: &U256) -> U256 {
+        self.dict.get(&key.to_string()).unwrap_or_default()
+    }
+    pub fn set(&self, key: &U256, value: U256) {
+        self.dict.set(&key.to_string(), value);
+    }
+}
+
+pub struct ReferralCodes {
+    dict: Dict,
+}
+impl ReferralCodes {
+    pub fn instance() -> ReferralCodes {
+        ReferralCodes {
+            dict: Dict::instance(REFERRAL_CODES_DICT),
+        }
+    }
+    pub fn init() {
+        Dict::init(REFERRAL_CODES_DICT)
+    }
+    pub fn get(&self, key: &str) -> Key {
+        self.dict.get(key).unwrap_or_else(account_zero_address)
+    }
+    pub fn set(&self, key: &str, value: Key) {
+        self.dict.set(key, value);
+    }
+}
+
+pub struct ReferrerCodes {
+    dict: Dict,
+}
+impl ReferrerCodes {
+    pub fn instance() -> ReferrerCodes {
+        ReferrerCodes {
+            dict: Dict::instance(REFERRER_CODES_DICT),
+        }
+    }
+    pub fn init() {
+        Dict::init(REFERRER_CODES_DICT)
+    }
+    pub fn get(&self, key: &Key) -> String {
+        self.dict.get_by_key(key).unwrap_or_default()
+    }
+    pub fn set(&self, key: &Key, value: String) {
+        self.dict.set_by_key(key, value);
+    }
+}
+
+pub struct AllowedReferrers {
+    dict: Dict,
+}
+impl AllowedReferrers {
+    pub fn instance() -> AllowedReferrers {
+        AllowedReferrers {
+            dict: Dict::instance(ALLOWED_REFERRERS_DICT),
+        }
+    }
+    pub fn init() {
+        Dict::init(ALLOWED_REFERRERS_DICT)
+    }
+    pub fn get(&self, key: &Key) -> bool {
+        self.dict.get_by_key(key).unwrap_or_default()
+    }
+    pub fn set(&self, key: &Key, value: bool) {
+        self.dict.set_by_key(key, value);
+    }
+}
+
+pub struct DeniedReferrers {
+    dict: Dict,
+}
+impl DeniedReferrers {
+    pub fn instance() -> DeniedReferrers {
+        DeniedReferrers {
+            dict: Dict::instance(DENIED_REFERRERS_DICT),
+        }
+    }
+    pub fn init() {
+        Dict::init(DENIED_REFERRERS_DICT)
+    }
+    pub fn get(&self, key: &Key) -> bool {
+        self.dict.get_by_key(key).unwrap_or_default()
+    }
+    pub fn set(&self, key: &Key, value: bool) {
+        self.dict.set_by_key(key, value);
+    }
+}
+
+pub struct AccountStaked {
+    dict: Dict,
+}
+impl AccountStaked {
+    pub fn instance() -> AccountStaked {
+        AccountStaked {
+            dict: Dict::instance(ACCOUNT_STAKED_DICT),
+        }
+    }
+    pub fn init() {
+        Dict::init(ACCOUNT_STAKED_DICT)
+    }
+    pub fn get(&self, key: &Key) -> U256 {
+        self.dict.get_by_key(key).unwrap_or_default()
+    }
+    pub fn set(&self, key: &Key, value: U256) {
+        self.dict.set_by_key(key, value);
+    }
+}
+
+pub struct FirstStakeDay {
+    dict: Dict,
+}
+impl FirstStakeDay {
+    pub fn instance() -> FirstStakeDay {
+        FirstStakeDay {
+            dict: Dict::instance(FIRST_STAKE_DAY_DICT),
+        }
+    }
+    pub fn init() {
+        Dict::init(FIRST_STAKE_DAY_DICT)
+    }
+    pub fn get(&self, key: &Key) -> U256 {
+        self.dict.get_by_key(key).unwrap_or_default()
+    }
+    pub fn set(&self, key: &Key, value: U256) {
+        self.dict.set_by_key(key, value);
+    }
+}
+
+pub struct ReferrerCooldownEnds {
+    dict: Dict,
+}
+impl ReferrerCooldownEnds {
+    pub fn instance() -> ReferrerCooldownEnds {
+        ReferrerCooldownEnds {
+            dict: Dict::instance(REFERRER_COOLDOWN_END_DICT),
+        }
+    }
+    pub fn init() {
+        Dict::init(REFERRER_COOLDOWN_END_DICT)
+    }
+    pub fn get(&self, key: &Key) -> U256 {
+        self.dict.get_by_key(key).unwrap_or_default()
+    }
+    pub fn set(&self, key: &Key, value: U256) {
+        self.dict.set_by_key(key, value);
+    }
+}
+
+pub struct DecayingCriticalMass {
+    dict: Dict,
+}
+impl DecayingCriticalMass {
+    pub fn instance() -> DecayingCriticalMass {
+        DecayingCriticalMass {
+            dict: Dict::instance(DECAYING_CRITICAL_MASS_DICT),
+        }
+    }
+    pub fn init() {
+        Dict::init(DECAYING_CRITICAL_MASS_DICT)
+    }
+    pub fn get(&self, key: &Key) -> DecayingMass {
+        self.dict.get_by_key(key).unwrap_or_default()
+    }
+    pub fn set(&self, key: &Key, value: DecayingMass) {
+        self.dict.set_by_key(key, value);
+    }
+}
+
+pub struct DecayingMassEnding {
+    dict: Dict,
+}
+impl DecayingMassEnding {
+    pub fn instance() -> DecayingMassEnding {
+        DecayingMassEnding {
+            dict: Dict::instance(DECAYING_MASS_ENDING_DICT),
+        }
+    }
+    pub fn init() {
+        Dict::init(DECAYING_MASS_ENDING_DICT)
+    }
+    pub fn get(&self, key0: &Key, key1: &U256) -> U256 {
+        self.dict.get_by_values((key0, key1)).unwrap_or_default()
+    }
+    pub fn set(&self, key0: &Key, key1: &U256, value: U256) {
+        self.dict.set_by_values((key0, key1), value);
+    }
+}
+
+pub struct ReferralCursors {
+    dict: Dict,
+}
+impl ReferralCursors {
+    pub fn instance() -> ReferralCursors {
+        ReferralCursors {
+            dict: Dict::instance(REFERRAL_CURSORS_DICT),
+        }
+    }
+    pub fn init() {
+        Dict::init(REFERRAL_CURSORS_DICT)
+    }
+    pub fn get(&self, key: &Key) -> U256 {
+        self.dict.get_by_key(key).unwrap_or_default()
+    }
+    pub fn set(&self, key: &Key, value: U256) {
+        self.dict.set_by_key(key, value);
+    }
+}
+
+pub struct ReferrerSummaries {
+    dict: Dict,
+}
+impl ReferrerSummaries {
+    pub fn instance() -> ReferrerSummaries {
+        ReferrerSummaries {
+            dict: Dict::instance(REFERRER_SUMMARIES_DICT),
+        }
+    }
+    pub fn init() {
+        Dict::init(REFERRER_SUMMARIES_DICT)
+    }
+    pub fn get(&self, key: &Key) -> ReferrerSummary {
+        self.dict.get_by_key(key).unwrap_or_default()
+    }
+    pub fn set(&self, key: &Key, value: ReferrerSummary) {
+        self.dict.set_by_key(key, value);
+    }
+}
+
+pub struct ReferrerSharesStarting {
+    dict: Dict,
+}
+impl ReferrerSharesStarting {
+    pub fn instance() -> ReferrerSharesStarting {
+        ReferrerSharesStarting {
+            dict: Dict::instance(REFERRER_SHARES_STARTING_DICT),
+        }
+    }
+    pub fn init() {
+        Dict::init(REFERRER_SHARES_STARTING_DICT)
+    }
+    pub fn get(&self, key0: &Key, key1: &U256) -> U256 {
+        self.dict.get_by_values((key0, key1)).unwrap_or_default()
+    }
+    pub fn set(&self, key0: &Key, key1: &U256, value: U256) {
+        self.dict.set_by_values((key0, key1), value);
+    }
+}
+
+pub struct ReferrerSharesEnding {
+    dict: Dict,
+}
+impl ReferrerSharesEnding {
+    pub fn instance() -> ReferrerSharesEnding {
+        ReferrerSharesEnding {
+            dict: Dict::instance(REFERRER_SHARES_ENDING_DICT),
+        }
+    }
+    pub fn init() {
+        Dict::init(REFERRER_SHARES_ENDING_DICT)
+    }
+    pub fn get(&self, key0: &Key, key1: &U256) -> U256 {
+        self.dict.get_by_values((key0, key1)).unwrap_or_default()
+    }
+    pub fn set(&self, key0: &Key, key1: &U256, value: U256) {
+        self.dict.set_by_values((key0, key1), value);
+    }
+}
+
+// whether the stake added its weight to the decaying critical mass of its referrer
+pub struct DecayingMassRecorded {
+    dict: Dict,
+}
+impl DecayingMassRecorded {
+    pub fn instance() -> DecayingMassRecorded {
+        DecayingMassRecorded {
+            dict: Dict::instance(DECAYING_MASS_RECORDED_DICT),
+        }
+    }
+    pub fn init() {
+        Dict::init(DECAYING_MASS_RECORDED_DICT)
+    }
+    pub fn get(&self, key0: &Key, key1: &Vec<u32>) -> bool {
+        self.dict.get_by_values((key0, key1)).unwrap_or_default()
+    }
+    pub fn set(&self, key0: &Key, key1: &Vec<u32>, value: bool) {
+        self.dict.set_by_values((key0, key1), value);
+    }
+}
+
+// whether the referral link of a stake is counted in the referrer summary
+pub struct SummaryCounted {
+    dict: Dict,
+}
+impl SummaryCounted {
+    pub fn instance() -> SummaryCounted {
+        SummaryCounted {
+            dict: Dict::instance(SUMMARY_COUNTED_DICT),
+        }
+    }
+    pub fn init() {
+        Dict::init(SUMMARY_COUNTED_DICT)
+    }
+    pub fn get(&self, key0: &Key, key1: &Vec<u32>) -> bool {
+        self.dict.get_by_values((key0, key1)).unwrap_or_default()
+    }
+    pub fn set(&self, key0: &Key, key1: &Vec<u32>, value: bool) {
+        self.dict.set_by_values((key0, key1), value);
+    }
+}
+
+/// @notice unwraps the result of an `economics` formula, reverting with the error of the call site
+pub fn math_or_revert(result: MathResult, overflow: Errors, division_by_zero: Errors) -> U256 {
+    match result {
+        Ok(value) => value,
+        Err(MathError::Overflow) => runtime::revert(overflow),
+        Err(MathError::DivisionByZero) => runtime::revert(division_by_zero),
+    }
+}
+
+pub fn parameter_default(name: &str) -> Option<U256> {
+    match name {
+        MIN_STAKE_AMOUNT_PARAM => Some(MIN_STAKE_AMOUNT.into()),
+        THRESHOLD_LIMIT_PARAM => Some(THRESHOLD_LIMIT.into()),
+        REFERRALS_RATE_PARAM => Some(REFERRALS_RATE.into()),
+        FORMULA_DAY_PARAM => Some(FORMULA_DAY.into()),
+        SHARE_PRICE_CAP_PARAM => Some(SHARE_PRICE_CAP.into()),
+        REFERRAL_TIER_DEPTH_PARAM => Some(REFERRAL_TIER_DEPTH.into()),
+        REFERRAL_TIER_2_RATE_PARAM => Some(REFERRAL_TIER_2_RATE.into()),
+        REFERRAL_TIER_3_RATE_PARAM => Some(REFERRAL_TIER_3_RATE.into()),
+        MIN_REFERRER_STAKE_PARAM => Some(MIN_REFERRER_STAKE.into()),
+        REFERRER_COOLDOWN_PARAM => Some(REFERRER_COOLDOWN.into()),
+        REFERRER_ALLOWLIST_PARAM => Some(REFERRER_ALLOWLIST.into()),
+        CRITICAL_MASS_DECAY_PARAM => Some(CRITICAL_MASS_DECAY.into()),
+        _ => None,
+    }
+}
+
+/// @notice optional controls are switched off by setting them to zero
+pub fn parameter_allows_zero(name: &str) -> bool {
+    matches!(
+        name,
+        MIN_REFERRER_STAKE_PARAM
+            | REFERRER_COOLDOWN_PARAM
+            | REFERRER_ALLOWLIST_PARAM
+            | CRITICAL_MASS_DECAY_PARAM
+    )
+}
+pub fn parameter(name: &str) -> U256 {
+    Parameters::instance()
+        .get(name)
+        .or_else(|| parameter_default(name))
+        .unwrap_or_default()
+}
+
+pub fn min_stake_amount() -> U256 {
+    parameter(MIN_STAKE_AMOUNT_PARAM)
+}
+pub fn threshold_limit() -> U256 {
+    parameter(THRESHOLD_LIMIT_PARAM)
+}
+pub fn referrals_rate() -> U256 {
+    parameter(REFERRALS_RATE_PARAM)
+}
+pub fn formula_day() -> U256 {
+    parameter(FORMULA_DAY_PARAM)
+}
+pub fn share_price_cap() -> U256 {
+    parameter(SHARE_PRICE_CAP_PARAM)
+}
+
+pub fn min_referrer_stake() -> U256 {
+    parameter(MIN_REFERRER_STAKE_PARAM)
+}
+pub fn referrer_cooldown() -> U256 {
+    parameter(REFERRER_COOLDOWN_PARAM)
+}
+pub fn referrer_allowlist() -> bool {
+    parameter(REFERRER_ALLOWLIST_PARAM) > 0.into()
+}
+
+pub fn critical_mass_decay() -> bool {
+    parameter(CRITICAL_MASS_DECAY_PARAM) > 0.into()
+}
+
+pub fn referral_tier_depth() -> u8 {
+    parameter(REFERRAL_TIER_DEPTH_PARAM).as_u32() as u8
+}
+pub fn referral_tier_rate(tier: u8) -> U256 {
+    match tier {
+        2 => parameter(REFERRAL_TIER_2_RATE_PARAM),
+        3 => parameter(REFERRAL_TIER_3_RATE_PARAM),
+        _ => 0.into(),
+    }
+}
+
+pub fn set_tier_shares(tier_shares: U256) {
+    set_key(TIER_SHARES, tier_shares);
+}
+pub fn tier_shares() -> U256 {
+    get_key(TIER_SHARES).unwrap_or_default()
+}
+
 pub fn launch_time() -> U256 {
     get_key(LAUNCH_TIME).unwrap_or_default()
 }
@@ -325,14 +1050,14 @@ pub fn set_inflation_rate(inflation_rate: u32) {
     set_key(INFLATION_RATE, inflation_rate);
 }
 pub fn inflation_rate() -> u32 {
-    get_key(INFLATION_RATE).unwrap_or(103000) // 3.000% (indirect -> checks throgh LiquidityGuard)
+    get_key(INFLATION_RATE).unwrap_or(economics::INFLATION_RATE)
 }
 
 pub fn set_liquidity_rate(liquidity_rate: u32) {
     set_key(LIQUIDITY_RATE, liquidity_rate);
 }
 pub fn liquidity_rate() -> u32 {
-    get_key(LIQUIDITY_RATE).unwrap_or(100006) // 3.000% (indirect -> checks throgh LiquidityGuard)
+    get_key(LIQUIDITY_RATE).unwrap_or(economics::LIQUIDITY_RATE)
 }
 
 pub fn set_stable_usd(stable_usd: Key) {
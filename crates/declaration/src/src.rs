@@ -3,7 +3,9 @@ use casper_contract::contract_api::runtime;
 use casper_types::{runtime_args, RuntimeArgs, U256};
 use casperlabs_contract_utils::{set_key, ContractContext, ContractStorage};
 use global::{
+    access_control::PARAMETER_MANAGER_ROLE,
     errors::Errors,
+    events::{emit, Events},
     functions::{block_timestamp, key_to_hash, package_hash},
     keys::LAUNCH_TIME,
     src::GLOBAL,
@@ -25,12 +27,107 @@ pub trait DECLARATION<Storage: ContractStorage>:
         LiquidityStakeCount::init();
         CriticalMass::init();
         Scrapes::init();
+        LegacyStakes::init();
         Stakes::init();
         ReferrerLinks::init();
         LiquidityStakes::init();
+        LiquidityScrapes::init();
         ScheduledToEnd::init();
         ReferralSharesToEnd::init();
         TotalPenalties::init();
+        Parameters::init();
+        PendingParameters::init();
+        ReferralCodes::init();
+        ReferrerCodes::init();
+        Uplines::init();
+        TierLinkCount::init();
+        TierLinks::init();
+        StakeTierShares::init();
+        StakeTierLinks::init();
+        TierSharesToEnd::init();
+        ReferralCursors::init();
+        ReferrerSummaries::init();
+        ReferrerSharesStarting::init();
+        ReferrerSharesEnding::init();
+        SummaryCounted::init();
+        AllowedReferrers::init();
+        DeniedReferrers::init();
+        AccountStaked::init();
+        FirstStakeDay::init();
+        ReferrerCooldownEnds::init();
+        DecayingCriticalMass::init();
+        DecayingMassEnding::init();
+        DecayingMassRecorded::init();
+    }
+
+    /// @notice queues a new value for an economic parameter, it can be executed once the timelock expires
+    /// @param name of the parameter, see `parameter_default` for the governed names
+    /// @param value proposed for the parameter
+    fn propose_parameter(&self, name: String, value: U256) {
+        self.only_role(PARAMETER_MANAGER_ROLE);
+        if parameter_default(&name).is_none()
+            || (value == 0.into() && !parameter_allows_zero(&name))
+        {
+            runtime::revert(Errors::InvalidParameter);
+        }
+        if name == SHARE_PRICE_CAP_PARAM && value < 100.into() {
+            runtime::revert(Errors::InvalidParameter);
+        }
+        if name == REFERRAL_TIER_DEPTH_PARAM && value > MAX_REFERRAL_TIERS.into() {
+            runtime::revert(Errors::InvalidParameter);
+        }
+        if (name == REFERRAL_TIER_2_RATE_PARAM || name == REFERRAL_TIER_3_RATE_PARAM)
+            && value > TIER_RATE_PRECISION.into()
+        {
+            runtime::revert(Errors::InvalidParameter);
+        }
+        if (name == REFERRER_ALLOWLIST_PARAM || name == CRITICAL_MASS_DECAY_PARAM)
+            && value > 1.into()
+        {
+            runtime::revert(Errors::InvalidParameter);
+        }
+        let eta: U256 = U256::from(block_timestamp()) + PARAMETER_TIMELOCK_DELAY;
+        PendingParameters::instance().set(&name, ParameterProposal { value, eta });
+        emit(&Events::ParameterProposed { name, value, eta });
+    }
+
+    /// @notice applies a queued parameter value after its timelock has expired
+    fn execute_parameter(&self, name: String) {
+        let proposal: ParameterProposal = PendingParameters::instance().get(&name);
+        if proposal.eta == 0.into() {
+            runtime::revert(Errors::NoPendingParameter);
+        }
+        if U256::from(block_timestamp()) < proposal.eta {
+            runtime::revert(Errors::TimelockNotExpired);
+        }
+        let old_value: U256 = parameter(&name);
+        Parameters::instance().set(&name, proposal.value);
+        PendingParameters::instance().set(&name, ParameterProposal::default());
+        emit(&Events::ParameterUpdated {
+            name,
+            old_value,
+            new_value: proposal.value,
+        });
+    }
+
+    fn cancel_parameter(&self, name: String) {
+        self.only_role(PARAMETER_MANAGER_ROLE);
+        if PendingParameters::instance().get(&name).eta == 0.into() {
+            runtime::revert(Errors::NoPendingParameter);
+        }
+        PendingParameters::instance().set(&name, ParameterProposal::default());
+        emit(&Events::ParameterCancelled { name });
+    }
+
+    fn get_parameter(&self, name: String) -> U256 {
+        if parameter_default(&name).is_none() {
+            runtime::revert(Errors::InvalidParameter);
+        }
+        parameter(&name)
+    }
+
+    fn get_pending_parameter(&self, name: String) -> ParameterProposal {
+        PendingParameters::instance().get(&name)
     }
 
     fn create_pair(&self) {
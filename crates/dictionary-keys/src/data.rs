@@ -0,0 +1,72 @@
+use blake2::{
+    digest::{Update, VariableOutput},
+    VarBlake2b,
+};
+use casper_types::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    Key, URef, U256,
+};
+use core::marker::PhantomData;
+
+/// One entry of a named dictionary of the stakeable token, decodes to `T`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DictionaryItem<T> {
+    pub dictionary_name: &'static str,
+    pub item_key: String,
+    value: PhantomData<T>,
+}
+
+impl<T: FromBytes> DictionaryItem<T> {
+    pub fn new(dictionary_name: &'static str, item_key: String) -> Self {
+        DictionaryItem {
+            dictionary_name,
+            item_key,
+            value: PhantomData,
+        }
+    }
+
+    /// Global state key of the item, `seed_uref` is the URef the contract stores under
+    /// `dictionary_name`
+    pub fn address(&self, seed_uref: URef) -> Key {
+        Key::dictionary(seed_uref, self.item_key.as_bytes())
+    }
+
+    /// Decodes the `bytes` of the CLValue returned for the item
+    pub fn decode(&self, bytes: &[u8]) -> Result<T, bytesrepr::Error> {
+        bytesrepr::deserialize(bytes.to_vec())
+    }
+
+    /// Same as `decode` for the hex encoded bytes of the RPC response
+    pub fn decode_hex(&self, bytes: &str) -> Result<T, bytesrepr::Error> {
+        let bytes: Vec<u8> = hex::decode(bytes).map_err(|_| bytesrepr::Error::Formatting)?;
+        self.decode(&bytes)
+    }
+}
+
+/// Item key of `Dict::get_by_key`, account and package hashes in lowercase hex.
+///
+/// # Panics
+///
+/// On other key variants, the contracts revert before storing those.
+pub fn key_to_str(key: &Key) -> String {
+    match key {
+        Key::Account(account) => hex::encode(account.value()),
+        Key::Hash(package) => hex::encode(package),
+        _ => panic!("dictionaries are only keyed by accounts and hashes"),
+    }
+}
+
+/// Item key of `Dict::get_by_values`, the blake2b-256 hash of the serialized values in hex
+pub fn values_to_str<T: ToBytes>(values: T) -> String {
+    let bytes: Vec<u8> = values.to_bytes().expect("values serialize");
+    let mut hasher = VarBlake2b::new(32).expect("valid digest length");
+    hasher.update(bytes);
+    let mut hash = [0u8; 32];
+    hasher.finalize_variable(|result| hash.copy_from_slice(result));
+    hex::encode(hash)
+}
+
+/// Item key of the dictionaries indexed by day, the decimal day
+pub fn day_to_str(day: U256) -> String {
+    day.to_string()
+}
@@ -0,0 +1,11 @@
+//! Host-side derivation of the dictionary item keys the contracts use, so indexers can read
+//! state with `state_get_dictionary_item` instead of deploying a session to an entry point.
+
+pub mod data;
+pub mod src;
+
+pub use data::*;
+pub use src::*;
+
+#[cfg(test)]
+mod tests;
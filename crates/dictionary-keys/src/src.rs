@@ -0,0 +1,211 @@
+use crate::data::*;
+use casper_types::{Key, U256};
+use common::keys::*;
+// value types, re-exported so clients can name what they decode
+pub use declaration::data::{
+    CriticalMass_, DecayingMass, LegacyStake, LiquidityStake, ParameterProposal, ReferrerLink,
+    ReferrerSummary, Stake, TierLink,
+};
+pub use snapshot::data::{LSnapShot, RSnapShot, SnapShot, TSnapShot};
+
+// dictionary of the casperlabs-erc20 token the stakeable token extends
+pub const BALANCES_DICT: &str = "balances";
+
+// stakes and links, by account and id
+pub fn stakes(staker: &Key, stake_id: &[u32]) -> DictionaryItem<Stake> {
+    DictionaryItem::new(STAKES_DICT, values_to_str((*staker, stake_id.to_vec())))
+}
+
+// stakes created before schema version 11 and not yet passed to `convert_stakes`
+pub fn legacy_stakes(staker: &Key, stake_id: &[u32]) -> DictionaryItem<LegacyStake> {
+    DictionaryItem::new(
+        LEGACY_STAKES_DICT,
+        values_to_str((*staker, stake_id.to_vec())),
+    )
+}
+
+pub fn scrapes(staker: &Key, stake_id: &[u32]) -> DictionaryItem<U256> {
+    DictionaryItem::new(SCRAPES_DICT, values_to_str((*staker, stake_id.to_vec())))
+}
+
+// tier shares of stakes created before schema version 11, later ones keep them in `Stake`
+pub fn stake_tier_shares(staker: &Key, stake_id: &[u32]) -> DictionaryItem<U256> {
+    DictionaryItem::new(
+        STAKE_TIER_SHARES_DICT,
+        values_to_str((*staker, stake_id.to_vec())),
+    )
+}
+
+pub fn liquidity_stakes(
+    staker: &Key,
+    liquidity_stake_id: &[u32],
+) -> DictionaryItem<LiquidityStake> {
+    DictionaryItem::new(
+        LIQUIDITY_STAKES_DICT,
+        values_to_str((*staker, liquidity_stake_id.to_vec())),
+    )
+}
+
+pub fn liquidity_scrapes(staker: &Key, liquidity_stake_id: &[u32]) -> DictionaryItem<u64> {
+    DictionaryItem::new(
+        LIQUIDITY_SCRAPES_DICT,
+        values_to_str((*staker, liquidity_stake_id.to_vec())),
+    )
+}
+
+pub fn referrer_links(referrer: &Key, referral_id: &[u32]) -> DictionaryItem<ReferrerLink> {
+    DictionaryItem::new(
+        REFERRER_LINKS_DICT,
+        values_to_str((*referrer, referral_id.to_vec())),
+    )
+}
+
+// upline and tier link id of each tier link of a stake
+pub fn stake_tier_links(staker: &Key, stake_id: &[u32]) -> DictionaryItem<Vec<(Key, Vec<u32>)>> {
+    DictionaryItem::new(
+        STAKE_TIER_LINKS_DICT,
+        values_to_str((*staker, stake_id.to_vec())),
+    )
+}
+
+pub fn tier_links(upline: &Key, tier_link_id: &[u32]) -> DictionaryItem<TierLink> {
+    DictionaryItem::new(
+        TIER_LINKS_DICT,
+        values_to_str((*upline, tier_link_id.to_vec())),
+    )
+}
+
+// counters and per account state, by account
+pub fn stake_count(staker: &Key) -> DictionaryItem<U256> {
+    DictionaryItem::new(STAKE_COUNT_DICT, key_to_str(staker))
+}
+
+pub fn referral_count(referrer: &Key) -> DictionaryItem<U256> {
+    DictionaryItem::new(REFERRAL_COUNT_DICT, key_to_str(referrer))
+}
+
+pub fn liquidity_stake_count(staker: &Key) -> DictionaryItem<U256> {
+    DictionaryItem::new(LIQUIDITY_STAKE_COUNT_DICT, key_to_str(staker))
+}
+
+pub fn tier_link_count(upline: &Key) -> DictionaryItem<U256> {
+    DictionaryItem::new(TIER_LINK_COUNT_DICT, key_to_str(upline))
+}
+
+pub fn critical_mass(referrer: &Key) -> DictionaryItem<CriticalMass_> {
+    DictionaryItem::new(CRITICAL_MASS_DICT, key_to_str(referrer))
+}
+
+pub fn decaying_critical_mass(referrer: &Key) -> DictionaryItem<DecayingMass> {
+    DictionaryItem::new(DECAYING_CRITICAL_MASS_DICT, key_to_str(referrer))
+}
+
+pub fn referrer_summaries(referrer: &Key) -> DictionaryItem<ReferrerSummary> {
+    DictionaryItem::new(REFERRER_SUMMARIES_DICT, key_to_str(referrer))
+}
+
+pub fn referral_cursors(referrer: &Key) -> DictionaryItem<U256> {
+    DictionaryItem::new(REFERRAL_CURSORS_DICT, key_to_str(referrer))
+}
+
+pub fn referrer_codes(referrer: &Key) -> DictionaryItem<String> {
+    DictionaryItem::new(REFERRER_CODES_DICT, key_to_str(referrer))
+}
+
+pub fn allowed_referrers(referrer: &Key) -> DictionaryItem<bool> {
+    DictionaryItem::new(ALLOWED_REFERRERS_DICT, key_to_str(referrer))
+}
+
+pub fn denied_referrers(referrer: &Key) -> DictionaryItem<bool> {
+    DictionaryItem::new(DENIED_REFERRERS_DICT, key_to_str(referrer))
+}
+
+pub fn account_staked(staker: &Key) -> DictionaryItem<U256> {
+    DictionaryItem::new(ACCOUNT_STAKED_DICT, key_to_str(staker))
+}
+
+pub fn first_stake_day(staker: &Key) -> DictionaryItem<U256> {
+    DictionaryItem::new(FIRST_STAKE_DAY_DICT, key_to_str(staker))
+}
+
+pub fn referrer_cooldown_ends(referrer: &Key) -> DictionaryItem<U256> {
+    DictionaryItem::new(REFERRER_COOLDOWN_END_DICT, key_to_str(referrer))
+}
+
+pub fn uplines(staker: &Key) -> DictionaryItem<Key> {
+    DictionaryItem::new(UPLINES_DICT, key_to_str(staker))
+}
+
+// by account and day
+pub fn decaying_mass_ending(referrer: &Key, day: U256) -> DictionaryItem<U256> {
+    DictionaryItem::new(DECAYING_MASS_ENDING_DICT, values_to_str((*referrer, day)))
+}
+
+pub fn referrer_shares_starting(referrer: &Key, day: U256) -> DictionaryItem<U256> {
+    DictionaryItem::new(
+        REFERRER_SHARES_STARTING_DICT,
+        values_to_str((*referrer, day)),
+    )
+}
+
+pub fn referrer_shares_ending(referrer: &Key, day: U256) -> DictionaryItem<U256> {
+    DictionaryItem::new(REFERRER_SHARES_ENDING_DICT, values_to_str((*referrer, day)))
+}
+
+// by day
+pub fn scheduled_to_end(day: U256) -> DictionaryItem<U256> {
+    DictionaryItem::new(SCHEDULED_TO_END_DICT, day_to_str(day))
+}
+
+pub fn referral_shares_to_end(day: U256) -> DictionaryItem<U256> {
+    DictionaryItem::new(REFERRAL_SHARES_TO_END_DICT, day_to_str(day))
+}
+
+pub fn total_penalties(day: U256) -> DictionaryItem<U256> {
+    DictionaryItem::new(TOTAL_PENALTIES_DICT, day_to_str(day))
+}
+
+pub fn tier_shares_to_end(day: U256) -> DictionaryItem<U256> {
+    DictionaryItem::new(TIER_SHARES_TO_END_DICT, day_to_str(day))
+}
+
+pub fn snapshots(day: U256) -> DictionaryItem<SnapShot> {
+    DictionaryItem::new(SNAPSHOTS_DICT, day_to_str(day))
+}
+
+pub fn rsnapshots(day: U256) -> DictionaryItem<RSnapShot> {
+    DictionaryItem::new(RSNAPSHOTS_DICT, day_to_str(day))
+}
+
+pub fn lsnapshots(day: U256) -> DictionaryItem<LSnapShot> {
+    DictionaryItem::new(LSNAPSHOTS_DICT, day_to_str(day))
+}
+
+pub fn tsnapshots(day: U256) -> DictionaryItem<TSnapShot> {
+    DictionaryItem::new(TSNAPSHOTS_DICT, day_to_str(day))
+}
+
+// by name
+pub fn parameters(name: &str) -> DictionaryItem<U256> {
+    DictionaryItem::new(PARAMETERS_DICT, name.to_string())
+}
+
+pub fn pending_parameters(name: &str) -> DictionaryItem<ParameterProposal> {
+    DictionaryItem::new(PENDING_PARAMETERS_DICT, name.to_string())
+}
+
+pub fn referral_codes(code: &str) -> DictionaryItem<Key> {
+    DictionaryItem::new(REFERRAL_CODES_DICT, code.to_string())
+}
+
+pub fn paused(operation: &str) -> DictionaryItem<bool> {
+    DictionaryItem::new(PAUSED_DICT, operation.to_string())
+}
+
+pub fn roles(role: &str, account: &Key) -> DictionaryItem<bool> {
+    DictionaryItem::new(ROLES_DICT, values_to_str((role.to_string(), *account)))
+}
+
+pub fn balances(owner: &Key) -> DictionaryItem<U256> {
+    DictionaryItem::new(BALANCES_DICT, key_to_str(owner))
+}
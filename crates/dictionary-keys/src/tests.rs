@@ -0,0 +1,66 @@
+use crate::*;
+use casper_types::{account::AccountHash, bytesrepr::ToBytes, Key, U256};
+use snapshot::data::SnapShot;
+
+fn account() -> Key {
+    Key::Account(AccountHash::new([1; 32]))
+}
+
+#[test]
+fn should_encode_keys_like_get_by_key() {
+    assert_eq!(key_to_str(&account()), "01".repeat(32));
+    assert_eq!(key_to_str(&Key::Hash([2; 32])), "02".repeat(32));
+}
+
+#[test]
+fn should_hash_tuples_like_get_by_values() {
+    // blake2b-256 of the serialized (Key::Account([1; 32]), vec![1u32, 2])
+    assert_eq!(
+        stakes(&account(), &[1, 2]).item_key,
+        "6aa938235c0258208aebf8ce9c566481b9474523a3256dd09eb73bf84fc2d188"
+    );
+    // blake2b-256 of the serialized ("admin", Key::Hash([2; 32]))
+    assert_eq!(
+        roles("admin", &Key::Hash([2; 32])).item_key,
+        "cdb8102668198028977fa27d44a1ff5d4e82e5f7ab045018737f6fd8dd2825b8"
+    );
+    assert_eq!(
+        stakes(&account(), &[1, 2]).item_key,
+        scrapes(&account(), &[1, 2]).item_key
+    );
+    assert_eq!(
+        stakes(&account(), &[1, 2]).item_key,
+        legacy_stakes(&account(), &[1, 2]).item_key
+    );
+    assert_eq!(
+        stakes(&account(), &[1, 2]).dictionary_name,
+        "stakes_v11_dict"
+    );
+    assert_eq!(
+        legacy_stakes(&account(), &[1, 2]).dictionary_name,
+        "stakes_dict"
+    );
+}
+
+#[test]
+fn should_use_decimal_days() {
+    assert_eq!(snapshots(U256::from(365)).item_key, "365");
+    assert_eq!(scheduled_to_end(U256::zero()).item_key, "0");
+}
+
+#[test]
+fn should_decode_stored_values() {
+    let snapshot = SnapShot {
+        total_shares: 1_002_739_726_000u64.into(),
+        inflation_amount: 2_747_000u64.into(),
+        scheduled_to_end: 0.into(),
+    };
+    let bytes: Vec<u8> = snapshot.to_bytes().unwrap();
+    let item = snapshots(U256::from(3));
+    let decoded: SnapShot = item.decode(&bytes).unwrap();
+    assert_eq!(decoded.total_shares, snapshot.total_shares);
+    assert_eq!(decoded.inflation_amount, snapshot.inflation_amount);
+    let decoded: SnapShot = item.decode_hex(&hex::encode(&bytes)).unwrap();
+    assert_eq!(decoded.total_shares, snapshot.total_shares);
+    assert!(item.decode(&bytes[1..]).is_err());
+}
@@ -0,0 +1,42 @@
+use casper_types::U256;
+
+pub const FORMULA_DAY: u16 = 25;
+pub const MAX_BONUS_DAYS_A: u16 = 1825;
+pub const MAX_BONUS_DAYS_B: u16 = 13505;
+pub const MIN_REFERRAL_DAYS: u16 = 365;
+
+pub const REFERRALS_RATE: u32 = 366_816_973;
+pub const INFLATION_RATE: u32 = 103000; // 3.000% (indirect -> checks throgh LiquidityGuard)
+pub const LIQUIDITY_RATE: u32 = 100006; // 3.000% (indirect -> checks throgh LiquidityGuard)
+pub const INFLATION_RATE_MAX: u32 = 103000;
+pub const LIQUIDITY_RATE_STEP: u32 = 6; // 0.006% moved between regular and liquidity shares per day
+
+pub const PRECISION_RATE: u64 = 1_000_000_000; // 1E9
+pub const BONUS_PRECISION: u64 = 10_000_000_000; // 1E10, bonus of a stake without referrer
+pub const REFERRAL_BONUS: u64 = 11_000_000_000; // 10% extra shares for stakes with a referrer
+pub const SHARE_PRICE_PRECISION: u64 = 100_000_000; // 1E8
+pub const TIER_RATE_PRECISION: u32 = 10_000;
+
+pub const DAILY_BONUS_A: u128 = 13698630136986302; // 25%:1825 = 0.01369863013 per day;
+pub const DAILY_BONUS_B: u128 = 370233246945575; // 5%:13505 = 0.00037023324 per day;
+
+pub const INITIAL_SHARE_PRICE: u64 = 100_000_000; // 100E15 <=> 100E6 == 0.1 WISE
+pub const FORMULA_DAY_SHARE_PRICE: u64 = 110_000_000; // 110E15 <=> 110E6
+pub const SHARE_PRICE_CAP: u32 = 110; // share price can increase by 10% per update
+
+/// Reason a calculation failed, `Overflow` also covers subtractions going below zero
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MathError {
+    Overflow,
+    DivisionByZero,
+}
+
+pub type MathResult = Result<U256, MathError>;
+
+/// Values stored for a day in one of the snapshot pools
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolSnapshot {
+    pub total_shares: U256,
+    pub inflation_amount: U256,
+    pub scheduled_to_end: U256,
+}
@@ -0,0 +1,11 @@
+#![no_std]
+
+pub mod data;
+pub mod simulator;
+pub mod src;
+
+pub use data::*;
+pub use src::*;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,392 @@
+extern crate alloc;
+use crate::{data::*, src::*};
+use alloc::{collections::BTreeMap, vec::Vec};
+use casper_types::U256;
+
+/// Inflation divisors the liquidity guard returns for a rate, see its `get_inflation`
+pub trait InflationTable {
+    fn get_inflation(&self, rate: u32) -> U256;
+}
+
+impl<F: Fn(u32) -> U256> InflationTable for F {
+    fn get_inflation(&self, rate: u32) -> U256 {
+        self(rate)
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimulatedStake {
+    pub staked_amount: U256,
+    pub stakes_shares: U256,
+    pub referrer_shares: U256,
+    pub tier_shares: U256,
+    pub reward_amount: U256,
+    pub penalty_amount: U256,
+    pub start_day: u64,
+    pub lock_days: u64,
+    pub final_day: u64,
+    pub close_day: u64,
+    pub has_referrer: bool,
+    pub is_active: bool,
+}
+
+/// In-memory model of the staking pools running the same formulas as the contract.
+///
+/// `day` is the current stakeable day and snapshots are kept up to date with it, so
+/// `snapshots[d]` is the regular snapshot of day `d`. Referrers are not modelled, tier
+/// shares are booked through `add_tier_links` with the rates of the active uplines.
+/// Scraping and liquidity stakes are not modelled, `liquidity_shares` can be set directly.
+pub struct Simulator<T: InflationTable> {
+    pub day: u64,
+    pub total_supply: U256,
+    pub total_staked: U256,
+    pub total_shares: U256,
+    pub referral_shares: U256,
+    pub tier_shares: U256,
+    pub liquidity_shares: U256,
+    pub share_price: U256,
+    pub inflation_rate: u32,
+    pub liquidity_rate: u32,
+    pub referrals_rate: U256,
+    pub formula_day: u64,
+    pub share_price_cap: U256,
+    pub is_liquidity_guard_active: bool,
+    pub snapshots: Vec<PoolSnapshot>,
+    pub rsnapshots: Vec<PoolSnapshot>,
+    pub tsnapshots: Vec<PoolSnapshot>,
+    pub lsnapshots: Vec<PoolSnapshot>,
+    pub stakes: Vec<SimulatedStake>,
+    scheduled_to_end: BTreeMap<u64, U256>,
+    referral_shares_to_end: BTreeMap<u64, U256>,
+    tier_shares_to_end: BTreeMap<u64, U256>,
+    total_penalties: BTreeMap<u64, U256>,
+    inflation: T,
+}
+
+impl<T: InflationTable> Simulator<T> {
+    /// @notice starts at day 0 with the default parameters of the contract
+    pub fn new(total_supply: U256, inflation: T) -> Simulator<T> {
+        Simulator {
+            day: 0,
+            total_supply,
+            total_staked: 0.into(),
+            total_shares: 0.into(),
+            referral_shares: 0.into(),
+            tier_shares: 0.into(),
+            liquidity_shares: 0.into(),
+            share_price: INITIAL_SHARE_PRICE.into(),
+            inflation_rate: INFLATION_RATE,
+            liquidity_rate: LIQUIDITY_RATE,
+            referrals_rate: REFERRALS_RATE.into(),
+            formula_day: FORMULA_DAY.into(),
+            share_price_cap: SHARE_PRICE_CAP.into(),
+            is_liquidity_guard_active: false,
+            snapshots: Vec::new(),
+            rsnapshots: Vec::new(),
+            tsnapshots: Vec::new(),
+            lsnapshots: Vec::new(),
+            stakes: Vec::new(),
+            scheduled_to_end: BTreeMap::new(),
+            referral_shares_to_end: BTreeMap::new(),
+            tier_shares_to_end: BTreeMap::new(),
+            total_penalties: BTreeMap::new(),
+            inflation,
+        }
+    }
+
+    /// @notice moves time forward and takes the snapshots of the passed days
+    pub fn advance_days(&mut self, days: u64) -> Result<(), MathError> {
+        self.day += days;
+        self.daily_snapshot_point()
+    }
+
+    /// @notice books a stake starting the next day, returns its index in `stakes`
+    /// @param has_referrer stake was created with a referrer, it gets the referral bonus
+    /// @param referrer_active referrer has reached critical mass and earns referrer shares
+    pub fn create_stake(
+        &mut self,
+        staked_amount: U256,
+        lock_days: u64,
+        has_referrer: bool,
+        referrer_active: bool,
+    ) -> Result<usize, MathError> {
+        self.total_supply = self
+            .total_supply
+            .checked_sub(staked_amount)
+            .ok_or(MathError::Overflow)?;
+        let start_day: u64 = self.day + 1;
+        let stake = SimulatedStake {
+            staked_amount,
+            stakes_shares: stakes_shares(
+                staked_amount,
+                lock_days.into(),
+                self.share_price,
+                has_referrer,
+            )?,
+            referrer_shares: if has_referrer && referrer_active {
+                referrer_shares(staked_amount, lock_days.into(), self.share_price)?
+            } else {
+                0.into()
+            },
+            start_day,
+            lock_days,
+            final_day: start_day + lock_days,
+            has_referrer,
+            is_active: true,
+            ..Default::default()
+        };
+        Self::add_to_day(
+            &mut self.scheduled_to_end,
+            stake.final_day,
+            stake.stakes_shares,
+        )?;
+        Self::add_to_day(
+            &mut self.referral_shares_to_end,
+            stake.final_day,
+            stake.referrer_shares,
+        )?;
+        self.total_staked = self
+            .total_staked
+            .checked_add(staked_amount)
+            .ok_or(MathError::Overflow)?;
+        self.total_shares = self
+            .total_shares
+            .checked_add(stake.stakes_shares)
+            .ok_or(MathError::Overflow)?;
+        self.referral_shares = self
+            .referral_shares
+            .checked_add(stake.referrer_shares)
+            .ok_or(MathError::Overflow)?;
+        self.stakes.push(stake);
+        Ok(self.stakes.len() - 1)
+    }
+
+    /// @notice books the tier shares of a stake that was just created, like the contract
+    /// does for the uplines of its referrer, returns the total tier shares of the stake
+    /// @param tier_rates rate in basis points of each upline from tier 2 on that has
+    /// reached critical mass
+    pub fn add_tier_links(&mut self, index: usize, tier_rates: &[u32]) -> MathResult {
+        let mut stake: SimulatedStake = self.stakes[index];
+        let mut total_tier_shares: U256 = 0.into();
+        for tier_rate in tier_rates {
+            total_tier_shares = total_tier_shares
+                .checked_add(tier_shares_amount(
+                    stake.referrer_shares,
+                    (*tier_rate).into(),
+                )?)
+                .ok_or(MathError::Overflow)?;
+        }
+        Self::add_to_day(
+            &mut self.tier_shares_to_end,
+            stake.final_day,
+            total_tier_shares,
+        )?;
+        self.tier_shares = self
+            .tier_shares
+            .checked_add(total_tier_shares)
+            .ok_or(MathError::Overflow)?;
+        stake.tier_shares = stake
+            .tier_shares
+            .checked_add(total_tier_shares)
+            .ok_or(MathError::Overflow)?;
+        self.stakes[index] = stake;
+        Ok(stake.tier_shares)
+    }
+
+    /// @notice ends a stake today, returns its reward and penalty
+    pub fn end_stake(&mut self, index: usize) -> Result<(U256, U256), MathError> {
+        let mut stake: SimulatedStake = self.stakes[index];
+        if !stake.is_active {
+            return Ok((0.into(), 0.into()));
+        }
+        stake.close_day = self.day;
+        stake.reward_amount = self.reward_of(&stake)?;
+        stake.penalty_amount =
+            if stake.start_day > stake.close_day || stake.final_day <= stake.close_day {
+                0.into()
+            } else {
+                penalties(
+                    stake.staked_amount,
+                    (stake.final_day - stake.close_day).into(),
+                    stake.lock_days.into(),
+                )?
+            };
+        stake.is_active = false;
+        let returned: U256 = stake.staked_amount.saturating_sub(stake.penalty_amount);
+        self.total_supply = self
+            .total_supply
+            .checked_add(returned)
+            .and_then(|supply| supply.checked_add(stake.reward_amount))
+            .ok_or(MathError::Overflow)?;
+        self.total_staked = self.total_staked.saturating_sub(stake.staked_amount);
+        self.total_shares = self.total_shares.saturating_sub(stake.stakes_shares);
+        self.referral_shares = self.referral_shares.saturating_sub(stake.referrer_shares);
+        self.tier_shares = self.tier_shares.saturating_sub(stake.tier_shares);
+        if stake.final_day >= self.day {
+            Self::remove_from_day(
+                &mut self.scheduled_to_end,
+                stake.final_day,
+                stake.stakes_shares,
+            );
+            Self::remove_from_day(
+                &mut self.referral_shares_to_end,
+                stake.final_day,
+                stake.referrer_shares,
+            );
+            Self::remove_from_day(
+                &mut self.tier_shares_to_end,
+                stake.final_day,
+                stake.tier_shares,
+            );
+        } else if let Some(previous) = self.day.checked_sub(1) {
+            let snapshot = &mut self.snapshots[previous as usize];
+            snapshot.scheduled_to_end = snapshot
+                .scheduled_to_end
+                .saturating_sub(stake.stakes_shares);
+            let rsnapshot = &mut self.rsnapshots[previous as usize];
+            rsnapshot.scheduled_to_end = rsnapshot
+                .scheduled_to_end
+                .saturating_sub(stake.referrer_shares);
+            let tsnapshot = &mut self.tsnapshots[previous as usize];
+            tsnapshot.scheduled_to_end =
+                tsnapshot.scheduled_to_end.saturating_sub(stake.tier_shares);
+        }
+        Self::add_to_day(
+            &mut self.total_penalties,
+            stake.close_day,
+            stake.penalty_amount,
+        )?;
+        self.share_price_update(
+            returned,
+            stake.reward_amount,
+            stake.has_referrer,
+            stake.lock_days.into(),
+            stake.stakes_shares,
+        )?;
+        self.stakes[index] = stake;
+        Ok((stake.reward_amount, stake.penalty_amount))
+    }
+
+    /// @notice reward an active stake has earned so far, or the reward it ended with
+    pub fn reward_of(&self, stake: &SimulatedStake) -> MathResult {
+        if !stake.is_active {
+            return Ok(stake.reward_amount);
+        }
+        let calculation_day: u64 = stake.final_day.min(self.snapshots.len() as u64);
+        if stake.start_day >= calculation_day {
+            return Ok(0.into());
+        }
+        reward_amount(
+            stake.stakes_shares,
+            self.snapshots[stake.start_day as usize..calculation_day as usize]
+                .iter()
+                .map(|snapshot| snapshot.inflation_amount),
+        )
+    }
+
+    fn share_price_update(
+        &mut self,
+        staked_amount: U256,
+        reward_amount: U256,
+        has_referrer: bool,
+        lock_days: U256,
+        stake_shares: U256,
+    ) -> Result<(), MathError> {
+        if stake_shares > 0.into() && self.day > self.formula_day {
+            let price: U256 = new_share_price(
+                staked_amount,
+                reward_amount,
+                stake_shares,
+                lock_days,
+                has_referrer,
+            )?;
+            if price > self.share_price {
+                self.share_price =
+                    capped_share_price(self.share_price, price, self.share_price_cap)?;
+            }
+        } else if self.day == self.formula_day {
+            self.share_price = FORMULA_DAY_SHARE_PRICE.into();
+        }
+        Ok(())
+    }
+
+    fn daily_snapshot_point(&mut self) -> Result<(), MathError> {
+        while (self.snapshots.len() as u64) < self.day {
+            let day: u64 = self.snapshots.len() as u64;
+            let previous: Option<usize> = (day as usize).checked_sub(1);
+            let snapshot: PoolSnapshot = daily_snapshot(
+                self.total_shares,
+                Self::day_value(&self.scheduled_to_end, day),
+                previous.map_or(0.into(), |p| self.snapshots[p].scheduled_to_end),
+                inflation_amount(
+                    self.total_staked,
+                    self.total_supply,
+                    Self::day_value(&self.total_penalties, day),
+                    self.inflation.get_inflation(self.inflation_rate),
+                )?,
+            )?;
+            let mut rsnapshot: PoolSnapshot = daily_snapshot(
+                self.referral_shares,
+                Self::day_value(&self.referral_shares_to_end, day),
+                previous.map_or(0.into(), |p| self.rsnapshots[p].scheduled_to_end),
+                pool_inflation(self.total_staked, self.total_supply, self.referrals_rate)?,
+            )?;
+            let mut tsnapshot: PoolSnapshot = daily_snapshot(
+                self.tier_shares,
+                Self::day_value(&self.tier_shares_to_end, day),
+                previous.map_or(0.into(), |p| self.tsnapshots[p].scheduled_to_end),
+                pool_inflation(self.total_staked, self.total_supply, self.referrals_rate)?,
+            )?;
+            // tiers are funded from the referral inflation, both pools split it by their shares
+            rsnapshot.inflation_amount = rsnapshot
+                .inflation_amount
+                .checked_add(tsnapshot.inflation_amount)
+                .ok_or(MathError::Overflow)?;
+            tsnapshot.inflation_amount = rsnapshot.inflation_amount;
+            let lsnapshot: PoolSnapshot = daily_snapshot(
+                self.liquidity_shares,
+                0.into(),
+                0.into(),
+                pool_inflation(
+                    self.total_staked,
+                    self.total_supply,
+                    self.inflation.get_inflation(self.liquidity_rate),
+                )?,
+            )?;
+            self.snapshots.push(snapshot);
+            self.rsnapshots.push(rsnapshot);
+            self.tsnapshots.push(tsnapshot);
+            self.lsnapshots.push(lsnapshot);
+            if let Some((liquidity_rate, inflation_rate)) = adjust_liquidity_rates(
+                self.is_liquidity_guard_active,
+                self.liquidity_rate,
+                self.inflation_rate,
+            ) {
+                self.liquidity_rate = liquidity_rate;
+                self.inflation_rate = inflation_rate;
+            }
+        }
+        Ok(())
+    }
+
+    fn day_value(values: &BTreeMap<u64, U256>, day: u64) -> U256 {
+        values.get(&day).copied().unwrap_or_default()
+    }
+
+    fn add_to_day(
+        values: &mut BTreeMap<u64, U256>,
+        day: u64,
+        amount: U256,
+    ) -> Result<(), MathError> {
+        let value: U256 = Self::day_value(values, day)
+            .checked_add(amount)
+            .ok_or(MathError::Overflow)?;
+        values.insert(day, value);
+        Ok(())
+    }
+
+    fn remove_from_day(values: &mut BTreeMap<u64, U256>, day: u64, amount: U256) {
+        let value: U256 = Self::day_value(values, day).saturating_sub(amount);
+        values.insert(day, value);
+    }
+}
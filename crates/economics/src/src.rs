@@ -0,0 +1,235 @@
+use crate::data::*;
+use casper_types::U256;
+
+fn add(a: U256, b: U256) -> MathResult {
+    a.checked_add(b).ok_or(MathError::Overflow)
+}
+
+fn sub(a: U256, b: U256) -> MathResult {
+    a.checked_sub(b).ok_or(MathError::Overflow)
+}
+
+fn mul(a: U256, b: U256) -> MathResult {
+    a.checked_mul(b).ok_or(MathError::Overflow)
+}
+
+fn div(a: U256, b: U256) -> MathResult {
+    a.checked_div(b).ok_or(MathError::DivisionByZero)
+}
+
+/// @notice bonus factor of the shares before the lock days are taken into account
+pub fn extra_bonus(has_referrer: bool) -> U256 {
+    if has_referrer {
+        REFERRAL_BONUS.into()
+    } else {
+        BONUS_PRECISION.into()
+    }
+}
+
+pub fn regular_bonus(lock_days: U256, daily: U256, max_days: U256) -> MathResult {
+    div(mul(lock_days.min(max_days), daily)?, BONUS_PRECISION.into())
+}
+
+/// @notice bonus of the first `MAX_BONUS_DAYS_A` lock days
+pub fn regular_bonus_a(lock_days: U256) -> MathResult {
+    regular_bonus(lock_days, DAILY_BONUS_A.into(), MAX_BONUS_DAYS_A.into())
+}
+
+/// @notice bonus of the lock days past `MAX_BONUS_DAYS_A`
+pub fn regular_bonus_b(lock_days: U256) -> MathResult {
+    regular_bonus(
+        lock_days.saturating_sub(MAX_BONUS_DAYS_A.into()),
+        DAILY_BONUS_B.into(),
+        MAX_BONUS_DAYS_B.into(),
+    )
+}
+
+pub fn get_bonus(lock_days: U256, extra_bonus: U256) -> MathResult {
+    add(
+        add(regular_bonus_a(lock_days)?, regular_bonus_b(lock_days)?)?,
+        extra_bonus,
+    )
+}
+
+pub fn base_amount(staked_amount: U256, share_price: U256) -> MathResult {
+    div(mul(staked_amount, PRECISION_RATE.into())?, share_price)
+}
+
+pub fn shares_amount(
+    staked_amount: U256,
+    lock_days: U256,
+    share_price: U256,
+    extra_bonus: U256,
+) -> MathResult {
+    div(
+        mul(
+            base_amount(staked_amount, share_price)?,
+            get_bonus(lock_days, extra_bonus)?,
+        )?,
+        BONUS_PRECISION.into(),
+    )
+}
+
+pub fn stakes_shares(
+    staked_amount: U256,
+    lock_days: U256,
+    share_price: U256,
+    has_referrer: bool,
+) -> MathResult {
+    shares_amount(
+        staked_amount,
+        lock_days,
+        share_price,
+        extra_bonus(has_referrer),
+    )
+}
+
+/// @notice shares of the referrer of a stake, the referrer is expected to have reached critical mass
+pub fn referrer_shares(staked_amount: U256, lock_days: U256, share_price: U256) -> MathResult {
+    if lock_days < MIN_REFERRAL_DAYS.into() {
+        return Ok(0.into());
+    }
+    shares_amount(
+        staked_amount,
+        lock_days,
+        share_price,
+        BONUS_PRECISION.into(),
+    )
+}
+
+/// @notice shares an upline earns from the referrer shares of a stake at a tier rate in basis points
+pub fn tier_shares_amount(referrer_shares: U256, tier_rate: U256) -> MathResult {
+    div(mul(referrer_shares, tier_rate)?, TIER_RATE_PRECISION.into())
+}
+
+/// @notice tier shares a scrape forfeits, in the ratio of the referrer penalty to the referrer shares
+pub fn tier_shares_penalty(
+    tier_shares: U256,
+    referrer_penalty: U256,
+    referrer_shares: U256,
+) -> MathResult {
+    div(mul(tier_shares, referrer_penalty)?, referrer_shares)
+}
+
+/// @notice share price at which an ended or scraped stake would have been worth its shares
+pub fn new_share_price(
+    staked_amount: U256,
+    reward_amount: U256,
+    stake_shares: U256,
+    lock_days: U256,
+    has_referrer: bool,
+) -> MathResult {
+    div(
+        mul(
+            mul(
+                add(staked_amount, reward_amount)?,
+                get_bonus(lock_days, extra_bonus(has_referrer))?,
+            )?,
+            SHARE_PRICE_PRECISION.into(),
+        )?,
+        stake_shares,
+    )
+}
+
+/// @notice limits a share price increase to `share_price_cap` percent of the current price
+pub fn capped_share_price(
+    share_price: U256,
+    new_share_price: U256,
+    share_price_cap: U256,
+) -> MathResult {
+    let max_share_price: U256 = div(mul(share_price, share_price_cap)?, 100.into())?;
+    Ok(new_share_price.min(max_share_price))
+}
+
+/// @notice penalty of a stake ended `days_left` days before its final day
+pub fn penalties(staked_amount: U256, days_left: U256, lock_days: U256) -> MathResult {
+    let lock_days: U256 = if lock_days > 1.into() {
+        lock_days - 1
+    } else {
+        1.into()
+    };
+    div(
+        mul(
+            staked_amount,
+            add(
+                100.into(),
+                div(mul(800.into(), sub(days_left, 1.into())?)?, lock_days)?,
+            )?,
+        )?,
+        1000.into(),
+    )
+}
+
+/// @notice sum of the daily rewards of `stake_shares` over the inflation amounts of the snapshots
+pub fn reward_amount<I>(stake_shares: U256, inflation_amounts: I) -> MathResult
+where
+    I: IntoIterator<Item = U256>,
+{
+    let mut reward_amount: U256 = 0.into();
+    for inflation_amount in inflation_amounts {
+        reward_amount = add(
+            reward_amount,
+            div(mul(stake_shares, PRECISION_RATE.into())?, inflation_amount)?,
+        )?;
+    }
+    Ok(reward_amount)
+}
+
+pub fn inflation_amount(
+    total_staked: U256,
+    total_supply: U256,
+    total_penalties: U256,
+    inflation: U256,
+) -> MathResult {
+    add(
+        pool_inflation(total_staked, total_supply, inflation)?,
+        total_penalties,
+    )
+}
+
+/// @notice inflation divisor of the referral and liquidity pools
+pub fn pool_inflation(total_staked: U256, total_supply: U256, rate: U256) -> MathResult {
+    div(mul(add(total_staked, total_supply)?, 10000.into())?, rate)
+}
+
+/// @notice snapshot of a pool for a day
+/// @param total_shares shares of the pool including the ones scheduled to end
+/// @param ending_today shares whose final day is the snapshot day
+/// @param previous_scheduled_to_end shares ended before, as stored in the previous snapshot
+/// @param inflation divisor returned by `inflation_amount` or `pool_inflation`
+pub fn daily_snapshot(
+    total_shares: U256,
+    ending_today: U256,
+    previous_scheduled_to_end: U256,
+    inflation: U256,
+) -> Result<PoolSnapshot, MathError> {
+    let scheduled_to_end: U256 = add(ending_today, previous_scheduled_to_end)?;
+    let total_shares: U256 = total_shares.saturating_sub(scheduled_to_end);
+    Ok(PoolSnapshot {
+        total_shares,
+        inflation_amount: div(mul(total_shares, PRECISION_RATE.into())?, inflation)?,
+        scheduled_to_end,
+    })
+}
+
+/// @notice moves inflation by `LIQUIDITY_RATE_STEP` between regular and liquidity shares,
+/// returns the new liquidity and inflation rate or None when the rates stay as they are
+pub fn adjust_liquidity_rates(
+    is_liquidity_guard_active: bool,
+    liquidity_rate: u32,
+    inflation_rate: u32,
+) -> Option<(u32, u32)> {
+    if is_liquidity_guard_active && liquidity_rate < INFLATION_RATE_MAX {
+        return Some((
+            liquidity_rate + LIQUIDITY_RATE_STEP,
+            inflation_rate - LIQUIDITY_RATE_STEP,
+        ));
+    }
+    if !is_liquidity_guard_active && inflation_rate < INFLATION_RATE_MAX {
+        return Some((
+            liquidity_rate - LIQUIDITY_RATE_STEP,
+            inflation_rate + LIQUIDITY_RATE_STEP,
+        ));
+    }
+    None
+}
@@ -0,0 +1,213 @@
+use crate::{simulator::Simulator, *};
+use casper_types::U256;
+
+const ONEHUNDRED_CSPR: u64 = 100_000_000_000;
+
+fn inflation(_rate: u32) -> U256 {
+    U256::from(2_747_000u64)
+}
+
+#[test]
+fn should_calculate_stakes_shares_like_the_contract() {
+    // same value `quote_stake` returns in the contract tests
+    assert_eq!(
+        stakes_shares(
+            ONEHUNDRED_CSPR.into(),
+            20.into(),
+            INITIAL_SHARE_PRICE.into(),
+            false
+        ),
+        Ok(U256::from(1_002_739_726_000u64))
+    );
+    assert!(
+        stakes_shares(
+            ONEHUNDRED_CSPR.into(),
+            20.into(),
+            INITIAL_SHARE_PRICE.into(),
+            true
+        )
+        .unwrap()
+            > U256::from(1_002_739_726_000u64)
+    );
+}
+
+#[test]
+fn should_cap_regular_bonus_at_max_days() {
+    let max_lock_days: U256 = (MAX_BONUS_DAYS_A + MAX_BONUS_DAYS_B).into();
+    assert_eq!(
+        regular_bonus_a(max_lock_days + 100),
+        regular_bonus_a(MAX_BONUS_DAYS_A.into())
+    );
+    assert_eq!(
+        regular_bonus_b(max_lock_days + 100),
+        regular_bonus_b(max_lock_days)
+    );
+    assert_eq!(regular_bonus_b(MAX_BONUS_DAYS_A.into()), Ok(0.into()));
+}
+
+#[test]
+fn should_only_give_referrer_shares_for_long_stakes() {
+    assert_eq!(
+        referrer_shares(
+            ONEHUNDRED_CSPR.into(),
+            (MIN_REFERRAL_DAYS - 1).into(),
+            INITIAL_SHARE_PRICE.into()
+        ),
+        Ok(0.into())
+    );
+    assert!(
+        referrer_shares(
+            ONEHUNDRED_CSPR.into(),
+            MIN_REFERRAL_DAYS.into(),
+            INITIAL_SHARE_PRICE.into()
+        )
+        .unwrap()
+            > 0.into()
+    );
+}
+
+#[test]
+fn should_calculate_penalties() {
+    // ended before the first day, 90% is kept back
+    assert_eq!(
+        penalties(ONEHUNDRED_CSPR.into(), 20.into(), 20.into()),
+        Ok(U256::from(90_000_000_000u64))
+    );
+    // ended on the last day, only the base 10%
+    assert_eq!(
+        penalties(ONEHUNDRED_CSPR.into(), 1.into(), 20.into()),
+        Ok(U256::from(10_000_000_000u64))
+    );
+    assert_eq!(
+        penalties(ONEHUNDRED_CSPR.into(), 0.into(), 20.into()),
+        Err(MathError::Overflow)
+    );
+}
+
+#[test]
+fn should_forfeit_tier_shares_with_the_referrer_penalty() {
+    // a quarter of the referrer shares forfeited takes a quarter of the tier shares
+    assert_eq!(
+        tier_shares_penalty(300.into(), 250.into(), 1_000.into()),
+        Ok(U256::from(75))
+    );
+    assert_eq!(
+        tier_shares_penalty(300.into(), 1_000.into(), 1_000.into()),
+        Ok(U256::from(300))
+    );
+    assert_eq!(
+        tier_shares_penalty(300.into(), 0.into(), 0.into()),
+        Err(MathError::DivisionByZero)
+    );
+}
+
+#[test]
+fn should_report_division_by_zero() {
+    assert_eq!(
+        base_amount(ONEHUNDRED_CSPR.into(), 0.into()),
+        Err(MathError::DivisionByZero)
+    );
+    assert_eq!(
+        reward_amount(1.into(), [U256::from(1), U256::from(0)]),
+        Err(MathError::DivisionByZero)
+    );
+}
+
+#[test]
+fn should_cap_share_price_increase() {
+    assert_eq!(
+        capped_share_price(100.into(), 200.into(), SHARE_PRICE_CAP.into()),
+        Ok(110.into())
+    );
+    assert_eq!(
+        capped_share_price(100.into(), 105.into(), SHARE_PRICE_CAP.into()),
+        Ok(105.into())
+    );
+}
+
+#[test]
+fn should_move_inflation_to_liquidity_shares_while_guard_is_active() {
+    assert_eq!(
+        adjust_liquidity_rates(true, LIQUIDITY_RATE, INFLATION_RATE),
+        Some((LIQUIDITY_RATE + 6, INFLATION_RATE - 6))
+    );
+    assert_eq!(
+        adjust_liquidity_rates(false, LIQUIDITY_RATE, INFLATION_RATE),
+        None
+    );
+}
+
+#[test]
+fn should_take_snapshots_and_subtract_ended_shares() {
+    let snapshot: PoolSnapshot =
+        daily_snapshot(1000.into(), 100.into(), 50.into(), 10.into()).unwrap();
+    assert_eq!(snapshot.scheduled_to_end, 150.into());
+    assert_eq!(snapshot.total_shares, 850.into());
+    assert_eq!(
+        snapshot.inflation_amount,
+        U256::from(850) * PRECISION_RATE / 10
+    );
+}
+
+#[test]
+fn should_simulate_a_mature_stake() {
+    let mut simulator = Simulator::new(U256::from(ONEHUNDRED_CSPR) * 1000, inflation);
+    let supply: U256 = simulator.total_supply;
+    let index: usize = simulator
+        .create_stake(ONEHUNDRED_CSPR.into(), 20, false, false)
+        .unwrap();
+    assert_eq!(simulator.total_supply, supply - ONEHUNDRED_CSPR);
+    simulator.advance_days(30).unwrap();
+    assert_eq!(simulator.snapshots.len(), 30);
+    let (reward, penalty) = simulator.end_stake(index).unwrap();
+    assert!(reward > 0.into(), "No reward");
+    assert_eq!(penalty, 0.into());
+    assert_eq!(simulator.total_supply, supply + reward);
+    assert_eq!(simulator.total_shares, 0.into());
+    assert!(!simulator.stakes[index].is_active);
+}
+
+#[test]
+fn should_simulate_an_early_end() {
+    let mut simulator = Simulator::new(U256::from(ONEHUNDRED_CSPR) * 1000, inflation);
+    let supply: U256 = simulator.total_supply;
+    let index: usize = simulator
+        .create_stake(ONEHUNDRED_CSPR.into(), 20, true, true)
+        .unwrap();
+    assert_eq!(simulator.referral_shares, 0.into(), "Lock too short");
+    simulator.advance_days(5).unwrap();
+    assert!(simulator.snapshots[4].total_shares > 0.into());
+    let (reward, penalty) = simulator.end_stake(index).unwrap();
+    assert!(penalty > 0.into(), "No penalty");
+    assert_eq!(simulator.total_supply, supply - penalty + reward);
+    simulator.advance_days(1).unwrap();
+    assert_eq!(simulator.snapshots[5].total_shares, 0.into());
+}
+
+#[test]
+fn should_simulate_tier_links_funded_from_referral_inflation() {
+    let mut simulator = Simulator::new(U256::from(ONEHUNDRED_CSPR) * 1000, inflation);
+    let index: usize = simulator
+        .create_stake(ONEHUNDRED_CSPR.into(), 365, true, true)
+        .unwrap();
+    let referrer_shares: U256 = simulator.stakes[index].referrer_shares;
+    let tier_shares: U256 = simulator.add_tier_links(index, &[2_000, 1_000]).unwrap();
+    assert_eq!(tier_shares, referrer_shares * 3_000 / TIER_RATE_PRECISION);
+    simulator.advance_days(2).unwrap();
+    let rsnapshot: PoolSnapshot = simulator.rsnapshots[1];
+    let tsnapshot: PoolSnapshot = simulator.tsnapshots[1];
+    assert_eq!(tsnapshot.total_shares, tier_shares);
+    assert_eq!(tsnapshot.inflation_amount, rsnapshot.inflation_amount);
+    // both pools together pay out what the referral pool alone would
+    let paid_out: U256 = rsnapshot.total_shares * PRECISION_RATE / rsnapshot.inflation_amount
+        + tsnapshot.total_shares * PRECISION_RATE / tsnapshot.inflation_amount;
+    let referral_inflation: U256 = pool_inflation(
+        simulator.total_staked,
+        simulator.total_supply,
+        REFERRALS_RATE.into(),
+    )
+    .unwrap();
+    assert!(paid_out <= referral_inflation && paid_out + 2 >= referral_inflation);
+    simulator.end_stake(index).unwrap();
+    assert_eq!(simulator.tier_shares, 0.into());
+}
@@ -0,0 +1,282 @@
+use crate::data::*;
+use casper_types::bytesrepr::{self, Bytes, FromBytes};
+use common::events::{self, Events};
+
+/// Parses one event of the `__events` dictionary, `bytes` are the event without the
+/// `Bytes` length prefix of the stored CLValue
+pub fn decode_ces(bytes: &[u8]) -> Result<Events, DecodeError> {
+    let (name, _): (String, _) = String::from_bytes(bytes)?;
+    let name: &str = name.strip_prefix(CES_EVENT_PREFIX).unwrap_or(&name);
+    Ok(match name {
+        "StakeStart" => {
+            let event: events::StakeStart = bytesrepr::deserialize(bytes.to_vec())?;
+            Events::StakeStart {
+                stake_id: event.stake_id,
+                staker_address: event.staker_address,
+                referral_address: event.referral_address,
+                staked_amount: event.staked_amount,
+                stakes_shares: event.stakes_shares,
+                referral_shares: event.referral_shares,
+                start_day: event.start_day,
+                lock_days: event.lock_days,
+                dai_equivalent: event.dai_equivalent,
+            }
+        }
+        "StakeEnd" => {
+            let event: events::StakeEnd = bytesrepr::deserialize(bytes.to_vec())?;
+            Events::StakeEnd {
+                stake_id: event.stake_id,
+                staker_address: event.staker_address,
+                referral_address: event.referral_address,
+                staked_amount: event.staked_amount,
+                stakes_shares: event.stakes_shares,
+                referral_shares: event.referral_shares,
+                reward_amount: event.reward_amount,
+                close_day: event.close_day,
+                penalty_amount: event.penalty_amount,
+            }
+        }
+        "InterestScraped" => {
+            let event: events::InterestScraped = bytesrepr::deserialize(bytes.to_vec())?;
+            Events::InterestScraped {
+                stake_id: event.stake_id,
+                staker_address: event.staker_address,
+                scrape_amount: event.scrape_amount,
+                scrape_day: event.scrape_day,
+                stakers_penalty: event.stakers_penalty,
+                referrer_penalty: event.referrer_penalty,
+                current_stakeable_day: event.current_stakeable_day,
+            }
+        }
+        "ReferralCollected" => {
+            let event: events::ReferralCollected = bytesrepr::deserialize(bytes.to_vec())?;
+            Events::ReferralCollected {
+                staker: event.staker,
+                stake_id: event.stake_id,
+                referrer: event.referrer,
+                referrer_id: event.referrer_id,
+                reward_amount: event.reward_amount,
+            }
+        }
+        "NewGlobals" => {
+            let event: events::NewGlobals = bytesrepr::deserialize(bytes.to_vec())?;
+            Events::NewGlobals {
+                total_shares: event.total_shares,
+                total_staked: event.total_staked,
+                share_rate: event.share_rate,
+                referral_shares: event.referral_shares,
+                current_stakeable_day: event.current_stakeable_day,
+            }
+        }
+        "NewSharePrice" => {
+            let event: events::NewSharePrice = bytesrepr::deserialize(bytes.to_vec())?;
+            Events::NewSharePrice {
+                new_share_price: event.new_share_price,
+                old_share_price: event.old_share_price,
+                current_stakeable_day: event.current_stakeable_day,
+            }
+        }
+        "UniswapReserves" => {
+            let event: events::UniswapReserves = bytesrepr::deserialize(bytes.to_vec())?;
+            Events::UniswapReserves {
+                reserve_a: event.reserve_a,
+                reserve_b: event.reserve_b,
+                block_timestamp_last: event.block_timestamp_last,
+            }
+        }
+        "LiquidityGuardStatus" => {
+            let event: events::LiquidityGuardStatus = bytesrepr::deserialize(bytes.to_vec())?;
+            Events::LiquidityGuardStatus {
+                is_active: event.is_active,
+            }
+        }
+        "RoleGranted" => {
+            let event: events::RoleGranted = bytesrepr::deserialize(bytes.to_vec())?;
+            Events::RoleGranted {
+                role: event.role,
+                account: event.account,
+                sender: event.sender,
+            }
+        }
+        "RoleRevoked" => {
+            let event: events::RoleRevoked = bytesrepr::deserialize(bytes.to_vec())?;
+            Events::RoleRevoked {
+                role: event.role,
+                account: event.account,
+                sender: event.sender,
+            }
+        }
+        "ParameterProposed" => {
+            let event: events::ParameterProposed = bytesrepr::deserialize(bytes.to_vec())?;
+            Events::ParameterProposed {
+                name: event.name,
+                value: event.value,
+                eta: event.eta,
+            }
+        }
+        "ParameterUpdated" => {
+            let event: events::ParameterUpdated = bytesrepr::deserialize(bytes.to_vec())?;
+            Events::ParameterUpdated {
+                name: event.name,
+                old_value: event.old_value,
+                new_value: event.new_value,
+            }
+        }
+        "ParameterCancelled" => {
+            let event: events::ParameterCancelled = bytesrepr::deserialize(bytes.to_vec())?;
+            Events::ParameterCancelled { name: event.name }
+        }
+        "Paused" => {
+            let event: events::Paused = bytesrepr::deserialize(bytes.to_vec())?;
+            Events::Paused {
+                operation: event.operation,
+                account: event.account,
+            }
+        }
+        "Unpaused" => {
+            let event: events::Unpaused = bytesrepr::deserialize(bytes.to_vec())?;
+            Events::Unpaused {
+                operation: event.operation,
+                account: event.account,
+            }
+        }
+        "Migrated" => {
+            let event: events::Migrated = bytesrepr::deserialize(bytes.to_vec())?;
+            Events::Migrated {
+                contract_hash: event.contract_hash,
+                from_version: event.from_version,
+                to_version: event.to_version,
+            }
+        }
+        "ReferralLinkCreated" => {
+            let event: events::ReferralLinkCreated = bytesrepr::deserialize(bytes.to_vec())?;
+            Events::ReferralLinkCreated {
+                referrer: event.referrer,
+                referral_id: event.referral_id,
+                staker: event.staker,
+                stake_id: event.stake_id,
+                referrer_shares: event.referrer_shares,
+            }
+        }
+        "CriticalMassActivated" => {
+            let event: events::CriticalMassActivated = bytesrepr::deserialize(bytes.to_vec())?;
+            Events::CriticalMassActivated {
+                referrer: event.referrer,
+                total_amount: event.total_amount,
+                activation_day: event.activation_day,
+            }
+        }
+        "CriticalMassReset" => {
+            let event: events::CriticalMassReset = bytesrepr::deserialize(bytes.to_vec())?;
+            Events::CriticalMassReset {
+                referrer: event.referrer,
+                total_amount: event.total_amount,
+            }
+        }
+        "DailySnapshot" => {
+            let event: events::DailySnapshot = bytesrepr::deserialize(bytes.to_vec())?;
+            Events::DailySnapshot {
+                day: event.day,
+                total_shares: event.total_shares,
+                inflation_amount: event.inflation_amount,
+                scheduled_to_end: event.scheduled_to_end,
+                referral_total_shares: event.referral_total_shares,
+                referral_inflation_amount: event.referral_inflation_amount,
+                referral_scheduled_to_end: event.referral_scheduled_to_end,
+                liquidity_total_shares: event.liquidity_total_shares,
+                liquidity_inflation_amount: event.liquidity_inflation_amount,
+            }
+        }
+        "LiquidityRatesAdjusted" => {
+            let event: events::LiquidityRatesAdjusted = bytesrepr::deserialize(bytes.to_vec())?;
+            Events::LiquidityRatesAdjusted {
+                liquidity_rate: event.liquidity_rate,
+                inflation_rate: event.inflation_rate,
+                is_liquidity_guard_active: event.is_liquidity_guard_active,
+            }
+        }
+        "ReferralCodeClaimed" => {
+            let event: events::ReferralCodeClaimed = bytesrepr::deserialize(bytes.to_vec())?;
+            Events::ReferralCodeClaimed {
+                code: event.code,
+                owner: event.owner,
+            }
+        }
+        "ReferralCodeTransferred" => {
+            let event: events::ReferralCodeTransferred = bytesrepr::deserialize(bytes.to_vec())?;
+            Events::ReferralCodeTransferred {
+                code: event.code,
+                from: event.from,
+                to: event.to,
+            }
+        }
+        "ReferralCodeReleased" => {
+            let event: events::ReferralCodeReleased = bytesrepr::deserialize(bytes.to_vec())?;
+            Events::ReferralCodeReleased {
+                code: event.code,
+                owner: event.owner,
+            }
+        }
+        "TierLinkCreated" => {
+            let event: events::TierLinkCreated = bytesrepr::deserialize(bytes.to_vec())?;
+            Events::TierLinkCreated {
+                upline: event.upline,
+                tier_link_id: event.tier_link_id,
+                staker: event.staker,
+                stake_id: event.stake_id,
+                tier: event.tier,
+                tier_shares: event.tier_shares,
+            }
+        }
+        "TierReferralCollected" => {
+            let event: events::TierReferralCollected = bytesrepr::deserialize(bytes.to_vec())?;
+            Events::TierReferralCollected {
+                staker: event.staker,
+                stake_id: event.stake_id,
+                upline: event.upline,
+                tier_link_id: event.tier_link_id,
+                reward_amount: event.reward_amount,
+            }
+        }
+        "ReferralInterestCollectedAll" => {
+            let event: events::ReferralInterestCollectedAll =
+                bytesrepr::deserialize(bytes.to_vec())?;
+            Events::ReferralInterestCollectedAll {
+                referrer: event.referrer,
+                from_cursor: event.from_cursor,
+                next_cursor: event.next_cursor,
+                links_collected: event.links_collected,
+                reward_amount: event.reward_amount,
+            }
+        }
+        "ReferrerListUpdated" => {
+            let event: events::ReferrerListUpdated = bytesrepr::deserialize(bytes.to_vec())?;
+            Events::ReferrerListUpdated {
+                referrer: event.referrer,
+                list: event.list,
+                listed: event.listed,
+                sender: event.sender,
+            }
+        }
+        "ReferrerIgnored" => {
+            let event: events::ReferrerIgnored = bytesrepr::deserialize(bytes.to_vec())?;
+            Events::ReferrerIgnored {
+                staker: event.staker,
+                referrer: event.referrer,
+            }
+        }
+        "InflationAssigned" => {
+            let event: events::InflationAssigned = bytesrepr::deserialize(bytes.to_vec())?;
+            Events::InflationAssigned {
+                sender: event.sender,
+            }
+        }
+        _ => return Err(DecodeError::UnknownEvent(name.to_string())),
+    })
+}
+
+/// Same as `decode_ces` for the bytes of the stored CLValue
+pub fn decode_ces_cl_value(bytes: &[u8]) -> Result<Events, DecodeError> {
+    let event: Bytes = bytesrepr::deserialize(bytes.to_vec())?;
+    decode_ces(&event)
+}
@@ -0,0 +1,118 @@
+use casper_types::{account::AccountHash, bytesrepr, Key, U128, U256};
+use std::{collections::BTreeMap, convert::TryInto};
+
+/// Prefix the Casper Event Standard may put in front of the event name
+pub const CES_EVENT_PREFIX: &str = "event_";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// `event_type` or the CES event name is not one of `Events`
+    UnknownEvent(String),
+    MissingField(&'static str),
+    InvalidField {
+        field: &'static str,
+        value: String,
+    },
+    Bytes(bytesrepr::Error),
+}
+
+impl From<bytesrepr::Error> for DecodeError {
+    fn from(error: bytesrepr::Error) -> Self {
+        DecodeError::Bytes(error)
+    }
+}
+
+/// Value of an event map field, parsed back from the string `emit` wrote
+pub trait FieldValue: Sized {
+    fn parse(value: &str) -> Option<Self>;
+}
+
+impl FieldValue for U256 {
+    fn parse(value: &str) -> Option<Self> {
+        U256::from_dec_str(value).ok()
+    }
+}
+
+impl FieldValue for U128 {
+    fn parse(value: &str) -> Option<Self> {
+        U128::from_dec_str(value).ok()
+    }
+}
+
+impl FieldValue for u64 {
+    fn parse(value: &str) -> Option<Self> {
+        value.parse().ok()
+    }
+}
+
+impl FieldValue for u32 {
+    fn parse(value: &str) -> Option<Self> {
+        value.parse().ok()
+    }
+}
+
+impl FieldValue for u8 {
+    fn parse(value: &str) -> Option<Self> {
+        value.parse().ok()
+    }
+}
+
+impl FieldValue for bool {
+    fn parse(value: &str) -> Option<Self> {
+        value.parse().ok()
+    }
+}
+
+impl FieldValue for String {
+    fn parse(value: &str) -> Option<Self> {
+        Some(value.to_string())
+    }
+}
+
+/// Keys were written with `Display`, e.g. "Key::Account(0101..)", the formatted
+/// "account-hash-0101.." form is accepted as well
+impl FieldValue for Key {
+    fn parse(value: &str) -> Option<Self> {
+        if let Some(hex) = strip(value, "Key::Account(") {
+            return hash(hex).map(|hash| Key::Account(AccountHash::new(hash)));
+        }
+        if let Some(hex) = strip(value, "Key::Hash(") {
+            return hash(hex).map(Key::Hash);
+        }
+        Key::from_formatted_str(value).ok()
+    }
+}
+
+/// Stake and referral ids were debug printed, e.g. "[1, 2]"
+impl FieldValue for Vec<u32> {
+    fn parse(value: &str) -> Option<Self> {
+        let items: &str = value.strip_prefix('[')?.strip_suffix(']')?.trim();
+        if items.is_empty() {
+            return Some(Vec::new());
+        }
+        items
+            .split(',')
+            .map(|item| item.trim().parse().ok())
+            .collect()
+    }
+}
+
+/// Reads `name` out of an event map
+pub fn field<T: FieldValue>(
+    map: &BTreeMap<String, String>,
+    name: &'static str,
+) -> Result<T, DecodeError> {
+    let value: &String = map.get(name).ok_or(DecodeError::MissingField(name))?;
+    T::parse(value).ok_or_else(|| DecodeError::InvalidField {
+        field: name,
+        value: value.clone(),
+    })
+}
+
+fn strip<'a>(value: &'a str, prefix: &str) -> Option<&'a str> {
+    value.strip_prefix(prefix)?.strip_suffix(')')
+}
+
+fn hash(value: &str) -> Option<[u8; 32]> {
+    hex::decode(value).ok()?.try_into().ok()
+}
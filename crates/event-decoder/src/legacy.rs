@@ -0,0 +1,107 @@
+use crate::data::*;
+use common::events::Events;
+use std::collections::BTreeMap;
+
+/// Parses an event map back into `Events`, `contract_package_hash` is not checked. Only the
+/// events `emit` wrote as maps before the move to the Casper Event Standard are known
+pub fn decode_map(map: &BTreeMap<String, String>) -> Result<Events, DecodeError> {
+    let event_type: String = field(map, "event_type")?;
+    Ok(match event_type.as_str() {
+        "stake_start" => Events::StakeStart {
+            stake_id: field(map, "stake_id")?,
+            staker_address: field(map, "staker_address")?,
+            referral_address: field(map, "referral_address")?,
+            staked_amount: field(map, "staked_amount")?,
+            stakes_shares: field(map, "stakes_shares")?,
+            referral_shares: field(map, "referral_shares")?,
+            start_day: field(map, "start_day")?,
+            lock_days: field(map, "lock_days")?,
+            dai_equivalent: field(map, "dai_equivalent")?,
+        },
+        "stake_end" => Events::StakeEnd {
+            stake_id: field(map, "stake_id")?,
+            staker_address: field(map, "staker_address")?,
+            referral_address: field(map, "referral_address")?,
+            staked_amount: field(map, "staked_amount")?,
+            stakes_shares: field(map, "stakes_shares")?,
+            referral_shares: field(map, "referral_shares")?,
+            reward_amount: field(map, "reward_amount")?,
+            close_day: field(map, "close_day")?,
+            penalty_amount: field(map, "penalty_amount")?,
+        },
+        "interest_scraped" => Events::InterestScraped {
+            stake_id: field(map, "stake_id")?,
+            staker_address: field(map, "staker_address")?,
+            scrape_amount: field(map, "scrape_amount")?,
+            scrape_day: field(map, "scrape_day")?,
+            stakers_penalty: field(map, "stakers_penalty")?,
+            referrer_penalty: field(map, "referrer_penalty")?,
+            current_stakeable_day: field(map, "current_stakeable_day")?,
+        },
+        "referral_collected" => Events::ReferralCollected {
+            staker: field(map, "staker")?,
+            stake_id: field(map, "stake_id")?,
+            referrer: field(map, "referrer")?,
+            referrer_id: field(map, "referrer_id")?,
+            reward_amount: field(map, "reward_amount")?,
+        },
+        "new_globals" => Events::NewGlobals {
+            total_shares: field(map, "total_shares")?,
+            total_staked: field(map, "total_staked")?,
+            share_rate: field(map, "share_rate")?,
+            referral_shares: field(map, "referrer_shares")
+                .or_else(|_| field(map, "referral_shares"))?,
+            current_stakeable_day: field(map, "current_stakeable_day")?,
+        },
+        "new_share_price" => Events::NewSharePrice {
+            new_share_price: field(map, "new_share_price")?,
+            old_share_price: field(map, "old_share_price")?,
+            current_stakeable_day: field(map, "current_stakeable_day")?,
+        },
+        "uniswap_reserves" => Events::UniswapReserves {
+            reserve_a: field(map, "reserve_a")?,
+            reserve_b: field(map, "reserve_b")?,
+            block_timestamp_last: field(map, "block_timestamp_last")?,
+        },
+        "liquidity_guard_status" => Events::LiquidityGuardStatus {
+            is_active: field(map, "is_active")?,
+        },
+        "role_granted" => Events::RoleGranted {
+            role: field(map, "role")?,
+            account: field(map, "account")?,
+            sender: field(map, "sender")?,
+        },
+        "role_revoked" => Events::RoleRevoked {
+            role: field(map, "role")?,
+            account: field(map, "account")?,
+            sender: field(map, "sender")?,
+        },
+        "parameter_proposed" => Events::ParameterProposed {
+            name: field(map, "name")?,
+            value: field(map, "value")?,
+            eta: field(map, "eta")?,
+        },
+        "parameter_updated" => Events::ParameterUpdated {
+            name: field(map, "name")?,
+            old_value: field(map, "old_value")?,
+            new_value: field(map, "new_value")?,
+        },
+        "parameter_cancelled" => Events::ParameterCancelled {
+            name: field(map, "name")?,
+        },
+        "paused" => Events::Paused {
+            operation: field(map, "operation")?,
+            account: field(map, "account")?,
+        },
+        "unpaused" => Events::Unpaused {
+            operation: field(map, "operation")?,
+            account: field(map, "account")?,
+        },
+        "migrated" => Events::Migrated {
+            contract_hash: field(map, "contract_hash")?,
+            from_version: field(map, "from_version")?,
+            to_version: field(map, "to_version")?,
+        },
+        _ => return Err(DecodeError::UnknownEvent(event_type)),
+    })
+}
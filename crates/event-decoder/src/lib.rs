@@ -0,0 +1,14 @@
+//! Host-side decoding of the events of the stakeable token into the typed `Events` enum, for
+//! indexers reading either the event maps of older deployments or the Casper Event Standard
+//! `__events` dictionary.
+
+pub mod ces;
+pub mod data;
+pub mod legacy;
+
+pub use ces::*;
+pub use data::*;
+pub use legacy::*;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,528 @@
+use crate::*;
+use casper_types::{account::AccountHash, bytesrepr::ToBytes, Key, U128, U256};
+use common::events::{ces_event, Events};
+use std::collections::BTreeMap;
+
+const PACKAGE_HASH: &str = "0202020202020202020202020202020202020202020202020202020202020202";
+
+fn account() -> Key {
+    Key::Account(AccountHash::new([1; 32]))
+}
+
+/// One event of every variant
+fn events() -> Vec<Events> {
+    vec![
+        Events::StakeStart {
+            stake_id: vec![1, 2],
+            staker_address: account(),
+            referral_address: account(),
+            staked_amount: U256::from(4),
+            stakes_shares: U256::from(5),
+            referral_shares: U256::from(6),
+            start_day: U256::from(7),
+            lock_days: U256::from(8),
+            dai_equivalent: U256::from(9),
+        },
+        Events::StakeEnd {
+            stake_id: vec![1, 2],
+            staker_address: account(),
+            referral_address: account(),
+            staked_amount: U256::from(4),
+            stakes_shares: U256::from(5),
+            referral_shares: U256::from(6),
+            reward_amount: U256::from(7),
+            close_day: U256::from(8),
+            penalty_amount: U256::from(9),
+        },
+        Events::InterestScraped {
+            stake_id: vec![1, 2],
+            staker_address: account(),
+            scrape_amount: U256::from(3),
+            scrape_day: U256::from(4),
+            stakers_penalty: U256::from(5),
+            referrer_penalty: U256::from(6),
+            current_stakeable_day: U256::from(7),
+        },
+        Events::ReferralCollected {
+            staker: account(),
+            stake_id: vec![1, 2],
+            referrer: account(),
+            referrer_id: vec![1, 2],
+            reward_amount: U256::from(5),
+        },
+        Events::NewGlobals {
+            total_shares: U256::from(1),
+            total_staked: U256::from(2),
+            share_rate: U256::from(3),
+            referral_shares: U256::from(4),
+            current_stakeable_day: U256::from(5),
+        },
+        Events::NewSharePrice {
+            new_share_price: U256::from(1),
+            old_share_price: U256::from(2),
+            current_stakeable_day: 3,
+        },
+        Events::UniswapReserves {
+            reserve_a: U128::from(1),
+            reserve_b: U128::from(2),
+            block_timestamp_last: 3,
+        },
+        Events::LiquidityGuardStatus { is_active: true },
+        Events::RoleGranted {
+            role: "name".to_string(),
+            account: account(),
+            sender: account(),
+        },
+        Events::RoleRevoked {
+            role: "name".to_string(),
+            account: account(),
+            sender: account(),
+        },
+        Events::ParameterProposed {
+            name: "name".to_string(),
+            value: U256::from(2),
+            eta: U256::from(3),
+        },
+        Events::ParameterUpdated {
+            name: "name".to_string(),
+            old_value: U256::from(2),
+            new_value: U256::from(3),
+        },
+        Events::ParameterCancelled {
+            name: "name".to_string(),
+        },
+        Events::Paused {
+            operation: "name".to_string(),
+            account: account(),
+        },
+        Events::Unpaused {
+            operation: "name".to_string(),
+            account: account(),
+        },
+        Events::Migrated {
+            contract_hash: account(),
+            from_version: 2,
+            to_version: 3,
+        },
+        Events::ReferralLinkCreated {
+            referrer: account(),
+            referral_id: vec![1, 2],
+            staker: account(),
+            stake_id: vec![1, 2],
+            referrer_shares: U256::from(5),
+        },
+        Events::CriticalMassActivated {
+            referrer: account(),
+            total_amount: U256::from(2),
+            activation_day: U256::from(3),
+        },
+        Events::CriticalMassReset {
+            referrer: account(),
+            total_amount: U256::from(2),
+        },
+        Events::DailySnapshot {
+            day: U256::from(1),
+            total_shares: U256::from(2),
+            inflation_amount: U256::from(3),
+            scheduled_to_end: U256::from(4),
+            referral_total_shares: U256::from(5),
+            referral_inflation_amount: U256::from(6),
+            referral_scheduled_to_end: U256::from(7),
+            liquidity_total_shares: U256::from(8),
+            liquidity_inflation_amount: U256::from(9),
+        },
+        Events::LiquidityRatesAdjusted {
+            liquidity_rate: 1,
+            inflation_rate: 2,
+            is_liquidity_guard_active: true,
+        },
+        Events::ReferralCodeClaimed {
+            code: "name".to_string(),
+            owner: account(),
+        },
+        Events::ReferralCodeTransferred {
+            code: "name".to_string(),
+            from: account(),
+            to: account(),
+        },
+        Events::ReferralCodeReleased {
+            code: "name".to_string(),
+            owner: account(),
+        },
+        Events::TierLinkCreated {
+            upline: account(),
+            tier_link_id: vec![1, 2],
+            staker: account(),
+            stake_id: vec![1, 2],
+            tier: 5,
+            tier_shares: U256::from(6),
+        },
+        Events::TierReferralCollected {
+            staker: account(),
+            stake_id: vec![1, 2],
+            upline: account(),
+            tier_link_id: vec![1, 2],
+            reward_amount: U256::from(5),
+        },
+        Events::ReferralInterestCollectedAll {
+            referrer: account(),
+            from_cursor: U256::from(2),
+            next_cursor: U256::from(3),
+            links_collected: U256::from(4),
+            reward_amount: U256::from(5),
+        },
+        Events::ReferrerListUpdated {
+            referrer: account(),
+            list: "name".to_string(),
+            listed: true,
+            sender: account(),
+        },
+        Events::ReferrerIgnored {
+            staker: account(),
+            referrer: account(),
+        },
+        Events::InflationAssigned { sender: account() },
+    ]
+}
+
+const ACCOUNT: &str =
+    "Key::Account(0101010101010101010101010101010101010101010101010101010101010101)";
+const HASH: &str = "Key::Hash(0202020202020202020202020202020202020202020202020202020202020202)";
+
+fn event_map(event_type: &str, fields: &[(&str, &str)]) -> BTreeMap<String, String> {
+    let mut map: BTreeMap<String, String> = BTreeMap::new();
+    map.insert(
+        "contract_package_hash".to_string(),
+        PACKAGE_HASH.to_string(),
+    );
+    map.insert("event_type".to_string(), event_type.to_string());
+    for (name, value) in fields {
+        map.insert(name.to_string(), value.to_string());
+    }
+    map
+}
+
+fn stake_start_map() -> BTreeMap<String, String> {
+    event_map(
+        "stake_start",
+        &[
+            ("stake_id", "[1, 2]"),
+            ("staker_address", ACCOUNT),
+            ("referral_address", HASH),
+            ("staked_amount", "1000000000"),
+            ("stakes_shares", "2000000000"),
+            ("referral_shares", "0"),
+            ("start_day", "16"),
+            ("lock_days", "30"),
+            ("dai_equivalent", "5"),
+        ],
+    )
+}
+
+/// Maps in the form `emit` stored them before the move to the Casper Event Standard, with
+/// the event each one holds
+fn legacy_maps() -> Vec<(BTreeMap<String, String>, Events)> {
+    vec![
+        (
+            stake_start_map(),
+            Events::StakeStart {
+                stake_id: vec![1, 2],
+                staker_address: account(),
+                referral_address: Key::Hash([2; 32]),
+                staked_amount: U256::from(1_000_000_000),
+                stakes_shares: U256::from(2_000_000_000u64),
+                referral_shares: U256::zero(),
+                start_day: U256::from(16),
+                lock_days: U256::from(30),
+                dai_equivalent: U256::from(5),
+            },
+        ),
+        (
+            event_map(
+                "stake_end",
+                &[
+                    ("stake_id", "[3]"),
+                    ("staker_address", ACCOUNT),
+                    ("referral_address", ACCOUNT),
+                    ("staked_amount", "4"),
+                    ("stakes_shares", "5"),
+                    ("referral_shares", "6"),
+                    ("reward_amount", "7"),
+                    ("close_day", "8"),
+                    ("penalty_amount", "9"),
+                ],
+            ),
+            Events::StakeEnd {
+                stake_id: vec![3],
+                staker_address: account(),
+                referral_address: account(),
+                staked_amount: U256::from(4),
+                stakes_shares: U256::from(5),
+                referral_shares: U256::from(6),
+                reward_amount: U256::from(7),
+                close_day: U256::from(8),
+                penalty_amount: U256::from(9),
+            },
+        ),
+        (
+            event_map(
+                "interest_scraped",
+                &[
+                    ("stake_id", "[1, 2]"),
+                    ("staker_address", ACCOUNT),
+                    ("scrape_amount", "3"),
+                    ("scrape_day", "4"),
+                    ("stakers_penalty", "5"),
+                    ("referrer_penalty", "6"),
+                    ("current_stakeable_day", "7"),
+                ],
+            ),
+            Events::InterestScraped {
+                stake_id: vec![1, 2],
+                staker_address: account(),
+                scrape_amount: U256::from(3),
+                scrape_day: U256::from(4),
+                stakers_penalty: U256::from(5),
+                referrer_penalty: U256::from(6),
+                current_stakeable_day: U256::from(7),
+            },
+        ),
+        (
+            event_map(
+                "referral_collected",
+                &[
+                    ("staker", ACCOUNT),
+                    ("stake_id", "[1, 2]"),
+                    ("referrer", HASH),
+                    ("referrer_id", "[]"),
+                    ("reward_amount", "5"),
+                ],
+            ),
+            Events::ReferralCollected {
+                staker: account(),
+                stake_id: vec![1, 2],
+                referrer: Key::Hash([2; 32]),
+                referrer_id: vec![],
+                reward_amount: U256::from(5),
+            },
+        ),
+        (
+            event_map(
+                "new_globals",
+                &[
+                    ("total_shares", "1"),
+                    ("total_staked", "2"),
+                    ("share_rate", "3"),
+                    ("referrer_shares", "4"),
+                    ("current_stakeable_day", "5"),
+                ],
+            ),
+            Events::NewGlobals {
+                total_shares: U256::from(1),
+                total_staked: U256::from(2),
+                share_rate: U256::from(3),
+                referral_shares: U256::from(4),
+                current_stakeable_day: U256::from(5),
+            },
+        ),
+        (
+            event_map(
+                "new_share_price",
+                &[
+                    ("new_share_price", "1"),
+                    ("old_share_price", "2"),
+                    ("current_stakeable_day", "3"),
+                ],
+            ),
+            Events::NewSharePrice {
+                new_share_price: U256::from(1),
+                old_share_price: U256::from(2),
+                current_stakeable_day: 3,
+            },
+        ),
+        (
+            event_map(
+                "uniswap_reserves",
+                &[
+                    ("reserve_a", "1"),
+                    ("reserve_b", "2"),
+                    ("block_timestamp_last", "3"),
+                ],
+            ),
+            Events::UniswapReserves {
+                reserve_a: U128::from(1),
+                reserve_b: U128::from(2),
+                block_timestamp_last: 3,
+            },
+        ),
+        (
+            event_map("liquidity_guard_status", &[("is_active", "true")]),
+            Events::LiquidityGuardStatus { is_active: true },
+        ),
+        (
+            event_map(
+                "role_granted",
+                &[("role", "admin"), ("account", ACCOUNT), ("sender", HASH)],
+            ),
+            Events::RoleGranted {
+                role: "admin".to_string(),
+                account: account(),
+                sender: Key::Hash([2; 32]),
+            },
+        ),
+        (
+            event_map(
+                "role_revoked",
+                &[("role", "admin"), ("account", ACCOUNT), ("sender", HASH)],
+            ),
+            Events::RoleRevoked {
+                role: "admin".to_string(),
+                account: account(),
+                sender: Key::Hash([2; 32]),
+            },
+        ),
+        (
+            event_map(
+                "parameter_proposed",
+                &[("name", "name"), ("value", "2"), ("eta", "3")],
+            ),
+            Events::ParameterProposed {
+                name: "name".to_string(),
+                value: U256::from(2),
+                eta: U256::from(3),
+            },
+        ),
+        (
+            event_map(
+                "parameter_updated",
+                &[("name", "name"), ("old_value", "2"), ("new_value", "3")],
+            ),
+            Events::ParameterUpdated {
+                name: "name".to_string(),
+                old_value: U256::from(2),
+                new_value: U256::from(3),
+            },
+        ),
+        (
+            event_map("parameter_cancelled", &[("name", "name")]),
+            Events::ParameterCancelled {
+                name: "name".to_string(),
+            },
+        ),
+        (
+            event_map(
+                "paused",
+                &[("operation", "create_stake"), ("account", ACCOUNT)],
+            ),
+            Events::Paused {
+                operation: "create_stake".to_string(),
+                account: account(),
+            },
+        ),
+        (
+            event_map(
+                "unpaused",
+                &[("operation", "create_stake"), ("account", ACCOUNT)],
+            ),
+            Events::Unpaused {
+                operation: "create_stake".to_string(),
+                account: account(),
+            },
+        ),
+        (
+            event_map(
+                "migrated",
+                &[
+                    ("contract_hash", HASH),
+                    ("from_version", "2"),
+                    ("to_version", "3"),
+                ],
+            ),
+            Events::Migrated {
+                contract_hash: Key::Hash([2; 32]),
+                from_version: 2,
+                to_version: 3,
+            },
+        ),
+    ]
+}
+
+#[test]
+fn should_decode_legacy_event_maps() {
+    for (map, event) in legacy_maps() {
+        assert_eq!(decode_map(&map), Ok(event));
+    }
+}
+
+#[test]
+fn should_decode_ces_events_as_emitted() {
+    for event in events() {
+        let bytes: Vec<u8> = ces_event(&event).to_bytes().unwrap();
+        assert_eq!(decode_ces(&bytes), Ok(event.clone()));
+        let cl_value: Vec<u8> = bytes.to_bytes().unwrap();
+        assert_eq!(decode_ces_cl_value(&cl_value), Ok(event));
+    }
+}
+
+#[test]
+fn should_decode_referral_shares_of_new_globals() {
+    let (mut map, event) = legacy_maps().remove(4);
+    let referral_shares: String = map.remove("referrer_shares").unwrap();
+    map.insert("referral_shares".to_string(), referral_shares);
+    assert_eq!(decode_map(&map), Ok(event));
+}
+
+#[test]
+fn should_reject_broken_events() {
+    let mut map: BTreeMap<String, String> = stake_start_map();
+    map.remove("lock_days");
+    assert_eq!(
+        decode_map(&map),
+        Err(DecodeError::MissingField("lock_days"))
+    );
+
+    let mut map: BTreeMap<String, String> = stake_start_map();
+    map.insert("stake_id".to_string(), "[1, x]".to_string());
+    assert_eq!(
+        decode_map(&map),
+        Err(DecodeError::InvalidField {
+            field: "stake_id",
+            value: "[1, x]".to_string()
+        })
+    );
+
+    let mut map: BTreeMap<String, String> = stake_start_map();
+    map.insert("event_type".to_string(), "stake_moved".to_string());
+    assert_eq!(
+        decode_map(&map),
+        Err(DecodeError::UnknownEvent("stake_moved".to_string()))
+    );
+
+    // only emitted through the Casper Event Standard, never as a map
+    let map: BTreeMap<String, String> = event_map("referral_link_created", &[]);
+    assert_eq!(
+        decode_map(&map),
+        Err(DecodeError::UnknownEvent(
+            "referral_link_created".to_string()
+        ))
+    );
+
+    let bytes: Vec<u8> = "StakeMoved".to_string().to_bytes().unwrap();
+    assert_eq!(
+        decode_ces(&bytes),
+        Err(DecodeError::UnknownEvent("StakeMoved".to_string()))
+    );
+}
+
+#[test]
+fn should_parse_field_formats() {
+    assert_eq!(<Vec<u32>>::parse("[]"), Some(vec![]));
+    assert_eq!(<Vec<u32>>::parse("1, 2"), None);
+    assert_eq!(
+        Key::parse(&format!("account-hash-{}", "01".repeat(32))),
+        Some(account())
+    );
+    assert_eq!(Key::parse("Key::Hash(02)"), None);
+    assert_eq!(U128::parse("-1"), None);
+    assert_eq!(bool::parse("false"), Some(false));
+}
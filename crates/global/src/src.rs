@@ -4,12 +4,19 @@ use casper_types::U256;
 use casperlabs_contract_utils::{ContractContext, ContractStorage};
 use casperlabs_erc20::ERC20;
 use common::{
+    access_control::ACCESSCONTROL,
     errors::Errors,
-    events::{emit, Events},
+    events::{emit, init_events, Events},
+    pausable::PAUSABLE,
 };
 
-pub trait GLOBAL<Storage: ContractStorage>: ContractContext<Storage> + ERC20<Storage> {
+pub trait GLOBAL<Storage: ContractStorage>:
+    ContractContext<Storage> + ERC20<Storage> + ACCESSCONTROL<Storage> + PAUSABLE<Storage>
+{
     fn init(&self) {
+        init_events();
+        ACCESSCONTROL::init(self, self.get_caller());
+        PAUSABLE::init(self);
         set_globals({
             let mut globals = globals();
             globals.share_price = U256::from(100) * U256::from(10).pow(6.into()); // 100E15 <=> 100E6 == 0.1 WISE
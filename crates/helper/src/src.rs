@@ -8,6 +8,7 @@ use timing::{
     globals,
     src::TIMING,
     CriticalMass, LiquidityStakeCount, ReferralCount, ReferrerLinks, Stake, StakeCount, Stakes,
+    TierLinkCount,
 };
 
 pub trait HELPER<Storage: ContractStorage>: ContractContext<Storage> + TIMING<Storage> {
@@ -54,6 +55,10 @@ pub trait HELPER<Storage: ContractStorage>: ContractContext<Storage> + TIMING<St
         self.generate_id(staker, LiquidityStakeCount::instance().get(&staker), 0x03)
     }
 
+    fn _generate_tier_link_id(&self, upline: Key) -> Vec<u32> {
+        self.generate_id(upline, TierLinkCount::instance().get(&upline), 0x04)
+    }
+
     fn stakes_pagination(&self, staker: Key, offset: U256, length: U256) -> Vec<Vec<u32>> {
         let start: U256 = if offset > 0.into() && StakeCount::instance().get(&staker) > offset {
             StakeCount::instance().get(&staker) - offset
@@ -92,14 +97,12 @@ pub trait HELPER<Storage: ContractStorage>: ContractContext<Storage> + TIMING<St
         } else {
             0.into()
         };
-        let mut i = 0;
         let mut referrals: Vec<Vec<u32>> = Vec::with_capacity((start - finish).as_usize());
         let mut r_index = start;
         while r_index > finish {
             let r_id: Vec<u32> = Self::generate_id(self, referrer, (r_index - 1).into(), 0x02);
             if self._non_zero_address(ReferrerLinks::instance().get(&referrer, &r_id).staker) {
-                referrals[i] = r_id;
-                i += 1;
+                referrals.push(r_id);
             }
             r_index -= 1.into();
         }
@@ -159,6 +162,10 @@ pub trait HELPER<Storage: ContractStorage>: ContractContext<Storage> + TIMING<St
         ReferralCount::instance().set(&referrer, ReferralCount::instance().get(&referrer) + 1);
     }
 
+    fn _increase_tier_link_count(&self, upline: Key) {
+        TierLinkCount::instance().set(&upline, TierLinkCount::instance().get(&upline) + 1);
+    }
+
     fn _increase_liquidity_stake_count(&self, staker: Key) {
         LiquidityStakeCount::instance()
             .set(&staker, LiquidityStakeCount::instance().get(&staker) + 1);
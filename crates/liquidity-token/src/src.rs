@@ -2,9 +2,16 @@ use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
 use casper_types::{Key, U256};
 use casperlabs_contract_utils::{ContractContext, ContractStorage};
 use staking_token::{
-    errors::Errors, functions::package_hash, globals, is_liquidity_guard_active, set_globals,
-    src::STAKINGTOKEN, uniswap_pair, LSnapshots, LiquidityStake, LiquidityStakes,
-    MIN_REFERRAL_DAYS, PRECISION_RATE,
+    errors::Errors,
+    functions::package_hash,
+    globals, is_liquidity_guard_active,
+    pausable::{LIQUIDITY_OPERATION, PAUSABLE},
+    set_globals,
+    src::STAKINGTOKEN,
+    uniswap_pair,
+    views::LiquidityStakeView,
+    LSnapshots, LiquidityScrapes, LiquidityStake, LiquidityStakes, MIN_REFERRAL_DAYS,
+    PRECISION_RATE,
 };
 
 pub trait LIQUIDITYTOKEN<Storage: ContractStorage>:
@@ -15,6 +22,7 @@ pub trait LIQUIDITYTOKEN<Storage: ContractStorage>:
     }
 
     fn create_liquidity_stake(&mut self, liquidity_tokens: U256) -> Vec<u32> {
+        self.when_not_paused(LIQUIDITY_OPERATION);
         self.snapshot_trigger();
         if !is_liquidity_guard_active() {
             runtime::revert(Errors::LiquidityGuardIsNotActive);
@@ -25,6 +33,11 @@ pub trait LIQUIDITYTOKEN<Storage: ContractStorage>:
             package_hash(),
             liquidity_tokens,
         );
+        self._create_liquidity_stake(self.get_caller(), liquidity_tokens)
+    }
+
+    /// @notice books a liquidity stake for LP tokens already held by the contract
+    fn _create_liquidity_stake(&mut self, staker: Key, liquidity_tokens: U256) -> Vec<u32> {
         let new_liquidity_stake: LiquidityStake = LiquidityStake {
             staked_amount: liquidity_tokens,
             reward_amount: Default::default(),
@@ -32,7 +45,7 @@ pub trait LIQUIDITYTOKEN<Storage: ContractStorage>:
             close_day: Default::default(),
             is_active: true,
         };
-        let liquidity_stake_id = self._generate_liquidity_stake_id(self.get_caller());
+        let liquidity_stake_id = self._generate_liquidity_stake_id(staker);
         set_globals({
             let mut globals = globals();
             globals.liquidity_shares = globals
@@ -41,27 +54,32 @@ pub trait LIQUIDITYTOKEN<Storage: ContractStorage>:
                 .unwrap_or_revert_with(Errors::AdditionOverflow18);
             globals
         });
-        LiquidityStakes::instance().set(
-            &self.get_caller(),
-            &liquidity_stake_id,
-            new_liquidity_stake,
-        );
-        self._increase_liquidity_stake_count(self.get_caller());
+        LiquidityStakes::instance().set(&staker, &liquidity_stake_id, new_liquidity_stake);
+        self._increase_liquidity_stake_count(staker);
         liquidity_stake_id
     }
 
+    /// @notice closes a liquidity stake and returns its LP tokens, returns the reward minted by
+    ///     this call, rewards minted by earlier partial withdrawals are not included
     fn end_liquidity_stake(&mut self, liquidity_stake_id: Vec<u32>) -> U256 {
+        self.when_not_paused(LIQUIDITY_OPERATION);
         self.snapshot_trigger();
         let mut liquidity_stake =
             LiquidityStakes::instance().get(&self.get_caller(), &liquidity_stake_id);
         if !liquidity_stake.is_active {
             runtime::revert(Errors::NotAnActiveStake3);
         }
+        let scrape_day: u64 =
+            LiquidityScrapes::instance().get(&self.get_caller(), &liquidity_stake_id);
+        let reward_amount: U256 =
+            LIQUIDITYTOKEN::_calculate_reward_amount(self, liquidity_stake, scrape_day);
         liquidity_stake.is_active = false;
         liquidity_stake.close_day = self._current_stakeable_day();
-        liquidity_stake.reward_amount =
-            LIQUIDITYTOKEN::_calculate_reward_amount(self, liquidity_stake);
-        self.mint(self.get_caller(), liquidity_stake.reward_amount);
+        liquidity_stake.reward_amount = liquidity_stake
+            .reward_amount
+            .checked_add(reward_amount)
+            .unwrap_or_revert_with(Errors::AdditionOverflow18);
+        self.mint(self.get_caller(), reward_amount);
         self._transfer(
             uniswap_pair(),
             self.get_caller(),
@@ -76,7 +94,60 @@ pub trait LIQUIDITYTOKEN<Storage: ContractStorage>:
             globals
         });
         LiquidityStakes::instance().set(&self.get_caller(), &liquidity_stake_id, liquidity_stake);
-        liquidity_stake.reward_amount
+        reward_amount
+    }
+
+    /// @notice A method for a staker to withdraw part of the LP tokens of a liquidity stake,
+    ///     rewards are settled up to now and the remaining tokens stay staked from the same start day
+    /// @param _liquidityStakeID - identification number
+    /// @param _lpAmount - amount of LP tokens to withdraw
+    fn withdraw_liquidity_partial(
+        &mut self,
+        liquidity_stake_id: Vec<u32>,
+        lp_amount: U256,
+    ) -> U256 {
+        self.when_not_paused(LIQUIDITY_OPERATION);
+        self.snapshot_trigger();
+        let mut liquidity_stake =
+            LiquidityStakes::instance().get(&self.get_caller(), &liquidity_stake_id);
+        if !liquidity_stake.is_active {
+            runtime::revert(Errors::NotAnActiveStake3);
+        }
+        if lp_amount == 0.into() || lp_amount >= liquidity_stake.staked_amount {
+            runtime::revert(Errors::InvalidWithdrawAmount);
+        }
+        let scrape_day: u64 =
+            LiquidityScrapes::instance().get(&self.get_caller(), &liquidity_stake_id);
+        let reward_amount: U256 =
+            LIQUIDITYTOKEN::_calculate_reward_amount(self, liquidity_stake, scrape_day);
+        liquidity_stake.reward_amount = liquidity_stake
+            .reward_amount
+            .checked_add(reward_amount)
+            .unwrap_or_revert_with(Errors::AdditionOverflow18);
+        let calculation_day: U256 = self._liquidity_calculation_day(liquidity_stake);
+        if calculation_day > scrape_day.into() {
+            LiquidityScrapes::instance().set(
+                &self.get_caller(),
+                &liquidity_stake_id,
+                calculation_day.as_u64(),
+            );
+        }
+        liquidity_stake.staked_amount = liquidity_stake
+            .staked_amount
+            .checked_sub(lp_amount)
+            .unwrap_or_revert_with(Errors::SubtractionUnderflow3);
+        self.mint(self.get_caller(), reward_amount);
+        self._transfer(uniswap_pair(), self.get_caller(), lp_amount);
+        set_globals({
+            let mut globals = globals();
+            globals.liquidity_shares = globals
+                .liquidity_shares
+                .checked_sub(lp_amount)
+                .unwrap_or_revert_with(Errors::SubtractionUnderflow3);
+            globals
+        });
+        LiquidityStakes::instance().set(&self.get_caller(), &liquidity_stake_id, liquidity_stake);
+        reward_amount
     }
 
     fn check_liquidity_stake_by_id(
@@ -85,27 +156,55 @@ pub trait LIQUIDITYTOKEN<Storage: ContractStorage>:
         liquidity_stake_id: Vec<u32>,
     ) -> (U256, U256, U256, U256, bool) {
         let stake: LiquidityStake = LiquidityStakes::instance().get(&staker, &liquidity_stake_id);
+        let scrape_day: u64 = LiquidityScrapes::instance().get(&staker, &liquidity_stake_id);
         (
             stake.start_day.into(),
             stake.staked_amount,
-            LIQUIDITYTOKEN::_calculate_reward_amount(self, stake),
+            if stake.is_active {
+                stake.reward_amount
+                    + LIQUIDITYTOKEN::_calculate_reward_amount(self, stake, scrape_day)
+            } else {
+                stake.reward_amount
+            },
             stake.close_day.into(),
             stake.is_active,
         )
     }
 
-    /// @notice calculates reward when closing liquidity stake
+    fn liquidity_stake_view(
+        &self,
+        staker: Key,
+        liquidity_stake_id: Vec<u32>,
+    ) -> LiquidityStakeView {
+        let stake: LiquidityStake = LiquidityStakes::instance().get(&staker, &liquidity_stake_id);
+        let scrape_day: u64 = LiquidityScrapes::instance().get(&staker, &liquidity_stake_id);
+        LiquidityStakeView {
+            staker,
+            liquidity_stake_id,
+            start_day: stake.start_day,
+            close_day: stake.close_day,
+            staked_amount: stake.staked_amount,
+            reward_amount: if stake.is_active {
+                stake.reward_amount
+                    + LIQUIDITYTOKEN::_calculate_reward_amount(self, stake, scrape_day)
+            } else {
+                stake.reward_amount
+            },
+            is_active: stake.is_active,
+        }
+    }
+
+    /// @notice calculates reward not yet settled when closing or withdrawing from liquidity stake
     /// @param _liquidityStake - stake instance
-    fn _calculate_reward_amount(&self, liquidity_stake: LiquidityStake) -> U256 {
-        let max_calculation_day: U256 =
-            (liquidity_stake.start_day + MIN_REFERRAL_DAYS as u64).into();
-        let calculation_day = if globals().current_stakeable_day < max_calculation_day {
-            globals().current_stakeable_day
+    /// @param _scrapeDay - day rewards were settled up to by a partial withdrawal
+    fn _calculate_reward_amount(&self, liquidity_stake: LiquidityStake, scrape_day: u64) -> U256 {
+        let calculation_day = self._liquidity_calculation_day(liquidity_stake);
+        let mut reward_amount: U256 = 0.into();
+        let mut day: U256 = if scrape_day > liquidity_stake.start_day {
+            scrape_day.into()
         } else {
-            max_calculation_day
+            liquidity_stake.start_day.into()
         };
-        let mut reward_amount: U256 = 0.into();
-        let mut day: U256 = liquidity_stake.start_day.into();
         while day < calculation_day {
             reward_amount += (liquidity_stake.staked_amount * PRECISION_RATE)
                 .checked_div(LSnapshots::instance().get(&day).inflation_amount)
@@ -114,4 +213,14 @@ pub trait LIQUIDITYTOKEN<Storage: ContractStorage>:
         }
         reward_amount
     }
+
+    fn _liquidity_calculation_day(&self, liquidity_stake: LiquidityStake) -> U256 {
+        let max_calculation_day: U256 =
+            (liquidity_stake.start_day + MIN_REFERRAL_DAYS as u64).into();
+        if globals().current_stakeable_day < max_calculation_day {
+            globals().current_stakeable_day
+        } else {
+            max_calculation_day
+        }
+    }
 }
@@ -2,14 +2,23 @@ use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
 use casper_types::{runtime_args, Key, RuntimeArgs, U256};
 use casperlabs_contract_utils::{ContractContext, ContractStorage};
 use snapshot::{
+    access_control::REFERRER_MANAGER_ROLE,
     data::*,
     errors::Errors,
     events::{emit, Events},
-    functions::key_to_hash,
-    latest_stable_usd_equivalent, path,
+    functions::{account_zero_address, key_to_hash},
+    latest_stable_usd_equivalent, math_or_revert, min_referrer_stake, path,
+    pausable::{PAUSABLE, REFERRAL_OPERATION},
+    referral_tier_depth, referral_tier_rate, referrer_allowlist, referrer_cooldown,
+    set_tier_shares,
     src::SNAPSHOT,
-    uniswap_router, CriticalMass, ReferralSharesToEnd, ReferrerLink, ReferrerLinks, Stake, Stakes,
-    PRECISION_RATE, THRESHOLD_LIMIT, YODAS_PER_STAKEABLE,
+    threshold_limit, tier_shares, uniswap_router,
+    views::{ReferralView, ReferrerSummaryView},
+    CriticalMass, ReferralCodes, ReferralCount, ReferralCursors, ReferralSharesToEnd,
+    ReferrerCodes, ReferrerLink, ReferrerLinks, ReferrerSharesEnding, ReferrerSharesStarting,
+    ReferrerSummaries, ReferrerSummary, Stake, StakeTierLinks, Stakes, SummaryCounted, TierLink,
+    TierLinks, TierSharesToEnd, Uplines, DECAY_PRECISION, MAX_REFERRAL_CODE_LENGTH,
+    MIN_REFERRAL_CODE_LENGTH, PRECISION_RATE, YODAS_PER_STAKEABLE,
 };
 
 pub trait REFERRALTOKEN<Storage: ContractStorage>:
@@ -54,23 +63,503 @@ pub trait REFERRALTOKEN<Storage: ContractStorage>:
         }
     }
 
+    /// @notice remembers who first referred the staker, the chain of uplines
+    /// is walked when higher referral tiers are enabled
+    fn _register_upline(&self, staker: Key, referrer: Key) {
+        if self._non_zero_address(referrer)
+            && Uplines::instance().get(&staker) == account_zero_address()
+        {
+            Uplines::instance().set(&staker, referrer);
+        }
+    }
+
+    /// @notice gives the referrer's own uplines a share of the referrer shares,
+    /// one tier link per upline that reached critical mass
+    /// @return the tier shares booked for the stake
+    fn _create_tier_links(
+        &self,
+        referrer: Key,
+        staker: Key,
+        stake_id: Vec<u32>,
+        referrer_shares: U256,
+        final_day: U256,
+    ) -> U256 {
+        let mut total_tier_shares: U256 = 0.into();
+        let mut tier_links: Vec<(Key, Vec<u32>)> = Vec::new();
+        let mut upline: Key = Uplines::instance().get(&referrer);
+        for tier in 2..=referral_tier_depth() {
+            if !self._non_zero_address(upline) || upline == staker {
+                break;
+            }
+            let tier_shares_amount: U256 = math_or_revert(
+                economics::tier_shares_amount(referrer_shares, referral_tier_rate(tier)),
+                Errors::MultiplicationOverflow5,
+                Errors::DivisionByZero5,
+            );
+            if tier_shares_amount > 0.into() && !self._not_critical_mass_referrer(upline) {
+                let tier_link_id: Vec<u32> = self._generate_tier_link_id(upline);
+                TierLinks::instance().set(
+                    &upline,
+                    &tier_link_id,
+                    TierLink {
+                        staker,
+                        stake_id: stake_id.clone(),
+                        tier,
+                        tier_shares: tier_shares_amount,
+                        reward_amount: Default::default(),
+                        processed_days: Default::default(),
+                        is_active: true,
+                    },
+                );
+                self._increase_tier_link_count(upline);
+                tier_links.push((upline, tier_link_id.clone()));
+                total_tier_shares = total_tier_shares
+                    .checked_add(tier_shares_amount)
+                    .unwrap_or_revert_with(Errors::AdditionOverflow4);
+                emit(&Events::TierLinkCreated {
+                    upline,
+                    tier_link_id,
+                    staker,
+                    stake_id: stake_id.clone(),
+                    tier,
+                    tier_shares: tier_shares_amount,
+                });
+            }
+            upline = Uplines::instance().get(&upline);
+        }
+        if total_tier_shares > 0.into() {
+            StakeTierLinks::instance().set(&staker, &stake_id, tier_links);
+            TierSharesToEnd::instance().set(
+                &final_day,
+                TierSharesToEnd::instance()
+                    .get(&final_day)
+                    .checked_add(total_tier_shares)
+                    .unwrap_or_revert_with(Errors::AdditionOverflow4),
+            );
+            set_tier_shares(
+                tier_shares()
+                    .checked_add(total_tier_shares)
+                    .unwrap_or_revert_with(Errors::AdditionOverflow4),
+            );
+        }
+        total_tier_shares
+    }
+
+    /// @notice lowers the tier links of a scraped stake in the ratio the scrape lowered
+    /// its referrer shares
+    /// @return the tier shares removed from the stake
+    fn _penalize_tier_links(
+        &self,
+        staker: Key,
+        stake_id: Vec<u32>,
+        referrer_penalty: U256,
+        referrer_shares: U256,
+        final_day: U256,
+    ) -> U256 {
+        let mut total_penalty: U256 = 0.into();
+        for (upline, tier_link_id) in StakeTierLinks::instance().get(&staker, &stake_id) {
+            let mut link: TierLink = TierLinks::instance().get(&upline, &tier_link_id);
+            if !link.is_active {
+                continue;
+            }
+            let penalty: U256 = math_or_revert(
+                economics::tier_shares_penalty(link.tier_shares, referrer_penalty, referrer_shares),
+                Errors::MultiplicationOverflow16,
+                Errors::DivisionByZero17,
+            );
+            link.tier_shares = link
+                .tier_shares
+                .checked_sub(penalty)
+                .unwrap_or_revert_with(Errors::SubtractionUnderflow8);
+            TierLinks::instance().set(&upline, &tier_link_id, link);
+            total_penalty = total_penalty
+                .checked_add(penalty)
+                .unwrap_or_revert_with(Errors::AdditionOverflow4);
+        }
+        self._remove_tier_shares(total_penalty, final_day);
+        total_penalty
+    }
+
+    fn _remove_tier_shares(&self, shares: U256, final_day: U256) {
+        if shares == 0.into() {
+            return;
+        }
+        set_tier_shares(if tier_shares() > shares {
+            tier_shares() - shares
+        } else {
+            0.into()
+        });
+        if self._not_past(final_day) {
+            TierSharesToEnd::instance().set(
+                &final_day,
+                if TierSharesToEnd::instance().get(&final_day) > shares {
+                    TierSharesToEnd::instance().get(&final_day) - shares
+                } else {
+                    0.into()
+                },
+            );
+        } else {
+            let day: U256 = self._previous_stakeable_day().into();
+            TSnapshots::instance().set(&day, {
+                let mut t_snapshots = TSnapshots::instance().get(&day);
+                t_snapshots.scheduled_to_end = if t_snapshots.scheduled_to_end > shares {
+                    t_snapshots.scheduled_to_end - shares
+                } else {
+                    0.into()
+                };
+                t_snapshots
+            });
+        }
+    }
+
+    /// @notice brings the running referral aggregates of a referrer up to the
+    /// current day, one loop over the days since the last checkpoint, only days
+    /// from the activation day on accrue as links are paid from there
+    fn _settled_referrer_summary(&self, referrer: Key) -> ReferrerSummary {
+        let mut summary: ReferrerSummary = ReferrerSummaries::instance().get(&referrer);
+        let current_day: U256 = self._current_stakeable_day().into();
+        let activation_day: U256 = CriticalMass::instance().get(&referrer).activation_day;
+        // without shares there is nothing scheduled to start or end in between
+        let mut day: U256 =
+            if summary.active_shares == 0.into() && summary.pending_shares == 0.into() {
+                current_day
+            } else {
+                summary.checkpoint_day
+            };
+        while day < current_day {
+            let starting: U256 = ReferrerSharesStarting::instance().get(&referrer, &day);
+            if starting > 0.into() {
+                summary.active_shares = summary
+                    .active_shares
+                    .checked_add(starting)
+                    .unwrap_or_revert_with(Errors::AdditionOverflow12);
+                summary.pending_shares = if summary.pending_shares > starting {
+                    summary.pending_shares - starting
+                } else {
+                    0.into()
+                };
+            }
+            let ending: U256 = ReferrerSharesEnding::instance().get(&referrer, &day);
+            summary.active_shares = if summary.active_shares > ending {
+                summary.active_shares - ending
+            } else {
+                0.into()
+            };
+            let inflation_amount: U256 = RSnapshots::instance().get(&day).inflation_amount;
+            if activation_day > 0.into()
+                && day >= activation_day
+                && summary.active_shares > 0.into()
+                && inflation_amount > 0.into()
+            {
+                summary.accrued_amount = summary
+                    .active_shares
+                    .checked_mul(PRECISION_RATE.into())
+                    .unwrap_or_revert_with(Errors::MultiplicationOverflow17)
+                    .checked_div(inflation_amount)
+                    .and_then(|amount| summary.accrued_amount.checked_add(amount))
+                    .unwrap_or_revert_with(Errors::AdditionOverflow12);
+            }
+            day += 1.into();
+        }
+        summary.checkpoint_day = current_day;
+        summary
+    }
+
+    /// @notice counts the referral link of a stake in the aggregates of its referrer
+    /// and marks it, only marked links are taken out again
+    fn _add_referrer_summary_link(
+        &self,
+        referrer: Key,
+        staker: Key,
+        stake_id: Vec<u32>,
+        shares: U256,
+        start_day: U256,
+        final_day: U256,
+    ) {
+        let mut summary: ReferrerSummary = self._settled_referrer_summary(referrer);
+        summary.active_links = summary
+            .active_links
+            .checked_add(1.into())
+            .unwrap_or_revert_with(Errors::AdditionOverflow12);
+        // a link whose cooldown outlasts the stake never counts
+        if start_day < final_day {
+            // the shares starting on the checkpoint day are moved by the next settlement
+            if start_day >= summary.checkpoint_day {
+                summary.pending_shares = summary
+                    .pending_shares
+                    .checked_add(shares)
+                    .unwrap_or_revert_with(Errors::AdditionOverflow12);
+                ReferrerSharesStarting::instance().set(
+                    &referrer,
+                    &start_day,
+                    ReferrerSharesStarting::instance()
+                        .get(&referrer, &start_day)
+                        .checked_add(shares)
+                        .unwrap_or_revert_with(Errors::AdditionOverflow12),
+                );
+            } else {
+                summary.active_shares = summary
+                    .active_shares
+                    .checked_add(shares)
+                    .unwrap_or_revert_with(Errors::AdditionOverflow12);
+            }
+            ReferrerSharesEnding::instance().set(
+                &referrer,
+                &final_day,
+                ReferrerSharesEnding::instance()
+                    .get(&referrer, &final_day)
+                    .checked_add(shares)
+                    .unwrap_or_revert_with(Errors::AdditionOverflow12),
+            );
+        }
+        ReferrerSummaries::instance().set(&referrer, summary);
+        SummaryCounted::instance().set(&staker, &stake_id, true);
+    }
+
+    /// @notice takes referrer shares out of the aggregates before their final day,
+    /// `end_link` moves the link to the ended count, links that were never counted
+    /// are skipped
+    #[allow(clippy::too_many_arguments)]
+    fn _remove_referrer_summary_shares(
+        &self,
+        referrer: Key,
+        staker: Key,
+        stake_id: Vec<u32>,
+        shares: U256,
+        start_day: U256,
+        final_day: U256,
+        end_link: bool,
+    ) {
+        if !SummaryCounted::instance().get(&staker, &stake_id) {
+            return;
+        }
+        let mut summary: ReferrerSummary = self._settled_referrer_summary(referrer);
+        if final_day >= summary.checkpoint_day && start_day < final_day {
+            if start_day >= summary.checkpoint_day {
+                summary.pending_shares = summary
+                    .pending_shares
+                    .checked_sub(shares)
+                    .unwrap_or_revert_with(Errors::SubtractionUnderflow9);
+                ReferrerSharesStarting::instance().set(
+                    &referrer,
+                    &start_day,
+                    ReferrerSharesStarting::instance()
+                        .get(&referrer, &start_day)
+                        .checked_sub(shares)
+                        .unwrap_or_revert_with(Errors::SubtractionUnderflow9),
+                );
+            } else {
+                summary.active_shares = summary
+                    .active_shares
+                    .checked_sub(shares)
+                    .unwrap_or_revert_with(Errors::SubtractionUnderflow9);
+            }
+            ReferrerSharesEnding::instance().set(
+                &referrer,
+                &final_day,
+                ReferrerSharesEnding::instance()
+                    .get(&referrer, &final_day)
+                    .checked_sub(shares)
+                    .unwrap_or_revert_with(Errors::SubtractionUnderflow9),
+            );
+        }
+        if end_link {
+            summary.active_links = summary
+                .active_links
+                .checked_sub(1.into())
+                .unwrap_or_revert_with(Errors::SubtractionUnderflow9);
+            summary.ended_links = summary
+                .ended_links
+                .checked_add(1.into())
+                .unwrap_or_revert_with(Errors::AdditionOverflow12);
+            SummaryCounted::instance().set(&staker, &stake_id, false);
+        }
+        ReferrerSummaries::instance().set(&referrer, summary);
+    }
+
+    fn _collect_referrer_summary(&self, referrer: Key, amount: U256) {
+        let mut summary: ReferrerSummary = self._settled_referrer_summary(referrer);
+        summary.accrued_amount = if summary.accrued_amount > amount {
+            summary.accrued_amount - amount
+        } else {
+            0.into()
+        };
+        summary.total_collected = summary
+            .total_collected
+            .checked_add(amount)
+            .unwrap_or_revert_with(Errors::AdditionOverflow12);
+        ReferrerSummaries::instance().set(&referrer, summary);
+    }
+
+    /// @notice returns the referral totals of a referrer without visiting its links
+    fn referrer_summary(&self, referrer: Key) -> ReferrerSummaryView {
+        let summary: ReferrerSummary = self._settled_referrer_summary(referrer);
+        let critical_mass = CriticalMass::instance().get(&referrer);
+        ReferrerSummaryView {
+            total_collected: summary.total_collected,
+            claimable: if self._not_critical_mass_referrer(referrer) {
+                0.into()
+            } else {
+                summary.accrued_amount
+            },
+            active_links: summary.active_links,
+            ended_links: summary.ended_links,
+            critical_mass: critical_mass.total_amount,
+            activation_day: critical_mass.activation_day,
+            threshold_distance: if threshold_limit() > critical_mass.total_amount {
+                threshold_limit() - critical_mass.total_amount
+            } else {
+                0.into()
+            },
+        }
+    }
+
+    /// @notice a referrer earns shares only if it passes the optional anti-sybil
+    /// controls: lists and minimum own stake, the cooldown delays its links instead
+    fn _eligible_referrer(&self, referrer: Key) -> bool {
+        if DeniedReferrers::instance().get(&referrer) {
+            return false;
+        }
+        if referrer_allowlist() && !AllowedReferrers::instance().get(&referrer) {
+            return false;
+        }
+        if AccountStaked::instance().get(&referrer) < min_referrer_stake() {
+            return false;
+        }
+        true
+    }
+
+    /// @notice fixes the day the links of a referrer start to count, `referrer_cooldown`
+    /// days after its first own stake or after its first link if it never staked
+    /// @return the first day referral interest accrues for the referrer
+    fn _referrer_cooldown_end(&self, referrer: Key) -> U256 {
+        let cooldown_end: U256 = ReferrerCooldownEnds::instance().get(&referrer);
+        if cooldown_end > 0.into() || referrer_cooldown() == 0.into() {
+            return cooldown_end;
+        }
+        let first_stake_day: U256 = FirstStakeDay::instance().get(&referrer);
+        let cooldown_end: U256 = if first_stake_day > 0.into() {
+            first_stake_day
+        } else {
+            self._current_stakeable_day().into()
+        }
+        .checked_add(referrer_cooldown())
+        .unwrap_or_revert_with(Errors::AdditionOverflow4);
+        ReferrerCooldownEnds::instance().set(&referrer, cooldown_end);
+        cooldown_end
+    }
+
+    /// @notice the first day a referral link counts, not before the cooldown of its referrer
+    fn _referral_start_day(&self, stake: Stake) -> U256 {
+        let cooldown_end: U256 = ReferrerCooldownEnds::instance().get(&stake.referrer);
+        if cooldown_end > stake.start_day.into() {
+            cooldown_end
+        } else {
+            stake.start_day.into()
+        }
+    }
+
+    fn _add_account_staked(&self, staker: Key, staked_amount: U256, start_day: U256) {
+        AccountStaked::instance().set(
+            &staker,
+            AccountStaked::instance()
+                .get(&staker)
+                .checked_add(staked_amount)
+                .unwrap_or_revert_with(Errors::AdditionOverflow4),
+        );
+        if FirstStakeDay::instance().get(&staker) == 0.into() {
+            FirstStakeDay::instance().set(&staker, start_day);
+        }
+    }
+
+    fn _remove_account_staked(&self, staker: Key, staked_amount: U256) {
+        let staked: U256 = AccountStaked::instance().get(&staker);
+        AccountStaked::instance().set(
+            &staker,
+            if staked > staked_amount {
+                staked - staked_amount
+            } else {
+                0.into()
+            },
+        );
+    }
+
+    /// @notice adds or removes referrers from the allowlist, caller must be a referrer manager
+    fn set_allowed_referrers(&self, referrers: Vec<Key>, allowed: bool) {
+        self.only_role(REFERRER_MANAGER_ROLE);
+        for referrer in referrers {
+            AllowedReferrers::instance().set(&referrer, allowed);
+            emit(&Events::ReferrerListUpdated {
+                referrer,
+                list: "allowlist".into(),
+                listed: allowed,
+                sender: self.get_caller(),
+            });
+        }
+    }
+
+    /// @notice adds or removes referrers from the denylist, caller must be a referrer manager
+    fn set_denied_referrers(&self, referrers: Vec<Key>, denied: bool) {
+        self.only_role(REFERRER_MANAGER_ROLE);
+        for referrer in referrers {
+            DeniedReferrers::instance().set(&referrer, denied);
+            emit(&Events::ReferrerListUpdated {
+                referrer,
+                list: "denylist".into(),
+                listed: denied,
+                sender: self.get_caller(),
+            });
+        }
+    }
+
+    fn is_eligible_referrer(&self, referrer: Key) -> bool {
+        self._non_zero_address(referrer) && self._eligible_referrer(referrer)
+    }
+
     fn _below_threshold_level(&self, referrer: Key) -> bool {
-        CriticalMass::instance().get(&referrer).total_amount < THRESHOLD_LIMIT.into()
+        CriticalMass::instance().get(&referrer).total_amount < threshold_limit()
     }
 
-    fn _add_critical_mass(&self, referrer: Key, dai_equivalent: U256) {
+    fn _add_critical_mass(
+        &self,
+        referrer: Key,
+        staker: Key,
+        stake_id: Vec<u32>,
+        dai_equivalent: U256,
+        lock_days: U256,
+        final_day: U256,
+    ) {
         let mut critical_mass = CriticalMass::instance().get(&referrer);
         critical_mass.total_amount = critical_mass
             .total_amount
             .checked_add(dai_equivalent)
             .unwrap_or_revert_with(Errors::AdditionOverflow5);
         CriticalMass::instance().set(&referrer, critical_mass);
-        critical_mass.activation_day = self._determine_activation_day(referrer);
-        CriticalMass::instance().set(&referrer, critical_mass);
+        self._add_decaying_mass(referrer, dai_equivalent, lock_days, final_day);
+        DecayingMassRecorded::instance().set(&staker, &stake_id, true);
+        self._update_activation_day(referrer);
     }
 
-    fn _remove_critical_mass(&self, referrer: Key, dai_equivalent: U256, start_day: U256) {
-        if !self._not_future(start_day) && self._non_zero_address(referrer) {
+    /// @notice the decaying weight is only taken out for stakes that recorded it
+    #[allow(clippy::too_many_arguments)]
+    fn _remove_critical_mass(
+        &self,
+        referrer: Key,
+        staker: Key,
+        stake_id: Vec<u32>,
+        dai_equivalent: U256,
+        start_day: U256,
+        lock_days: U256,
+        final_day: U256,
+    ) {
+        if !self._non_zero_address(referrer) {
+            return;
+        }
+        if DecayingMassRecorded::instance().get(&staker, &stake_id) {
+            self._remove_decaying_mass(referrer, dai_equivalent, lock_days, final_day);
+            DecayingMassRecorded::instance().set(&staker, &stake_id, false);
+        }
+        if !self._not_future(start_day) {
             let mut critical_mass = CriticalMass::instance().get(&referrer);
             critical_mass.total_amount = if critical_mass.total_amount > dai_equivalent {
                 critical_mass.total_amount - dai_equivalent
@@ -78,13 +567,174 @@ pub trait REFERRALTOKEN<Storage: ContractStorage>:
                 0.into()
             };
             CriticalMass::instance().set(&referrer, critical_mass);
-            critical_mass.activation_day = self._determine_activation_day(referrer);
-            CriticalMass::instance().set(&referrer, critical_mass);
+            self._update_activation_day(referrer);
+        } else if critical_mass_decay() {
+            self._update_activation_day(referrer);
+        }
+    }
+
+    /// @notice each referred stake counts with `dai_equivalent * days_left / lock_days`,
+    /// tracked as `weight * (end_day - day)` so it decays without visiting the stakes
+    fn _decaying_weight(&self, dai_equivalent: U256, lock_days: U256) -> U256 {
+        dai_equivalent
+            .checked_mul(DECAY_PRECISION.into())
+            .unwrap_or_revert_with(Errors::MultiplicationOverflow5)
+            .checked_div(lock_days)
+            .unwrap_or_revert_with(Errors::DivisionByZero5)
+    }
+
+    /// @notice the last day a stake ending on `final_day` counts with its weight
+    fn _decaying_end_day(&self, final_day: U256) -> U256 {
+        final_day
+            .checked_sub(1.into())
+            .unwrap_or_revert_with(Errors::SubtractionUnderflow10)
+    }
+
+    fn _settled_decaying_mass(&self, referrer: Key) -> DecayingMass {
+        let mut mass: DecayingMass = DecayingCriticalMass::instance().get(&referrer);
+        let current_day: U256 = self._current_stakeable_day().into();
+        let mut day: U256 = if mass.weight == 0.into() {
+            current_day
+        } else {
+            mass.checkpoint_day
+        };
+        while day <= current_day {
+            let ending: U256 = DecayingMassEnding::instance().get(&referrer, &day);
+            if ending > 0.into() {
+                let weighted_ending: U256 = ending
+                    .checked_mul(day)
+                    .unwrap_or_revert_with(Errors::MultiplicationOverflow18);
+                mass.weight = if mass.weight > ending {
+                    mass.weight - ending
+                } else {
+                    0.into()
+                };
+                mass.weighted_end = if mass.weighted_end > weighted_ending {
+                    mass.weighted_end - weighted_ending
+                } else {
+                    0.into()
+                };
+            }
+            day += 1.into();
+        }
+        mass.checkpoint_day = current_day + 1;
+        mass
+    }
+
+    fn _add_decaying_mass(
+        &self,
+        referrer: Key,
+        dai_equivalent: U256,
+        lock_days: U256,
+        final_day: U256,
+    ) {
+        let weight: U256 = self._decaying_weight(dai_equivalent, lock_days);
+        let end_day: U256 = self._decaying_end_day(final_day);
+        let mut mass: DecayingMass = self._settled_decaying_mass(referrer);
+        mass.weight = mass
+            .weight
+            .checked_add(weight)
+            .unwrap_or_revert_with(Errors::AdditionOverflow5);
+        mass.weighted_end = weight
+            .checked_mul(end_day)
+            .and_then(|weighted_end| mass.weighted_end.checked_add(weighted_end))
+            .unwrap_or_revert_with(Errors::MultiplicationOverflow18);
+        DecayingMassEnding::instance().set(
+            &referrer,
+            &end_day,
+            DecayingMassEnding::instance()
+                .get(&referrer, &end_day)
+                .checked_add(weight)
+                .unwrap_or_revert_with(Errors::AdditionOverflow5),
+        );
+        DecayingCriticalMass::instance().set(&referrer, mass);
+    }
+
+    fn _remove_decaying_mass(
+        &self,
+        referrer: Key,
+        dai_equivalent: U256,
+        lock_days: U256,
+        final_day: U256,
+    ) {
+        let weight: U256 = self._decaying_weight(dai_equivalent, lock_days);
+        let end_day: U256 = self._decaying_end_day(final_day);
+        let mut mass: DecayingMass = self._settled_decaying_mass(referrer);
+        // a stake past its end day was already taken out by the settlement
+        if end_day >= mass.checkpoint_day {
+            mass.weight = mass
+                .weight
+                .checked_sub(weight)
+                .unwrap_or_revert_with(Errors::SubtractionUnderflow10);
+            mass.weighted_end = weight
+                .checked_mul(end_day)
+                .and_then(|weighted_end| mass.weighted_end.checked_sub(weighted_end))
+                .unwrap_or_revert_with(Errors::SubtractionUnderflow10);
+            DecayingMassEnding::instance().set(
+                &referrer,
+                &end_day,
+                DecayingMassEnding::instance()
+                    .get(&referrer, &end_day)
+                    .checked_sub(weight)
+                    .unwrap_or_revert_with(Errors::SubtractionUnderflow10),
+            );
+        }
+        DecayingCriticalMass::instance().set(&referrer, mass);
+    }
+
+    fn decayed_critical_mass(&self, referrer: Key) -> U256 {
+        let mass: DecayingMass = self._settled_decaying_mass(referrer);
+        let elapsed: U256 = mass
+            .weight
+            .checked_mul(U256::from(self._current_stakeable_day()))
+            .unwrap_or_revert_with(Errors::MultiplicationOverflow18);
+        if mass.weighted_end > elapsed {
+            (mass.weighted_end - elapsed) / DECAY_PRECISION
+        } else {
+            0.into()
+        }
+    }
+
+    /// @notice re-evaluates the activation of a referrer whose critical mass decays over time
+    fn _refresh_critical_mass(&self, referrer: Key) {
+        if critical_mass_decay() {
+            DecayingCriticalMass::instance().set(&referrer, self._settled_decaying_mass(referrer));
+            self._update_activation_day(referrer);
+        }
+    }
+
+    fn _update_activation_day(&self, referrer: Key) {
+        let mut critical_mass = CriticalMass::instance().get(&referrer);
+        let previous_activation_day = critical_mass.activation_day;
+        critical_mass.activation_day = self._determine_activation_day(referrer);
+        CriticalMass::instance().set(&referrer, critical_mass);
+        if previous_activation_day == 0.into() && critical_mass.activation_day > 0.into() {
+            // links are paid from the new activation day on, what accrued under an earlier
+            // activation is never collected
+            let mut summary: ReferrerSummary = self._settled_referrer_summary(referrer);
+            summary.accrued_amount = 0.into();
+            ReferrerSummaries::instance().set(&referrer, summary);
+            emit(&Events::CriticalMassActivated {
+                referrer,
+                total_amount: critical_mass.total_amount,
+                activation_day: critical_mass.activation_day,
+            });
+        }
+        if previous_activation_day > 0.into() && critical_mass.activation_day == 0.into() {
+            emit(&Events::CriticalMassReset {
+                referrer,
+                total_amount: critical_mass.total_amount,
+            });
         }
     }
 
     fn _determine_activation_day(&self, referrer: Key) -> U256 {
-        if self._below_threshold_level(referrer) {
+        let below_threshold_level: bool = if critical_mass_decay() {
+            self.decayed_critical_mass(referrer) < threshold_limit()
+        } else {
+            self._below_threshold_level(referrer)
+        };
+        if below_threshold_level {
             0.into()
         } else {
             self._activation_day(referrer)
@@ -121,18 +771,93 @@ pub trait REFERRALTOKEN<Storage: ContractStorage>:
     }
 
     fn referrer_interest(&mut self, referral_id: Vec<u32>, scrape_days: U256) {
+        self.when_not_paused(REFERRAL_OPERATION);
         self.snapshot_trigger();
+        self._refresh_critical_mass(self.get_caller());
         self._referrer_interest(self.get_caller(), referral_id, scrape_days);
     }
 
     fn referrer_interest_bulk(&mut self, referral_ids: Vec<Vec<u32>>, scrape_days: Vec<U256>) {
+        self.when_not_paused(REFERRAL_OPERATION);
         self.snapshot_trigger();
+        self._refresh_critical_mass(self.get_caller());
         for i in 0..referral_ids.len() {
             self._referrer_interest(self.get_caller(), referral_ids[i].clone(), scrape_days[i]);
         }
     }
 
-    fn _referrer_interest(&mut self, referrer: Key, referral_id: Vec<u32>, mut process_days: U256) {
+    /// @notice collects every active referral of the caller in one go, starting
+    /// at `cursor` or where the previous call stopped and visiting at most `max_links` links
+    /// @param _maxLinks links visited by this call, capped at `MAX_COLLECT_LINKS`
+    fn collect_all_referral_interest(
+        &mut self,
+        cursor: Option<U256>,
+        max_links: U256,
+    ) -> (U256, U256) {
+        self.when_not_paused(REFERRAL_OPERATION);
+        self.snapshot_trigger();
+        let referrer: Key = self.get_caller();
+        self._refresh_critical_mass(referrer);
+        let referral_count: U256 = ReferralCount::instance().get(&referrer);
+        let from_cursor: U256 =
+            cursor.unwrap_or_else(|| ReferralCursors::instance().get(&referrer));
+        if from_cursor > referral_count {
+            runtime::revert(Errors::InvalidCursor);
+        }
+        let max_links: U256 = if max_links == 0.into() || max_links > MAX_COLLECT_LINKS.into() {
+            MAX_COLLECT_LINKS.into()
+        } else {
+            max_links
+        };
+        let last_index: U256 = if referral_count - from_cursor > max_links {
+            from_cursor + max_links
+        } else {
+            referral_count
+        };
+        let mut reward_amount: U256 = 0.into();
+        let mut links_collected: U256 = 0.into();
+        let mut index: U256 = from_cursor;
+        while index < last_index {
+            let referral_id: Vec<u32> = self.generate_id(referrer, index, 0x02);
+            if ReferrerLinks::instance()
+                .get(&referrer, &referral_id)
+                .is_active
+            {
+                reward_amount = reward_amount
+                    .checked_add(self._collect_referral_link(referrer, referral_id, 0.into()))
+                    .unwrap_or_revert_with(Errors::AdditionOverflow12);
+                links_collected += 1.into();
+            }
+            index += 1.into();
+        }
+        let next_cursor: U256 = if last_index < referral_count {
+            last_index
+        } else {
+            0.into()
+        };
+        ReferralCursors::instance().set(&referrer, next_cursor);
+        self.mint(referrer, reward_amount);
+        emit(&Events::ReferralInterestCollectedAll {
+            referrer,
+            from_cursor,
+            next_cursor,
+            links_collected,
+            reward_amount,
+        });
+        (next_cursor, reward_amount)
+    }
+
+    fn _referrer_interest(&mut self, referrer: Key, referral_id: Vec<u32>, process_days: U256) {
+        let referral_interest = self._collect_referral_link(referrer, referral_id, process_days);
+        self.mint(referrer, referral_interest);
+    }
+
+    fn _collect_referral_link(
+        &mut self,
+        referrer: Key,
+        referral_id: Vec<u32>,
+        mut process_days: U256,
+    ) -> U256 {
         let mut link: ReferrerLink = ReferrerLinks::instance().get(&referrer, &referral_id);
         if !link.is_active {
             runtime::revert(Errors::NotActive);
@@ -170,7 +895,7 @@ pub trait REFERRALTOKEN<Storage: ContractStorage>:
             .checked_add(referral_interest)
             .unwrap_or_revert_with(Errors::AdditionOverflow12);
         ReferrerLinks::instance().set(&referrer, &referral_id, link);
-        self.mint(referrer, referral_interest);
+        self._collect_referrer_summary(referrer, referral_interest);
         emit(&Events::ReferralCollected {
             staker,
             stake_id,
@@ -178,6 +903,179 @@ pub trait REFERRALTOKEN<Storage: ContractStorage>:
             referrer_id: referral_id,
             reward_amount: referral_interest,
         });
+        referral_interest
+    }
+
+    fn tier_referrer_interest(&mut self, tier_link_id: Vec<u32>, scrape_days: U256) {
+        self.when_not_paused(REFERRAL_OPERATION);
+        self.snapshot_trigger();
+        self._refresh_critical_mass(self.get_caller());
+        self._tier_referrer_interest(self.get_caller(), tier_link_id, scrape_days);
+    }
+
+    fn _tier_referrer_interest(
+        &mut self,
+        upline: Key,
+        tier_link_id: Vec<u32>,
+        mut process_days: U256,
+    ) {
+        let mut link: TierLink = TierLinks::instance().get(&upline, &tier_link_id);
+        if !link.is_active {
+            runtime::revert(Errors::NotActive);
+        }
+        let staker: Key = link.staker;
+        let stake_id: Vec<u32> = link.stake_id.clone();
+        let stake: Stake = Stakes::instance().get(&staker, &stake_id);
+        let start_day: U256 = self._determine_tier_start_day(upline, stake, link.clone());
+        let mut final_day: U256 = self._determine_final_day(stake);
+        if self._stake_ended(stake) {
+            if process_days > 0.into() && process_days < self._days_diff(start_day, final_day) {
+                link.processed_days = link
+                    .processed_days
+                    .checked_add(process_days)
+                    .unwrap_or_revert_with(Errors::AdditionOverflow7);
+                final_day = start_day
+                    .checked_add(process_days)
+                    .unwrap_or_revert_with(Errors::AdditionOverflow1);
+            } else {
+                link.is_active = false;
+            }
+        } else {
+            process_days = self._days_diff(start_day, self._current_stakeable_day().into());
+            link.processed_days = link
+                .processed_days
+                .checked_add(process_days)
+                .unwrap_or_revert_with(Errors::AdditionOverflow9);
+            final_day = start_day
+                .checked_add(process_days)
+                .unwrap_or_revert_with(Errors::AdditionOverflow10);
+        }
+        let tier_interest = self._get_tier_interest(link.tier_shares, start_day, final_day);
+        link.reward_amount = link
+            .reward_amount
+            .checked_add(tier_interest)
+            .unwrap_or_revert_with(Errors::AdditionOverflow12);
+        TierLinks::instance().set(&upline, &tier_link_id, link);
+        self.mint(upline, tier_interest);
+        emit(&Events::TierReferralCollected {
+            staker,
+            stake_id,
+            upline,
+            tier_link_id,
+            reward_amount: tier_interest,
+        });
+    }
+
+    fn check_tier_link_by_id(
+        &self,
+        upline: Key,
+        tier_link_id: Vec<u32>,
+    ) -> (Key, Vec<u32>, u8, U256, U256, bool, bool) {
+        let link: TierLink = TierLinks::instance().get(&upline, &tier_link_id);
+        let stake: Stake = Stakes::instance().get(&link.staker, &link.stake_id);
+        (
+            link.staker,
+            link.stake_id.clone(),
+            link.tier,
+            link.tier_shares,
+            self._get_tier_interest(
+                link.tier_shares,
+                self._determine_tier_start_day(upline, stake, link.clone()),
+                self._determine_final_day(stake),
+            ),
+            link.is_active,
+            stake.is_active,
+        )
+    }
+
+    fn _get_tier_interest(&self, tier_shares: U256, start_day: U256, final_day: U256) -> U256 {
+        let mut tier_interest: U256 = 0.into();
+        let mut day: U256 = start_day;
+        while day < final_day {
+            let inflation_amount: U256 = TSnapshots::instance().get(&day).inflation_amount;
+            if inflation_amount > 0.into() {
+                tier_interest += (tier_shares * PRECISION_RATE) / inflation_amount;
+            }
+            day += 1.into();
+        }
+        tier_interest
+    }
+
+    fn _determine_tier_start_day(&self, upline: Key, stake: Stake, link: TierLink) -> U256 {
+        (if CriticalMass::instance().get(&upline).activation_day > stake.start_day.into() {
+            CriticalMass::instance().get(&upline).activation_day
+        } else {
+            stake.start_day.into()
+        })
+        .checked_add(link.processed_days)
+        .unwrap_or_revert_with(Errors::AdditionOverflow6)
+    }
+
+    /// @notice reserves a referral code for the caller, one code per account
+    /// @param _code 3 to 32 characters of lowercase letters, digits, '-' or '_'
+    fn claim_referral_code(&self, code: String) {
+        let owner = self.get_caller();
+        if !ReferrerCodes::instance().get(&owner).is_empty() {
+            runtime::revert(Errors::ReferralCodeAlreadyClaimed);
+        }
+        if code.len() < MIN_REFERRAL_CODE_LENGTH
+            || code.len() > MAX_REFERRAL_CODE_LENGTH
+            || !code
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+        {
+            runtime::revert(Errors::InvalidReferralCode);
+        }
+        if ReferralCodes::instance().get(&code) != account_zero_address() {
+            runtime::revert(Errors::ReferralCodeTaken);
+        }
+        ReferralCodes::instance().set(&code, owner);
+        ReferrerCodes::instance().set(&owner, code.clone());
+        emit(&Events::ReferralCodeClaimed { code, owner });
+    }
+
+    /// @notice hands the caller's referral code over to another account
+    fn transfer_referral_code(&self, recipient: Key) {
+        let owner = self.get_caller();
+        let code = ReferrerCodes::instance().get(&owner);
+        if code.is_empty() {
+            runtime::revert(Errors::NoReferralCode);
+        }
+        if !ReferrerCodes::instance().get(&recipient).is_empty() {
+            runtime::revert(Errors::ReferralCodeAlreadyClaimed);
+        }
+        ReferralCodes::instance().set(&code, recipient);
+        ReferrerCodes::instance().set(&owner, String::new());
+        ReferrerCodes::instance().set(&recipient, code.clone());
+        emit(&Events::ReferralCodeTransferred {
+            code,
+            from: owner,
+            to: recipient,
+        });
+    }
+
+    /// @notice gives up the caller's referral code so it can be claimed again
+    fn release_referral_code(&self) {
+        let owner = self.get_caller();
+        let code = ReferrerCodes::instance().get(&owner);
+        if code.is_empty() {
+            runtime::revert(Errors::NoReferralCode);
+        }
+        ReferralCodes::instance().set(&code, account_zero_address());
+        ReferrerCodes::instance().set(&owner, String::new());
+        emit(&Events::ReferralCodeReleased { code, owner });
+    }
+
+    fn resolve_referral_code(&self, code: String) -> Key {
+        ReferralCodes::instance().get(&code)
+    }
+
+    fn _referrer_by_code(&self, code: &str) -> Key {
+        let referrer = ReferralCodes::instance().get(code);
+        if referrer == account_zero_address() {
+            runtime::revert(Errors::UnknownReferralCode);
+        }
+        referrer
     }
 
     fn check_referrals_by_id(
@@ -203,6 +1101,27 @@ pub trait REFERRALTOKEN<Storage: ContractStorage>:
         )
     }
 
+    fn referral_view(&self, referrer: Key, referral_id: Vec<u32>) -> ReferralView {
+        let link: ReferrerLink = ReferrerLinks::instance().get(&referrer, &referral_id);
+        let stake: Stake = Stakes::instance().get(&link.staker, &link.stake_id);
+        ReferralView {
+            referrer,
+            referral_id,
+            staker: link.staker,
+            stake_id: link.stake_id.clone(),
+            referrer_shares: stake.referrer_shares,
+            referral_interest: self._check_referral_interest(
+                stake,
+                self._determine_start_day(stake, link.clone()),
+                self._determine_final_day(stake),
+            ),
+            is_active_referral: link.is_active,
+            is_active_stake: stake.is_active,
+            is_mature_stake: self._is_mature_stake(stake),
+            is_ended_stake: self._stake_ended(stake),
+        }
+    }
+
     fn _check_referral_interest(&self, stake: Stake, start_day: U256, final_day: U256) -> U256 {
         if self._not_critical_mass_referrer(stake.referrer) {
             0.into()
@@ -224,10 +1143,11 @@ pub trait REFERRALTOKEN<Storage: ContractStorage>:
     }
 
     fn _determine_start_day(&self, stake: Stake, link: ReferrerLink) -> U256 {
-        (if CriticalMass::instance().get(&stake.referrer).activation_day > stake.start_day.into() {
+        let start_day: U256 = self._referral_start_day(stake);
+        (if CriticalMass::instance().get(&stake.referrer).activation_day > start_day {
             CriticalMass::instance().get(&stake.referrer).activation_day
         } else {
-            stake.start_day.into()
+            start_day
         })
         .checked_add(link.processed_days)
         .unwrap_or_revert_with(Errors::AdditionOverflow6)
@@ -3,7 +3,10 @@ use casper_types_derive::{CLTyped, FromBytes, ToBytes};
 use casperlabs_contract_utils::Dict;
 extern crate alloc;
 use alloc::{string::ToString, vec::Vec};
-use helper::keys::{LSNAPSHOTS_DICT, RSNAPSHOTS_DICT, SNAPSHOTS_DICT};
+use helper::keys::{LSNAPSHOTS_DICT, RSNAPSHOTS_DICT, SNAPSHOTS_DICT, TSNAPSHOTS_DICT};
+
+// most days returned by a single snapshot range query
+pub const MAX_SNAPSHOT_RANGE: u64 = 365;
 
 #[derive(Default, Debug, Clone, Copy, CLTyped, ToBytes, FromBytes)]
 pub struct SnapShot {
@@ -25,6 +28,13 @@ pub struct LSnapShot {
     pub inflation_amount: U256,
 }
 
+#[derive(Default, Debug, Clone, Copy, CLTyped, ToBytes, FromBytes)]
+pub struct TSnapShot {
+    pub total_shares: U256,
+    pub inflation_amount: U256,
+    pub scheduled_to_end: U256,
+}
+
 pub struct Snapshots {
     dict: Dict,
 }
@@ -84,3 +94,23 @@ impl LSnapshots {
         self.dict.set(&key.to_string(), value);
     }
 }
+
+pub struct TSnapshots {
+    dict: Dict,
+}
+impl TSnapshots {
+    pub fn instance() -> TSnapshots {
+        TSnapshots {
+            dict: Dict::instance(TSNAPSHOTS_DICT),
+        }
+    }
+    pub fn init() {
+        Dict::init(TSNAPSHOTS_DICT)
+    }
+    pub fn get(&self, key: &U256) -> TSnapShot {
+        self.dict.get(&key.to_string()).unwrap_or_default()
+    }
+    pub fn set(&self, key: &U256, value: TSnapShot) {
+        self.dict.set(&key.to_string(), value);
+    }
+}
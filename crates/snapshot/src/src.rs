@@ -3,6 +3,7 @@ use casper_contract::contract_api::runtime;
 use casper_contract::unwrap_or_revert::UnwrapOrRevert;
 use casper_types::{runtime_args, Key, RuntimeArgs, U128, U256};
 use casperlabs_contract_utils::{ContractContext, ContractStorage};
+use economics::PoolSnapshot;
 use helper::{
     errors::Errors,
     events::{emit, Events},
@@ -17,6 +18,7 @@ pub trait SNAPSHOT<Storage: ContractStorage>: ContractContext<Storage> + HELPER<
         Snapshots::init();
         RSnapshots::init();
         LSnapshots::init();
+        TSnapshots::init();
     }
 
     fn snapshot_trigger(&mut self) {
@@ -92,99 +94,136 @@ pub trait SNAPSHOT<Storage: ContractStorage>: ContractContext<Storage> + HELPER<
     /// @notice internal function that offloads global values to daily snapshots updates globals.currentStakeableDay
     fn _daily_snapshot_point(&mut self, update_day: u64) {
         self.liquidity_guard_trigger();
-        let mut scheduled_to_end_today: U256;
         let total_staked_today: U256 = globals().total_staked;
         let mut day = globals().current_stakeable_day;
         while day < update_day.into() {
             // ------------------------------------
             // prepare snapshot for regular shares
-            // reusing scheduledToEndToday variable
-            scheduled_to_end_today = ScheduledToEnd::instance().get(&day.into())
-                + if day.checked_sub(1.into()).is_some() {
-                    Snapshots::instance()
-                        .get(&(day - 1).into())
-                        .scheduled_to_end
-                } else {
-                    0.into()
-                };
-            let mut snapshot: SnapShot = Snapshots::instance().get(&day.into());
-            snapshot.scheduled_to_end = scheduled_to_end_today;
-            snapshot.total_shares = if globals().total_shares > scheduled_to_end_today {
-                globals().total_shares - scheduled_to_end_today
-            } else {
-                0.into()
-            };
             let total_supply = self.total_supply();
-            snapshot.inflation_amount = snapshot
-                .total_shares
-                .checked_mul(PRECISION_RATE.into())
-                .unwrap_or_revert_with(Errors::MultiplicationOverflow2)
-                .checked_div(self._inflation_amount(
-                    total_staked_today,
-                    total_supply,
-                    TotalPenalties::instance().get(&day.into()),
-                    runtime::call_versioned_contract(
-                        key_to_hash(liquidity_guard(), Errors::InvalidHash7),
-                        None,
-                        "get_inflation",
-                        runtime_args! {
-                            "amount" => inflation_rate()
-                        },
+            let regular: PoolSnapshot = math_or_revert(
+                economics::daily_snapshot(
+                    globals().total_shares,
+                    ScheduledToEnd::instance().get(&day),
+                    if day.checked_sub(1.into()).is_some() {
+                        Snapshots::instance().get(&(day - 1)).scheduled_to_end
+                    } else {
+                        0.into()
+                    },
+                    self._inflation_amount(
+                        total_staked_today,
+                        total_supply,
+                        TotalPenalties::instance().get(&day),
+                        runtime::call_versioned_contract(
+                            key_to_hash(liquidity_guard(), Errors::InvalidHash7),
+                            None,
+                            "get_inflation",
+                            runtime_args! {
+                                "amount" => inflation_rate()
+                            },
+                        ),
                     ),
-                ))
-                .unwrap_or_revert_with(Errors::DivisionByZero1);
+                ),
+                Errors::MultiplicationOverflow2,
+                Errors::DivisionByZero1,
+            );
+            let snapshot: SnapShot = SnapShot {
+                total_shares: regular.total_shares,
+                inflation_amount: regular.inflation_amount,
+                scheduled_to_end: regular.scheduled_to_end,
+            };
             // store regular snapshot
             Snapshots::instance().set(&day.into(), snapshot);
             // ------------------------------------
             // prepare snapshot for referrer shares
-            // reusing scheduledToEndToday variable
-            scheduled_to_end_today = ReferralSharesToEnd::instance().get(&day.into())
-                + if day.checked_sub(1.into()).is_some() {
-                    RSnapshots::instance()
-                        .get(&(day - 1).into())
-                        .scheduled_to_end
-                } else {
-                    0.into()
-                };
-            let mut rsnapshot: RSnapShot = RSnapshots::instance().get(&day.into());
-            rsnapshot.scheduled_to_end = scheduled_to_end_today;
-            rsnapshot.total_shares = if globals().referral_shares > scheduled_to_end_today {
-                globals().referral_shares - scheduled_to_end_today
-            } else {
-                0.into()
+            let referral: PoolSnapshot = math_or_revert(
+                economics::daily_snapshot(
+                    globals().referral_shares,
+                    ReferralSharesToEnd::instance().get(&day),
+                    if day.checked_sub(1.into()).is_some() {
+                        RSnapshots::instance().get(&(day - 1)).scheduled_to_end
+                    } else {
+                        0.into()
+                    },
+                    self._referral_inflation(total_staked_today, total_supply),
+                ),
+                Errors::MultiplicationOverflow3,
+                Errors::DivisionByZero3,
+            );
+            // ------------------------------------
+            // prepare snapshot for tier referrer shares
+            let tier: PoolSnapshot = math_or_revert(
+                economics::daily_snapshot(
+                    tier_shares(),
+                    TierSharesToEnd::instance().get(&day),
+                    if day.checked_sub(1.into()).is_some() {
+                        TSnapshots::instance().get(&(day - 1)).scheduled_to_end
+                    } else {
+                        0.into()
+                    },
+                    self._referral_inflation(total_staked_today, total_supply),
+                ),
+                Errors::MultiplicationOverflow3,
+                Errors::DivisionByZero3,
+            );
+            // tiers are funded from the referral inflation, both pools split it by their shares
+            let referral_inflation_amount: U256 = referral
+                .inflation_amount
+                .checked_add(tier.inflation_amount)
+                .unwrap_or_revert_with(Errors::AdditionOverflow3);
+            let rsnapshot: RSnapShot = RSnapShot {
+                total_shares: referral.total_shares,
+                inflation_amount: referral_inflation_amount,
+                scheduled_to_end: referral.scheduled_to_end,
             };
-            rsnapshot.inflation_amount = rsnapshot
-                .total_shares
-                .checked_mul(PRECISION_RATE.into())
-                .unwrap_or_revert_with(Errors::MultiplicationOverflow3)
-                .checked_div(self._referral_inflation(total_staked_today, total_supply))
-                .unwrap_or_revert_with(Errors::DivisionByZero3);
             // store referral snapshot
             RSnapshots::instance().set(&day.into(), rsnapshot);
+            let tsnapshot: TSnapShot = TSnapShot {
+                total_shares: tier.total_shares,
+                inflation_amount: referral_inflation_amount,
+                scheduled_to_end: tier.scheduled_to_end,
+            };
+            // store tier referral snapshot
+            TSnapshots::instance().set(&day.into(), tsnapshot);
             // ------------------------------------
-            // prepare snapshot for liquidity shares
-            // reusing scheduledToEndToday variable
-            let mut lsnapshot: LSnapShot = LSnapshots::instance().get(&day.into());
-            lsnapshot.total_shares = globals().liquidity_shares;
-            lsnapshot.inflation_amount = lsnapshot
-                .total_shares
-                .checked_mul(PRECISION_RATE.into())
-                .unwrap_or_revert_with(Errors::MultiplicationOverflow4)
-                .checked_div(self._liquidity_inflation(
-                    total_staked_today,
-                    total_supply,
-                    runtime::call_versioned_contract(
-                        key_to_hash(liquidity_guard(), Errors::InvalidHash8),
-                        None,
-                        "get_inflation",
-                        runtime_args! {
-                            "amount" => liquidity_rate()
-                        },
+            // prepare snapshot for liquidity shares, they are never scheduled to end
+            let liquidity: PoolSnapshot = math_or_revert(
+                economics::daily_snapshot(
+                    globals().liquidity_shares,
+                    0.into(),
+                    0.into(),
+                    self._liquidity_inflation(
+                        total_staked_today,
+                        total_supply,
+                        runtime::call_versioned_contract(
+                            key_to_hash(liquidity_guard(), Errors::InvalidHash8),
+                            None,
+                            "get_inflation",
+                            runtime_args! {
+                                "amount" => liquidity_rate()
+                            },
+                        ),
                     ),
-                ))
-                .unwrap_or_revert_with(Errors::DivisionByZero4);
+                ),
+                Errors::MultiplicationOverflow4,
+                Errors::DivisionByZero4,
+            );
+            let lsnapshot: LSnapShot = LSnapShot {
+                total_shares: liquidity.total_shares,
+                inflation_amount: liquidity.inflation_amount,
+            };
             // store liquidity snapshot
             LSnapshots::instance().set(&day.into(), lsnapshot);
+            emit(&Events::DailySnapshot {
+                day,
+                total_shares: snapshot.total_shares,
+                inflation_amount: snapshot.inflation_amount,
+                scheduled_to_end: snapshot.scheduled_to_end,
+                referral_total_shares: rsnapshot.total_shares,
+                referral_inflation_amount: rsnapshot.inflation_amount,
+                referral_scheduled_to_end: rsnapshot.scheduled_to_end,
+                liquidity_total_shares: lsnapshot.total_shares,
+                liquidity_inflation_amount: lsnapshot.inflation_amount,
+            });
             self._adjust_liquidity_rates();
             set_globals({
                 let mut globals = globals();
@@ -197,17 +236,25 @@ pub trait SNAPSHOT<Storage: ContractStorage>: ContractContext<Storage> + HELPER<
 
     /// @notice moves inflation up and down by 0.006% from regular shares to liquidity shares if the liquidityGuard is active (visa-versa)
     fn _adjust_liquidity_rates(&self) {
-        if is_liquidity_guard_active() && liquidity_rate() < INFLATION_RATE_MAX {
-            set_liquidity_rate(liquidity_rate() + 6);
-            set_inflation_rate(inflation_rate() - 6);
-            return;
-        }
-        if !is_liquidity_guard_active() && inflation_rate() < INFLATION_RATE_MAX {
-            set_inflation_rate(inflation_rate() + 6);
-            set_liquidity_rate(liquidity_rate() - 6);
+        if let Some((new_liquidity_rate, new_inflation_rate)) = economics::adjust_liquidity_rates(
+            is_liquidity_guard_active(),
+            liquidity_rate(),
+            inflation_rate(),
+        ) {
+            set_liquidity_rate(new_liquidity_rate);
+            set_inflation_rate(new_inflation_rate);
+            self._log_liquidity_rates();
         }
     }
 
+    fn _log_liquidity_rates(&self) {
+        emit(&Events::LiquidityRatesAdjusted {
+            liquidity_rate: liquidity_rate(),
+            inflation_rate: inflation_rate(),
+            is_liquidity_guard_active: is_liquidity_guard_active(),
+        });
+    }
+
     fn _inflation_amount(
         &self,
         total_staked: U256,
@@ -215,11 +262,61 @@ pub trait SNAPSHOT<Storage: ContractStorage>: ContractContext<Storage> + HELPER<
         total_penalties: U256,
         inflation_rate: U256,
     ) -> U256 {
-        (total_staked + total_supply) * 10000 / inflation_rate + total_penalties
+        math_or_revert(
+            economics::inflation_amount(
+                total_staked,
+                total_supply,
+                total_penalties,
+                inflation_rate,
+            ),
+            Errors::MultiplicationOverflow14,
+            Errors::DivisionByZero15,
+        )
+    }
+
+    /// @notice returns regular snapshots of the days `from_day` to `to_day` inclusive
+    fn snapshot_range(&self, from_day: U256, to_day: U256) -> Vec<SnapShot> {
+        let snapshots = Snapshots::instance();
+        self._snapshot_range(from_day, to_day, |day| snapshots.get(day))
+    }
+
+    /// @notice returns referral snapshots of the days `from_day` to `to_day` inclusive
+    fn rsnapshot_range(&self, from_day: U256, to_day: U256) -> Vec<RSnapShot> {
+        let snapshots = RSnapshots::instance();
+        self._snapshot_range(from_day, to_day, |day| snapshots.get(day))
+    }
+
+    /// @notice returns liquidity snapshots of the days `from_day` to `to_day` inclusive
+    fn lsnapshot_range(&self, from_day: U256, to_day: U256) -> Vec<LSnapShot> {
+        let snapshots = LSnapshots::instance();
+        self._snapshot_range(from_day, to_day, |day| snapshots.get(day))
+    }
+
+    /// @notice reads the snapshot of each day of a range checked against `MAX_SNAPSHOT_RANGE`
+    fn _snapshot_range<T, F: Fn(&U256) -> T>(
+        &self,
+        from_day: U256,
+        to_day: U256,
+        snapshot: F,
+    ) -> Vec<T> {
+        if from_day > to_day || to_day - from_day >= MAX_SNAPSHOT_RANGE.into() {
+            runtime::revert(Errors::InvalidSnapshotRange);
+        }
+        let mut snapshots: Vec<T> = Vec::new();
+        let mut day: U256 = from_day;
+        while day <= to_day {
+            snapshots.push(snapshot(&day));
+            day += 1.into();
+        }
+        snapshots
     }
 
     fn _referral_inflation(&self, total_staked: U256, total_supply: U256) -> U256 {
-        (total_staked + total_supply) * 10000 / REFERRALS_RATE
+        math_or_revert(
+            economics::pool_inflation(total_staked, total_supply, referrals_rate()),
+            Errors::MultiplicationOverflow14,
+            Errors::DivisionByZero15,
+        )
     }
 
     fn _liquidity_inflation(
@@ -228,6 +325,10 @@ pub trait SNAPSHOT<Storage: ContractStorage>: ContractContext<Storage> + HELPER<
         total_supply: U256,
         liquidity_rate: U256,
     ) -> U256 {
-        (total_staked + total_supply) * 10000 / liquidity_rate
+        math_or_revert(
+            economics::pool_inflation(total_staked, total_supply, liquidity_rate),
+            Errors::MultiplicationOverflow14,
+            Errors::DivisionByZero15,
+        )
     }
 }
@@ -6,7 +6,9 @@ use referral_token::{
     errors::Errors,
     events::{emit, Events},
     functions::account_zero_address,
+    pausable::{END_OPERATION, PAUSABLE, SCRAPE_OPERATION, STAKE_OPERATION},
     src::REFERRALTOKEN,
+    views::{StakeQuote, StakeView},
     *,
 };
 
@@ -28,12 +30,35 @@ pub trait STAKINGTOKEN<Storage: ContractStorage>:
         }
     }
 
+    fn create_stake_by_code_bulk(
+        &mut self,
+        staked_amount: Vec<U256>,
+        lock_days: Vec<u64>,
+        code: Vec<String>,
+    ) {
+        for i in 0..staked_amount.len() {
+            let _ = self.create_stake_by_code(staked_amount[i], lock_days[i], code[i].clone());
+        }
+    }
+
+    /// @notice same as create_stake with the referrer looked up by its referral code
+    fn create_stake_by_code(
+        &mut self,
+        staked_amount: U256,
+        lock_days: u64,
+        code: String,
+    ) -> (Vec<u32>, U256, Vec<u32>) {
+        let referrer = self._referrer_by_code(&code);
+        self.create_stake(staked_amount, lock_days, referrer)
+    }
+
     fn create_stake(
         &mut self,
         staked_amount: U256,
         lock_days: u64,
         referrer: Key,
     ) -> (Vec<u32>, U256, Vec<u32>) {
+        self.when_not_paused(STAKE_OPERATION);
         self.snapshot_trigger();
         if self.get_caller() == referrer || !self._not_contract(referrer) {
             runtime::revert(Errors::InvalidReferrer);
@@ -41,10 +66,10 @@ pub trait STAKINGTOKEN<Storage: ContractStorage>:
         if lock_days < MIN_LOCK_DAYS.into() || lock_days > MAX_LOCK_DAYS.into() {
             runtime::revert(Errors::StakeIsNotInRange);
         }
-        if staked_amount < MIN_STAKE_AMOUNT.into() {
+        if staked_amount < min_stake_amount() {
             runtime::revert(Errors::StakeIsNotLargeEnough);
         }
-        let (new_stake, stake_id, start_day) =
+        let (mut new_stake, stake_id, start_day) =
             self._create_stake(self.get_caller(), staked_amount, lock_days, referrer);
         let mut referral_id: Vec<u32> = Default::default();
         if new_stake.referrer_shares > 0.into() {
@@ -57,10 +82,39 @@ pub trait STAKINGTOKEN<Storage: ContractStorage>:
             };
             referral_id = self._generate_referral_id(referrer);
             ReferrerLinks::instance().set(&referrer, &referral_id, referrer_link);
+            emit(&Events::ReferralLinkCreated {
+                referrer,
+                referral_id: referral_id.clone(),
+                staker: self.get_caller(),
+                stake_id: stake_id.clone(),
+                referrer_shares: new_stake.referrer_shares,
+            });
             self._increase_referral_count(referrer);
             self._add_referrer_shares_to_end(new_stake.final_day.into(), new_stake.referrer_shares);
+            self._referrer_cooldown_end(referrer);
+            self._add_referrer_summary_link(
+                referrer,
+                self.get_caller(),
+                stake_id.clone(),
+                new_stake.stakes_shares,
+                self._referral_start_day(new_stake),
+                new_stake.final_day.into(),
+            );
+            new_stake.tier_shares = self._create_tier_links(
+                referrer,
+                self.get_caller(),
+                stake_id.clone(),
+                new_stake.referrer_shares,
+                new_stake.final_day.into(),
+            );
         }
+        self._register_upline(self.get_caller(), new_stake.referrer);
         Stakes::instance().set(&self.get_caller(), &stake_id, new_stake);
+        self._add_account_staked(
+            self.get_caller(),
+            new_stake.staked_amount,
+            new_stake.start_day.into(),
+        );
         self._increase_stake_count(self.get_caller());
         self._increase_globals(
             new_stake.staked_amount,
@@ -71,7 +125,7 @@ pub trait STAKINGTOKEN<Storage: ContractStorage>:
         emit(&Events::StakeStart {
             stake_id: stake_id.clone(),
             staker_address: self.get_caller(),
-            referral_address: referrer,
+            referral_address: new_stake.referrer,
             staked_amount: new_stake.staked_amount,
             stakes_shares: new_stake.stakes_shares,
             referral_shares: new_stake.referrer_shares,
@@ -94,6 +148,13 @@ pub trait STAKINGTOKEN<Storage: ContractStorage>:
         referrer: Key,
     ) -> (Stake, Vec<u32>, u64) {
         self.burn(staker, staked_amount);
+        let referrer: Key =
+            if self._non_zero_address(referrer) && !self._eligible_referrer(referrer) {
+                emit(&Events::ReferrerIgnored { staker, referrer });
+                account_zero_address()
+            } else {
+                referrer
+            };
         let start_day = self._next_stakeable_day();
         let stake_id = self._generate_stake_id(staker);
         let mut new_stake: Stake = Stake {
@@ -119,10 +180,18 @@ pub trait STAKINGTOKEN<Storage: ContractStorage>:
             referrer_shares: Default::default(),
             referrer: account_zero_address(),
             is_active: true,
+            tier_shares: Default::default(),
         };
         if self._non_zero_address(referrer) {
             new_stake.referrer = referrer;
-            self._add_critical_mass(new_stake.referrer, new_stake.dai_equivalent);
+            self._add_critical_mass(
+                new_stake.referrer,
+                staker,
+                stake_id.clone(),
+                new_stake.dai_equivalent,
+                new_stake.lock_days.into(),
+                new_stake.final_day.into(),
+            );
             new_stake.referrer_shares =
                 self._referrer_shares(staked_amount, lock_days.into(), referrer);
         }
@@ -130,8 +199,10 @@ pub trait STAKINGTOKEN<Storage: ContractStorage>:
     }
 
     fn end_stake(&mut self, stake_id: Vec<u32>) -> U256 {
+        self.when_not_paused(END_OPERATION);
         self.snapshot_trigger();
         let (ended_stake, penalty_amount) = self._end_stake(self.get_caller(), stake_id.clone());
+        self._remove_account_staked(self.get_caller(), ended_stake.staked_amount);
         self._decrease_globals(
             ended_stake.staked_amount,
             ended_stake.stakes_shares,
@@ -142,10 +213,24 @@ pub trait STAKINGTOKEN<Storage: ContractStorage>:
             ended_stake.final_day.into(),
             ended_stake.referrer_shares,
         );
+        self._remove_referrer_summary_shares(
+            ended_stake.referrer,
+            self.get_caller(),
+            stake_id.clone(),
+            ended_stake.stakes_shares,
+            self._referral_start_day(ended_stake),
+            ended_stake.final_day.into(),
+            true,
+        );
+        self._remove_tier_shares(ended_stake.tier_shares, ended_stake.final_day.into());
         self._remove_critical_mass(
             ended_stake.referrer,
+            self.get_caller(),
+            stake_id.clone(),
             ended_stake.dai_equivalent,
             ended_stake.start_day.into(),
+            ended_stake.lock_days.into(),
+            ended_stake.final_day.into(),
         );
         self._store_penalty(ended_stake.close_day, penalty_amount);
         self._share_price_update(
@@ -194,11 +279,67 @@ pub trait STAKINGTOKEN<Storage: ContractStorage>:
         (stake, penalty)
     }
 
+    /// @notice returns the principal of a stake without rewards or penalties,
+    /// only available while regular stake ends are paused
+    fn emergency_end_stake(&mut self, stake_id: Vec<u32>) -> U256 {
+        self.when_paused(END_OPERATION);
+        let staker = self.get_caller();
+        let mut stake = Stakes::instance().get(&staker, &stake_id);
+        if !stake.is_active {
+            runtime::revert(Errors::NotAnActiveStake1);
+        }
+        stake.close_day = self._current_stakeable_day();
+        stake.reward_amount = 0.into();
+        stake.is_active = false;
+        Stakes::instance().set(&staker, &stake_id, stake);
+        self.mint(staker, stake.staked_amount);
+        self._remove_account_staked(staker, stake.staked_amount);
+        self._decrease_globals(
+            stake.staked_amount,
+            stake.stakes_shares,
+            stake.referrer_shares,
+        );
+        self._remove_scheduled_shares(stake.final_day.into(), stake.stakes_shares);
+        self._remove_referrer_shares_to_end(stake.final_day.into(), stake.referrer_shares);
+        self._remove_referrer_summary_shares(
+            stake.referrer,
+            staker,
+            stake_id.clone(),
+            stake.stakes_shares,
+            self._referral_start_day(stake),
+            stake.final_day.into(),
+            true,
+        );
+        self._remove_tier_shares(stake.tier_shares, stake.final_day.into());
+        self._remove_critical_mass(
+            stake.referrer,
+            staker,
+            stake_id.clone(),
+            stake.dai_equivalent,
+            stake.start_day.into(),
+            stake.lock_days.into(),
+            stake.final_day.into(),
+        );
+        emit(&Events::StakeEnd {
+            stake_id,
+            staker_address: staker,
+            referral_address: stake.referrer,
+            staked_amount: stake.staked_amount,
+            stakes_shares: stake.stakes_shares,
+            referral_shares: stake.referrer_shares,
+            reward_amount: stake.reward_amount,
+            close_day: stake.close_day.into(),
+            penalty_amount: 0.into(),
+        });
+        stake.staked_amount
+    }
+
     fn scrape_interest(
         &mut self,
         stake_id: Vec<u32>,
         scrape_days: u64,
     ) -> (U256, U256, U256, U256, U256) {
+        self.when_not_paused(SCRAPE_OPERATION);
         self.snapshot_trigger();
         if !Stakes::instance()
             .get(&self.get_caller(), &stake_id)
@@ -244,11 +385,31 @@ pub trait STAKINGTOKEN<Storage: ContractStorage>:
                     account_zero_address(),
                     globals().share_price,
                 );
+                let tier_penalty: U256 = self._penalize_tier_links(
+                    self.get_caller(),
+                    stake_id.clone(),
+                    referrer_penalty,
+                    stake.referrer_shares,
+                    stake.final_day.into(),
+                );
+                stake.tier_shares = stake
+                    .tier_shares
+                    .checked_sub(tier_penalty)
+                    .unwrap_or_revert_with(Errors::SubtractionUnderflow8);
                 stake.referrer_shares = stake
                     .referrer_shares
                     .checked_sub(referrer_penalty)
                     .unwrap_or_revert_with(Errors::SubtractionUnderflow2);
                 self._remove_referrer_shares_to_end(stake.final_day.into(), referrer_penalty);
+                self._remove_referrer_summary_shares(
+                    stake.referrer,
+                    self.get_caller(),
+                    stake_id.clone(),
+                    stakers_penalty,
+                    self._referral_start_day(stake),
+                    stake.final_day.into(),
+                    false,
+                );
             }
             self._decrease_globals(0.into(), stakers_penalty, referrer_penalty);
             self._share_price_update(
@@ -338,7 +499,7 @@ pub trait STAKINGTOKEN<Storage: ContractStorage>:
         lock_days: U256,
         stake_shares: U256,
     ) {
-        if stake_shares > 0.into() && self._current_stakeable_day() > FORMULA_DAY.into() {
+        if stake_shares > 0.into() && U256::from(self._current_stakeable_day()) > formula_day() {
             let mut new_share_price: U256 = self._get_new_share_price(
                 staked_amount,
                 reward_amount,
@@ -347,23 +508,15 @@ pub trait STAKINGTOKEN<Storage: ContractStorage>:
                 referrer,
             );
             if new_share_price > globals().share_price {
-                new_share_price = if new_share_price
-                    < globals()
-                        .share_price
-                        .checked_mul(110.into())
-                        .unwrap_or_revert_with(Errors::MultiplicationOverflow11)
-                        .checked_div(100.into())
-                        .unwrap_or_revert_with(Errors::DivisionByZero9)
-                {
-                    new_share_price
-                } else {
-                    globals()
-                        .share_price
-                        .checked_mul(110.into())
-                        .unwrap_or_revert_with(Errors::MultiplicationOverflow12)
-                        .checked_div(100.into())
-                        .unwrap_or_revert_with(Errors::DivisionByZero10)
-                };
+                new_share_price = math_or_revert(
+                    economics::capped_share_price(
+                        globals().share_price,
+                        new_share_price,
+                        share_price_cap(),
+                    ),
+                    Errors::MultiplicationOverflow11,
+                    Errors::DivisionByZero9,
+                );
                 emit(&Events::NewSharePrice {
                     new_share_price,
                     old_share_price: globals().share_price,
@@ -377,10 +530,10 @@ pub trait STAKINGTOKEN<Storage: ContractStorage>:
             }
             return;
         }
-        if self._current_stakeable_day() == FORMULA_DAY as u64 {
+        if U256::from(self._current_stakeable_day()) == formula_day() {
             set_globals({
                 let mut globals = globals();
-                globals.share_price = 110_000_000.into(); // 110E15 <=> 110E6
+                globals.share_price = economics::FORMULA_DAY_SHARE_PRICE.into();
                 globals
             });
         }
@@ -394,23 +547,17 @@ pub trait STAKINGTOKEN<Storage: ContractStorage>:
         lock_days: U256,
         referrer: Key,
     ) -> U256 {
-        let bonus_amount = self._get_bonus(
-            lock_days,
-            if self._non_zero_address(referrer) {
-                11_000_000_000u64.into()
-            } else {
-                10_000_000_000u64.into()
-            },
-        );
-        staked_amount
-            .checked_add(reward_amount)
-            .unwrap_or_revert_with(Errors::AdditionOverflow17)
-            .checked_mul(bonus_amount)
-            .unwrap_or_revert_with(Errors::MultiplicationOverflow9)
-            .checked_mul(100_000_000.into())
-            .unwrap_or_revert_with(Errors::MultiplicationOverflow10)
-            .checked_div(stake_shares)
-            .unwrap_or_revert_with(Errors::DivisionByZero8)
+        math_or_revert(
+            economics::new_share_price(
+                staked_amount,
+                reward_amount,
+                stake_shares,
+                lock_days,
+                self._non_zero_address(referrer),
+            ),
+            Errors::MultiplicationOverflow9,
+            Errors::DivisionByZero8,
+        )
     }
 
     fn check_mature_stake(&self, staker: Key, stake_id: Vec<u32>) -> bool {
@@ -451,6 +598,102 @@ pub trait STAKINGTOKEN<Storage: ContractStorage>:
         )
     }
 
+    fn stake_view(&self, staker: Key, stake_id: Vec<u32>) -> StakeView {
+        let stake: Stake = Stakes::instance().get(&staker, &stake_id);
+        StakeView {
+            staker,
+            stake_id,
+            start_day: stake.start_day,
+            lock_days: stake.lock_days,
+            final_day: stake.final_day,
+            close_day: stake.close_day,
+            scrape_day: stake.scrape_day,
+            staked_amount: stake.staked_amount,
+            stakes_shares: stake.stakes_shares,
+            referrer_shares: stake.referrer_shares,
+            referrer: stake.referrer,
+            reward_amount: self._check_reward_amount(stake),
+            penalty_amount: self._calculate_penalty_amount(stake),
+            is_active: stake.is_active,
+            is_mature: self._is_mature_stake(stake),
+        }
+    }
+
+    /// @notice projects shares and rewards of a stake without creating it, the reward
+    /// range applies the lowest, average and highest daily rate of recent snapshots
+    fn quote_stake(&self, staked_amount: U256, lock_days: u64, referrer: Key) -> StakeQuote {
+        if lock_days < MIN_LOCK_DAYS.into() || lock_days > MAX_LOCK_DAYS.into() {
+            runtime::revert(Errors::StakeIsNotInRange);
+        }
+        if staked_amount < min_stake_amount() {
+            runtime::revert(Errors::StakeIsNotLargeEnough);
+        }
+        let referrer: Key = if self._non_zero_address(referrer) && self._eligible_referrer(referrer)
+        {
+            referrer
+        } else {
+            account_zero_address()
+        };
+        let share_price: U256 = globals().share_price;
+        let extra_bonus: U256 = economics::extra_bonus(self._non_zero_address(referrer));
+        let stakes_shares: U256 =
+            self._stakes_shares(staked_amount, lock_days.into(), referrer, share_price);
+        let mut quote: StakeQuote = StakeQuote {
+            stakes_shares,
+            referrer_shares: if self._non_zero_address(referrer) {
+                self._referrer_shares(staked_amount, lock_days.into(), referrer)
+            } else {
+                0.into()
+            },
+            bonus_a: math_or_revert(
+                economics::regular_bonus_a(lock_days.into()),
+                Errors::MultiplicationOverflow7,
+                Errors::DivisionByZero7,
+            ),
+            bonus_b: math_or_revert(
+                economics::regular_bonus_b(lock_days.into()),
+                Errors::MultiplicationOverflow8,
+                Errors::DivisionByZero7,
+            ),
+            referral_bonus: extra_bonus - economics::BONUS_PRECISION,
+            total_bonus: self._get_bonus(lock_days.into(), extra_bonus),
+            share_price,
+            ..Default::default()
+        };
+        let last_day: U256 = globals().current_stakeable_day;
+        let mut day: U256 = if last_day > QUOTE_SNAPSHOT_DAYS.into() {
+            last_day - QUOTE_SNAPSHOT_DAYS
+        } else {
+            0.into()
+        };
+        let mut total_daily_reward: U256 = 0.into();
+        while day < last_day {
+            let inflation_amount: U256 = Snapshots::instance().get(&day).inflation_amount;
+            if inflation_amount > 0.into() {
+                let daily_reward: U256 = math_or_revert(
+                    economics::reward_amount(stakes_shares, [inflation_amount]),
+                    Errors::AdditionOverflow19,
+                    Errors::DivisionByZero11,
+                );
+                if quote.sampled_days == 0.into() || daily_reward < quote.min_reward {
+                    quote.min_reward = daily_reward;
+                }
+                if daily_reward > quote.max_reward {
+                    quote.max_reward = daily_reward;
+                }
+                total_daily_reward += daily_reward;
+                quote.sampled_days += 1.into();
+            }
+            day += 1.into();
+        }
+        if quote.sampled_days > 0.into() {
+            quote.average_reward = total_daily_reward / quote.sampled_days * U256::from(lock_days);
+            quote.min_reward = quote.min_reward * U256::from(lock_days);
+            quote.max_reward = quote.max_reward * U256::from(lock_days);
+        }
+        quote
+    }
+
     fn _stakes_shares(
         &self,
         staked_amount: U256,
@@ -458,21 +701,12 @@ pub trait STAKINGTOKEN<Storage: ContractStorage>:
         referrer: Key,
         share_price: U256,
     ) -> U256 {
-        if self._non_zero_address(referrer) {
-            self._shares_amount(
-                staked_amount,
-                lock_days,
-                share_price,
-                11_000_000_000u64.into(),
-            )
-        } else {
-            self._shares_amount(
-                staked_amount,
-                lock_days,
-                share_price,
-                10_000_000_000u64.into(),
-            )
-        }
+        self._shares_amount(
+            staked_amount,
+            lock_days,
+            share_price,
+            economics::extra_bonus(self._non_zero_address(referrer)),
+        )
     }
 
     fn _shares_amount(
@@ -482,58 +716,45 @@ pub trait STAKINGTOKEN<Storage: ContractStorage>:
         share_price: U256,
         extra_bonus: U256,
     ) -> U256 {
-        self._base_amount(staked_amount, share_price)
-            .checked_mul(self._get_bonus(lock_days, extra_bonus))
-            .unwrap_or_revert_with(Errors::MultiplicationOverflow6)
-            .checked_div(10_000_000_000u64.into())
-            .unwrap_or_revert_with(Errors::MultiplicationOverflow6)
+        math_or_revert(
+            economics::shares_amount(staked_amount, lock_days, share_price, extra_bonus),
+            Errors::MultiplicationOverflow6,
+            Errors::DivisionByZero6,
+        )
     }
 
     fn _get_bonus(&self, lock_days: U256, extra_bonus: U256) -> U256 {
-        self._regular_bonus(lock_days, DAILY_BONUS_A.into(), MAX_BONUS_DAYS_A.into())
-            + self._regular_bonus(
-                if lock_days > MAX_BONUS_DAYS_A.into() {
-                    lock_days - MAX_BONUS_DAYS_A
-                } else {
-                    0.into()
-                },
-                DAILY_BONUS_B.into(),
-                MAX_BONUS_DAYS_B.into(),
-            )
-            + extra_bonus
+        math_or_revert(
+            economics::get_bonus(lock_days, extra_bonus),
+            Errors::MultiplicationOverflow7,
+            Errors::DivisionByZero7,
+        )
     }
 
     fn _regular_bonus(&self, lock_days: U256, daily: U256, max_days: U256) -> U256 {
-        (if lock_days > max_days {
-            max_days
-                .checked_mul(daily)
-                .unwrap_or_revert_with(Errors::MultiplicationOverflow7)
-        } else {
-            lock_days
-                .checked_mul(daily)
-                .unwrap_or_revert_with(Errors::MultiplicationOverflow8)
-        })
-        .checked_div(10_000_000_000u64.into())
-        .unwrap_or_revert_with(Errors::DivisionByZero7)
+        math_or_revert(
+            economics::regular_bonus(lock_days, daily, max_days),
+            Errors::MultiplicationOverflow7,
+            Errors::DivisionByZero7,
+        )
     }
 
     fn _base_amount(&self, staked_amount: U256, share_price: U256) -> U256 {
-        staked_amount
-            .checked_mul(PRECISION_RATE.into())
-            .unwrap_or_revert_with(Errors::MultiplicationOverflow6)
-            .checked_div(share_price)
-            .unwrap_or_revert_with(Errors::DivisionByZero6)
+        math_or_revert(
+            economics::base_amount(staked_amount, share_price),
+            Errors::MultiplicationOverflow6,
+            Errors::DivisionByZero6,
+        )
     }
 
     fn _referrer_shares(&self, staked_amount: U256, lock_days: U256, referrer: Key) -> U256 {
-        if self._not_critical_mass_referrer(referrer) || lock_days < MIN_REFERRAL_DAYS.into() {
+        if self._not_critical_mass_referrer(referrer) {
             0.into()
         } else {
-            self._shares_amount(
-                staked_amount,
-                lock_days,
-                globals().share_price,
-                10_000_000_000u64.into(),
+            math_or_revert(
+                economics::referrer_shares(staked_amount, lock_days, globals().share_price),
+                Errors::MultiplicationOverflow6,
+                Errors::DivisionByZero6,
             )
         }
     }
@@ -575,11 +796,15 @@ pub trait STAKINGTOKEN<Storage: ContractStorage>:
     }
 
     fn _get_penalties(&self, stake: Stake) -> U256 {
-        stake.staked_amount
-            * (U256::from(100)
-                + (U256::from(800) * (self._days_left(stake) - U256::from(1))
-                    / (self._get_lock_days(stake))))
-            / 1000
+        math_or_revert(
+            economics::penalties(
+                stake.staked_amount,
+                self._days_left(stake),
+                stake.lock_days.into(),
+            ),
+            Errors::MultiplicationOverflow13,
+            Errors::DivisionByZero14,
+        )
     }
 
     fn _calculate_reward_amount(&self, stake: Stake) -> U256 {
@@ -591,14 +816,14 @@ pub trait STAKINGTOKEN<Storage: ContractStorage>:
     }
 
     fn _loop_reward_amount(&self, stake_shares: U256, start_day: U256, final_day: U256) -> U256 {
-        let mut reward_amount: U256 = 0.into();
-        let mut day: U256 = start_day;
-        while day < final_day {
-            reward_amount += (stake_shares * PRECISION_RATE)
-                .checked_div(Snapshots::instance().get(&day).inflation_amount)
-                .unwrap_or_revert_with(Errors::DivisionByZero11);
-            day += 1.into();
-        }
-        reward_amount
+        math_or_revert(
+            economics::reward_amount(
+                stake_shares,
+                (start_day.as_u64()..final_day.as_u64())
+                    .map(|day| Snapshots::instance().get(&day.into()).inflation_amount),
+            ),
+            Errors::AdditionOverflow19,
+            Errors::DivisionByZero11,
+        )
     }
 }
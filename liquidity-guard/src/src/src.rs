@@ -4,11 +4,13 @@ use casper_types::{Key, U256};
 use casperlabs_contract_utils::{ContractContext, ContractStorage};
 use common::{
     errors::Errors,
+    events::{emit, init_events, Events},
     functions::{set_contract_hash, set_package_hash},
 };
 
 pub trait LIQUIDITYGUARD<Storage: ContractStorage>: ContractContext<Storage> {
     fn init(&mut self, contract_hash: Key, package_hash: Key) {
+        init_events();
         InflationLN::init();
         set_contract_hash(contract_hash);
         set_package_hash(package_hash);
@@ -523,6 +525,9 @@ pub trait LIQUIDITYGUARD<Storage: ContractStorage>: ContractContext<Storage> {
         inflation_ln.set(&102988, 123966332u64.into());
         inflation_ln.set(&102994, 123721514u64.into());
         inflation_ln.set(&103000, 123477676u64.into());
+        emit(&Events::InflationAssigned {
+            sender: self.get_caller(),
+        });
     }
 
     fn get_inflation(&self, amount: u32) -> U256 {
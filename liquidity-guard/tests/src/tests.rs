@@ -1,7 +1,11 @@
-use casper_types::{runtime_args, Key, RuntimeArgs, U256};
+use casper_types::{
+    bytesrepr::{self, Bytes},
+    runtime_args, Key, RuntimeArgs, U256,
+};
 use casperlabs_test_env::TestEnv;
 use tests_common::{
     deploys::deploy_liquidity_guard,
+    events,
     helpers::{call, now, result_dict},
     keys::*,
 };
@@ -26,6 +30,19 @@ fn assign_inflation() {
         103000.to_string(),
     );
     assert_eq!(ret, 123477676u64.into(), "Inflation value not assigned");
+    let events_length: u32 = liquidity_guard.query_named_key("__events_length".into());
+    assert_eq!(events_length, 1, "InflationAssigned not emitted");
+    let event: Bytes = liquidity_guard
+        .query_dictionary("__events", 0.to_string())
+        .expect("Event not stored");
+    let event: events::InflationAssigned = bytesrepr::deserialize(event.into()).unwrap();
+    assert_eq!(
+        event,
+        events::InflationAssigned {
+            sender: Key::Account(owner)
+        },
+        "Wrong sender"
+    );
 }
 
 #[test]
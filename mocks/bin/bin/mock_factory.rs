@@ -0,0 +1,84 @@
+#![no_main]
+
+extern crate alloc;
+use alloc::vec;
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{
+    runtime_args, CLTyped, CLValue, EntryPoint, EntryPointAccess, EntryPointType, EntryPoints,
+    Group, Key, Parameter, RuntimeArgs,
+};
+use casperlabs_contract_utils::{ContractContext, OnChainContractStorage};
+use mocks_crate::{install::install, src::MOCKFACTORY};
+
+#[derive(Default)]
+struct MockFactory(OnChainContractStorage);
+impl ContractContext<OnChainContractStorage> for MockFactory {
+    fn storage(&self) -> &OnChainContractStorage {
+        &self.0
+    }
+}
+impl MOCKFACTORY<OnChainContractStorage> for MockFactory {}
+
+#[no_mangle]
+fn constructor() {
+    let contract_hash: Key = runtime::get_named_arg("contract_hash");
+    let package_hash: Key = runtime::get_named_arg("package_hash");
+    MockFactory::default().init(contract_hash, package_hash);
+}
+
+#[no_mangle]
+fn create_pair() {
+    let token_a: Key = runtime::get_named_arg("token_a");
+    let token_b: Key = runtime::get_named_arg("token_b");
+    let pair_hash: Key = runtime::get_named_arg("pair_hash");
+    MockFactory::default().create_pair(token_a, token_b, pair_hash);
+}
+
+#[no_mangle]
+fn get_pair() {
+    let token_a: Key = runtime::get_named_arg("token_a");
+    let token_b: Key = runtime::get_named_arg("token_b");
+    let ret: Key = MockFactory::default().get_pair(token_a, token_b);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+fn get_entry_points() -> EntryPoints {
+    let mut entry_points = EntryPoints::new();
+    entry_points.add_entry_point(EntryPoint::new(
+        "constructor",
+        vec![
+            Parameter::new("contract_hash", Key::cl_type()),
+            Parameter::new("package_hash", Key::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Groups(vec![Group::new("constructor")]),
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "create_pair",
+        vec![
+            Parameter::new("token_a", Key::cl_type()),
+            Parameter::new("token_b", Key::cl_type()),
+            Parameter::new("pair_hash", Key::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_pair",
+        vec![
+            Parameter::new("token_a", Key::cl_type()),
+            Parameter::new("token_b", Key::cl_type()),
+        ],
+        Key::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    install(get_entry_points(), runtime_args! {});
+}
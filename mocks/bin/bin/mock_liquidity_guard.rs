@@ -0,0 +1,78 @@
+#![no_main]
+
+extern crate alloc;
+use alloc::vec;
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{
+    runtime_args, CLType, CLTyped, CLValue, EntryPoint, EntryPointAccess, EntryPointType,
+    EntryPoints, Group, Key, Parameter, RuntimeArgs, U256,
+};
+use casperlabs_contract_utils::{ContractContext, OnChainContractStorage};
+use mocks_crate::{install::install, src::MOCKLIQUIDITYGUARD};
+
+#[derive(Default)]
+struct MockLiquidityGuard(OnChainContractStorage);
+impl ContractContext<OnChainContractStorage> for MockLiquidityGuard {
+    fn storage(&self) -> &OnChainContractStorage {
+        &self.0
+    }
+}
+impl MOCKLIQUIDITYGUARD<OnChainContractStorage> for MockLiquidityGuard {}
+
+#[no_mangle]
+fn constructor() {
+    let contract_hash: Key = runtime::get_named_arg("contract_hash");
+    let package_hash: Key = runtime::get_named_arg("package_hash");
+    MockLiquidityGuard::default().init(contract_hash, package_hash);
+}
+
+#[no_mangle]
+fn get_inflation() {
+    let amount: u32 = runtime::get_named_arg("amount");
+    let ret: U256 = MockLiquidityGuard::default().get_inflation(amount);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn set_inflation() {
+    let amount: u32 = runtime::get_named_arg("amount");
+    let inflation: U256 = runtime::get_named_arg("inflation");
+    MockLiquidityGuard::default().set_inflation(amount, inflation);
+}
+
+fn get_entry_points() -> EntryPoints {
+    let mut entry_points = EntryPoints::new();
+    entry_points.add_entry_point(EntryPoint::new(
+        "constructor",
+        vec![
+            Parameter::new("contract_hash", Key::cl_type()),
+            Parameter::new("package_hash", Key::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Groups(vec![Group::new("constructor")]),
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_inflation",
+        vec![Parameter::new("amount", u32::cl_type())],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_inflation",
+        vec![
+            Parameter::new("amount", u32::cl_type()),
+            Parameter::new("inflation", U256::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    install(get_entry_points(), runtime_args! {});
+}
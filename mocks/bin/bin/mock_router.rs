@@ -0,0 +1,144 @@
+#![no_main]
+
+extern crate alloc;
+use alloc::{boxed::Box, string::String, vec, vec::Vec};
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{
+    runtime_args, CLType, CLTyped, CLValue, EntryPoint, EntryPointAccess, EntryPointType,
+    EntryPoints, Group, Key, Parameter, RuntimeArgs, U256,
+};
+use casperlabs_contract_utils::{ContractContext, OnChainContractStorage};
+use mocks_crate::{install::install, src::MOCKROUTER};
+
+#[derive(Default)]
+struct MockRouter(OnChainContractStorage);
+impl ContractContext<OnChainContractStorage> for MockRouter {
+    fn storage(&self) -> &OnChainContractStorage {
+        &self.0
+    }
+}
+impl MOCKROUTER<OnChainContractStorage> for MockRouter {}
+
+#[no_mangle]
+fn constructor() {
+    let contract_hash: Key = runtime::get_named_arg("contract_hash");
+    let package_hash: Key = runtime::get_named_arg("package_hash");
+    MockRouter::default().init(contract_hash, package_hash);
+}
+
+#[no_mangle]
+fn set_price() {
+    let token_in: Key = runtime::get_named_arg("token_in");
+    let token_out: Key = runtime::get_named_arg("token_out");
+    let price: U256 = runtime::get_named_arg("price");
+    MockRouter::default().set_price(token_in, token_out, price);
+}
+
+#[no_mangle]
+fn get_amounts_out() {
+    let amount_in: U256 = runtime::get_named_arg("amount_in");
+    let path: Vec<Key> = runtime::get_named_arg("path");
+    let ret: Vec<U256> = MockRouter::default().get_amounts_out(amount_in, path);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn swap_exact_tokens_for_tokens() {
+    let amount_in: U256 = runtime::get_named_arg("amount_in");
+    let amount_out_min: U256 = runtime::get_named_arg("amount_out_min");
+    let path: Vec<String> = runtime::get_named_arg("path");
+    let to: Key = runtime::get_named_arg("to");
+    let ret: Vec<U256> =
+        MockRouter::default().swap_exact_tokens_for_tokens(amount_in, amount_out_min, path, to);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn add_liquidity() {
+    let token_a: Key = runtime::get_named_arg("token_a");
+    let token_b: Key = runtime::get_named_arg("token_b");
+    let amount_a_desired: U256 = runtime::get_named_arg("amount_a_desired");
+    let amount_b_desired: U256 = runtime::get_named_arg("amount_b_desired");
+    let to: Key = runtime::get_named_arg("to");
+    let pair: Option<Key> = runtime::get_named_arg("pair");
+    let ret: (U256, U256, U256) = MockRouter::default().add_liquidity(
+        token_a,
+        token_b,
+        amount_a_desired,
+        amount_b_desired,
+        to,
+        pair,
+    );
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+fn get_entry_points() -> EntryPoints {
+    let mut entry_points = EntryPoints::new();
+    entry_points.add_entry_point(EntryPoint::new(
+        "constructor",
+        vec![
+            Parameter::new("contract_hash", Key::cl_type()),
+            Parameter::new("package_hash", Key::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Groups(vec![Group::new("constructor")]),
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_price",
+        vec![
+            Parameter::new("token_in", Key::cl_type()),
+            Parameter::new("token_out", Key::cl_type()),
+            Parameter::new("price", U256::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_amounts_out",
+        vec![
+            Parameter::new("amount_in", U256::cl_type()),
+            Parameter::new("path", Vec::<Key>::cl_type()),
+        ],
+        CLType::List(Box::new(U256::cl_type())),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "swap_exact_tokens_for_tokens",
+        vec![
+            Parameter::new("amount_in", U256::cl_type()),
+            Parameter::new("amount_out_min", U256::cl_type()),
+            Parameter::new("path", Vec::<String>::cl_type()),
+            Parameter::new("to", Key::cl_type()),
+            Parameter::new("deadline", U256::cl_type()),
+        ],
+        CLType::List(Box::new(U256::cl_type())),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "add_liquidity",
+        vec![
+            Parameter::new("token_a", Key::cl_type()),
+            Parameter::new("token_b", Key::cl_type()),
+            Parameter::new("amount_a_desired", U256::cl_type()),
+            Parameter::new("amount_b_desired", U256::cl_type()),
+            Parameter::new("amount_a_min", U256::cl_type()),
+            Parameter::new("amount_b_min", U256::cl_type()),
+            Parameter::new("to", Key::cl_type()),
+            Parameter::new("pair", Option::<Key>::cl_type()),
+            Parameter::new("deadline", U256::cl_type()),
+        ],
+        <(U256, U256, U256)>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    install(get_entry_points(), runtime_args! {});
+}
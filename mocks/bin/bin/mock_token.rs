@@ -0,0 +1,242 @@
+#![no_main]
+
+extern crate alloc;
+use alloc::vec;
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{
+    runtime_args, CLType, CLTyped, CLValue, EntryPoint, EntryPointAccess, EntryPointType,
+    EntryPoints, Group, Key, Parameter, RuntimeArgs, URef, U128, U256,
+};
+use casperlabs_contract_utils::{ContractContext, OnChainContractStorage};
+use mocks_crate::{
+    install::install,
+    src::{MOCKPAIR, MOCKTOKEN},
+};
+
+// also deployed as the uniswap pair, the LP token then carries the scripted reserves
+
+#[derive(Default)]
+struct MockToken(OnChainContractStorage);
+impl ContractContext<OnChainContractStorage> for MockToken {
+    fn storage(&self) -> &OnChainContractStorage {
+        &self.0
+    }
+}
+impl MOCKTOKEN<OnChainContractStorage> for MockToken {}
+impl MOCKPAIR<OnChainContractStorage> for MockToken {}
+
+#[no_mangle]
+fn constructor() {
+    let contract_hash: Key = runtime::get_named_arg("contract_hash");
+    let package_hash: Key = runtime::get_named_arg("package_hash");
+    MOCKTOKEN::init(&mut MockToken::default(), contract_hash, package_hash);
+}
+
+#[no_mangle]
+fn balance_of() {
+    let owner: Key = runtime::get_named_arg("owner");
+    let ret: U256 = MockToken::default().balance_of(owner);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn total_supply() {
+    let ret: U256 = MockToken::default().total_supply();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn transfer() {
+    let recipient: Key = runtime::get_named_arg("recipient");
+    let amount: U256 = runtime::get_named_arg("amount");
+    let ret: Result<(), u32> = MockToken::default().transfer(recipient, amount);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn transfer_from() {
+    let owner: Key = runtime::get_named_arg("owner");
+    let recipient: Key = runtime::get_named_arg("recipient");
+    let amount: U256 = runtime::get_named_arg("amount");
+    let ret: Result<(), u32> = MockToken::default().transfer_from(owner, recipient, amount);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn approve() {
+    let spender: Key = runtime::get_named_arg("spender");
+    let amount: U256 = runtime::get_named_arg("amount");
+    MockToken::default().approve(spender, amount);
+}
+
+#[no_mangle]
+fn mint() {
+    let to: Key = runtime::get_named_arg("to");
+    let amount: U256 = runtime::get_named_arg("amount");
+    MockToken::default().mint(to, amount);
+}
+
+#[no_mangle]
+fn deposit() {
+    let amount: U256 = runtime::get_named_arg("amount");
+    let purse: URef = runtime::get_named_arg("purse");
+    MockToken::default().deposit(amount, purse);
+}
+
+#[no_mangle]
+fn get_reserves() {
+    let ret: (U128, U128, u64) = MockToken::default().get_reserves();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn set_reserves() {
+    let reserve0: U128 = runtime::get_named_arg("reserve0");
+    let reserve1: U128 = runtime::get_named_arg("reserve1");
+    MockToken::default().set_reserves(reserve0, reserve1);
+}
+
+#[no_mangle]
+fn token0() {
+    let ret: Key = MockToken::default().token0();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn token1() {
+    let ret: Key = MockToken::default().token1();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn set_tokens() {
+    let token0: Key = runtime::get_named_arg("token0");
+    let token1: Key = runtime::get_named_arg("token1");
+    MockToken::default().set_tokens(token0, token1);
+}
+
+fn get_entry_points() -> EntryPoints {
+    let mut entry_points = EntryPoints::new();
+    entry_points.add_entry_point(EntryPoint::new(
+        "constructor",
+        vec![
+            Parameter::new("contract_hash", Key::cl_type()),
+            Parameter::new("package_hash", Key::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Groups(vec![Group::new("constructor")]),
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "balance_of",
+        vec![Parameter::new("owner", Key::cl_type())],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "total_supply",
+        vec![],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "transfer",
+        vec![
+            Parameter::new("recipient", Key::cl_type()),
+            Parameter::new("amount", U256::cl_type()),
+        ],
+        Result::<(), u32>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "transfer_from",
+        vec![
+            Parameter::new("owner", Key::cl_type()),
+            Parameter::new("recipient", Key::cl_type()),
+            Parameter::new("amount", U256::cl_type()),
+        ],
+        Result::<(), u32>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "approve",
+        vec![
+            Parameter::new("spender", Key::cl_type()),
+            Parameter::new("amount", U256::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "mint",
+        vec![
+            Parameter::new("to", Key::cl_type()),
+            Parameter::new("amount", U256::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "deposit",
+        vec![
+            Parameter::new("amount", U256::cl_type()),
+            Parameter::new("purse", URef::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_reserves",
+        vec![],
+        <(U128, U128, u64)>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_reserves",
+        vec![
+            Parameter::new("reserve0", U128::cl_type()),
+            Parameter::new("reserve1", U128::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "token0",
+        vec![],
+        Key::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "token1",
+        vec![],
+        Key::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_tokens",
+        vec![
+            Parameter::new("token0", Key::cl_type()),
+            Parameter::new("token1", Key::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    install(get_entry_points(), runtime_args! {});
+}
@@ -0,0 +1,33 @@
+#![no_main]
+
+extern crate alloc;
+use alloc::string::String;
+use casper_contract::{
+    contract_api::{runtime, system},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{runtime_args, ApiError, Key, RuntimeArgs};
+use common::keys::*;
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let entrypoint: String = runtime::get_named_arg(ENTRYPOINT);
+    let package_hash: Key = runtime::get_named_arg(PACKAGE_HASH);
+
+    match entrypoint.as_str() {
+        // Stakeable_Token, the purse has to be created in the caller's context
+        SET_LIQUIDITY_TRANSFOMER => {
+            let immutable_transformer: Key = runtime::get_named_arg("immutable_transformer");
+            let () = runtime::call_versioned_contract(
+                package_hash.into_hash().unwrap_or_revert().into(),
+                None,
+                SET_LIQUIDITY_TRANSFOMER,
+                runtime_args! {
+                    "immutable_transformer" => immutable_transformer,
+                    "transformer_purse" => system::create_purse()
+                },
+            );
+        }
+        _ => runtime::revert(ApiError::UnexpectedKeyVariant),
+    };
+}
@@ -0,0 +1,161 @@
+extern crate alloc;
+use alloc::string::{String, ToString};
+use casper_types::{ContractPackageHash, Key, U128, U256};
+use casperlabs_contract_utils::{get_key, set_key, Dict};
+use common::{functions::zero_address, keys::*};
+
+pub const PRICE_PRECISION: u64 = 1_000_000_000; // 1E9 <=> 1:1
+pub const DEFAULT_INFLATION: u64 = 60_835_153_328; // liquidity guard value at the initial rates
+
+/// Same keys as the ERC20 contracts, so `balances` is read the same way for mocks
+pub fn key_to_str(key: &Key) -> String {
+    match key {
+        Key::Account(account) => account.to_string(),
+        Key::Hash(package) => ContractPackageHash::new(*package).to_string(),
+        _ => String::new(),
+    }
+}
+
+pub struct Balances {
+    dict: Dict,
+}
+impl Balances {
+    pub fn instance() -> Balances {
+        Balances {
+            dict: Dict::instance(MOCK_BALANCES_DICT),
+        }
+    }
+    pub fn init() {
+        Dict::init(MOCK_BALANCES_DICT)
+    }
+    pub fn get(&self, owner: &Key) -> U256 {
+        self.dict.get(&key_to_str(owner)).unwrap_or_default()
+    }
+    pub fn set(&self, owner: &Key, value: U256) {
+        self.dict.set(&key_to_str(owner), value);
+    }
+}
+
+pub struct Allowances {
+    dict: Dict,
+}
+impl Allowances {
+    pub fn instance() -> Allowances {
+        Allowances {
+            dict: Dict::instance(MOCK_ALLOWANCES_DICT),
+        }
+    }
+    pub fn init() {
+        Dict::init(MOCK_ALLOWANCES_DICT)
+    }
+    pub fn get(&self, owner: &Key, spender: &Key) -> U256 {
+        self.dict
+            .get_by_values((owner, spender))
+            .unwrap_or_default()
+    }
+    pub fn set(&self, owner: &Key, spender: &Key, value: U256) {
+        self.dict.set_by_values((owner, spender), value);
+    }
+}
+
+/// Output per `PRICE_PRECISION` of input for a hop of a swap path, 1:1 until set
+pub struct Prices {
+    dict: Dict,
+}
+impl Prices {
+    pub fn instance() -> Prices {
+        Prices {
+            dict: Dict::instance(MOCK_PRICES_DICT),
+        }
+    }
+    pub fn init() {
+        Dict::init(MOCK_PRICES_DICT)
+    }
+    pub fn get(&self, token_in: &Key, token_out: &Key) -> U256 {
+        self.dict
+            .get_by_values((token_in, token_out))
+            .unwrap_or_else(|| PRICE_PRECISION.into())
+    }
+    pub fn set(&self, token_in: &Key, token_out: &Key, value: U256) {
+        self.dict.set_by_values((token_in, token_out), value);
+    }
+}
+
+pub struct Pairs {
+    dict: Dict,
+}
+impl Pairs {
+    pub fn instance() -> Pairs {
+        Pairs {
+            dict: Dict::instance(MOCK_PAIRS_DICT),
+        }
+    }
+    pub fn init() {
+        Dict::init(MOCK_PAIRS_DICT)
+    }
+    pub fn get(&self, token_a: &Key, token_b: &Key) -> Key {
+        self.dict
+            .get_by_values((token_a, token_b))
+            .unwrap_or_else(zero_address)
+    }
+    pub fn set(&self, token_a: &Key, token_b: &Key, value: Key) {
+        self.dict.set_by_values((token_a, token_b), value);
+    }
+}
+
+/// Inflation returned for a rate, `DEFAULT_INFLATION` until set
+pub struct Inflations {
+    dict: Dict,
+}
+impl Inflations {
+    pub fn instance() -> Inflations {
+        Inflations {
+            dict: Dict::instance(MOCK_INFLATIONS_DICT),
+        }
+    }
+    pub fn init() {
+        Dict::init(MOCK_INFLATIONS_DICT)
+    }
+    pub fn get(&self, key: &u32) -> U256 {
+        self.dict
+            .get(key.to_string().as_str())
+            .unwrap_or_else(|| DEFAULT_INFLATION.into())
+    }
+    pub fn set(&self, key: &u32, value: U256) {
+        self.dict.set(key.to_string().as_str(), value);
+    }
+}
+
+pub fn set_total_supply(total_supply: U256) {
+    set_key(MOCK_TOTAL_SUPPLY, total_supply);
+}
+pub fn total_supply() -> U256 {
+    get_key(MOCK_TOTAL_SUPPLY).unwrap_or_default()
+}
+
+pub fn set_reserves(reserve0: U128, reserve1: U128, block_timestamp_last: u64) {
+    set_key(MOCK_RESERVE0, reserve0);
+    set_key(MOCK_RESERVE1, reserve1);
+    set_key(MOCK_BLOCK_TIMESTAMP_LAST, block_timestamp_last);
+}
+pub fn reserves() -> (U128, U128, u64) {
+    (
+        get_key(MOCK_RESERVE0).unwrap_or_default(),
+        get_key(MOCK_RESERVE1).unwrap_or_default(),
+        get_key(MOCK_BLOCK_TIMESTAMP_LAST).unwrap_or_default(),
+    )
+}
+
+pub fn set_token0(token0: Key) {
+    set_key(MOCK_TOKEN0, token0);
+}
+pub fn token0() -> Key {
+    get_key(MOCK_TOKEN0).unwrap_or_else(zero_address)
+}
+
+pub fn set_token1(token1: Key) {
+    set_key(MOCK_TOKEN1, token1);
+}
+pub fn token1() -> Key {
+    get_key(MOCK_TOKEN1).unwrap_or_else(zero_address)
+}
@@ -0,0 +1,60 @@
+extern crate alloc;
+use alloc::{collections::BTreeSet, format, string::String};
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{contracts::ContractHash, EntryPoints, Key, RuntimeArgs, URef};
+
+/// Installs a mock the way the contract installers do and calls its constructor with
+/// `constructor_args` plus `contract_hash` and `package_hash`. Mocks are never upgraded.
+pub fn install(entry_points: EntryPoints, mut constructor_args: RuntimeArgs) {
+    let contract_name: String = runtime::get_named_arg("contract_name");
+    let (package_hash, access_token) = storage::create_contract_package_at_hash();
+    let (contract_hash, _): (ContractHash, _) =
+        storage::add_contract_version(package_hash, entry_points, Default::default());
+
+    constructor_args
+        .insert("contract_hash", Key::from(contract_hash))
+        .unwrap_or_revert();
+    constructor_args
+        .insert("package_hash", Key::from(package_hash))
+        .unwrap_or_revert();
+
+    // Add the constructor group to the package hash with a single URef.
+    let constructor_access: URef =
+        storage::create_contract_user_group(package_hash, "constructor", 1, Default::default())
+            .unwrap_or_revert()
+            .pop()
+            .unwrap_or_revert();
+
+    let _: () =
+        runtime::call_versioned_contract(package_hash, None, "constructor", constructor_args);
+
+    // Remove all URefs from the constructor group, so no one can call it for the second time.
+    let mut urefs = BTreeSet::new();
+    urefs.insert(constructor_access);
+    storage::remove_contract_user_group_urefs(package_hash, "constructor", urefs)
+        .unwrap_or_revert();
+
+    runtime::put_key(
+        &format!("{}_package_hash", contract_name),
+        package_hash.into(),
+    );
+    runtime::put_key(
+        &format!("{}_package_hash_wrapped", contract_name),
+        storage::new_uref(package_hash).into(),
+    );
+    runtime::put_key(
+        &format!("{}_contract_hash", contract_name),
+        contract_hash.into(),
+    );
+    runtime::put_key(
+        &format!("{}_contract_hash_wrapped", contract_name),
+        storage::new_uref(contract_hash).into(),
+    );
+    runtime::put_key(
+        &format!("{}_package_access_token", contract_name),
+        access_token.into(),
+    );
+}
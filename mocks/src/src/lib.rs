@@ -0,0 +1,7 @@
+//! Stand-ins for the Uniswap, WCSPR/SCSPR and liquidity guard contracts the stakeable token
+//! calls, so its tests run without wasm files from sibling repositories. Every setter is
+//! public, tests script balances, prices and reserves directly.
+
+pub mod data;
+pub mod install;
+pub mod src;
@@ -0,0 +1,239 @@
+extern crate alloc;
+use crate::data::*;
+use alloc::{string::String, vec::Vec};
+use casper_contract::{
+    contract_api::{runtime, system},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{runtime_args, Key, RuntimeArgs, URef, U128, U256, U512};
+use casperlabs_contract_utils::{ContractContext, ContractStorage};
+use common::{errors::Errors, functions::*};
+
+/// ERC20 with an open `mint`, stands in for the stable usd token, WCSPR and SCSPR
+pub trait MOCKTOKEN<Storage: ContractStorage>: ContractContext<Storage> {
+    fn init(&mut self, contract_hash: Key, package_hash: Key) {
+        Balances::init();
+        Allowances::init();
+        set_contract_hash(contract_hash);
+        set_package_hash(package_hash);
+        set_purse(system::create_purse());
+    }
+
+    fn balance_of(&self, owner: Key) -> U256 {
+        Balances::instance().get(&owner)
+    }
+
+    fn total_supply(&self) -> U256 {
+        total_supply()
+    }
+
+    fn transfer(&mut self, recipient: Key, amount: U256) -> Result<(), u32> {
+        self._transfer(self.get_caller(), recipient, amount)
+    }
+
+    fn transfer_from(&mut self, owner: Key, recipient: Key, amount: U256) -> Result<(), u32> {
+        let spender: Key = self.get_caller();
+        let allowance: U256 = Allowances::instance().get(&owner, &spender);
+        if allowance < amount {
+            return Err(Errors::InsufficientAllowance as u32);
+        }
+        Allowances::instance().set(&owner, &spender, allowance - amount);
+        self._transfer(owner, recipient, amount)
+    }
+
+    fn approve(&mut self, spender: Key, amount: U256) {
+        Allowances::instance().set(&self.get_caller(), &spender, amount);
+    }
+
+    fn mint(&mut self, to: Key, amount: U256) {
+        Balances::instance().set(&to, Balances::instance().get(&to) + amount);
+        set_total_supply(total_supply() + amount);
+    }
+
+    /// @notice wraps CSPR like WCSPR/SCSPR, moves `amount` motes from `from_purse` and mints as much
+    fn deposit(&mut self, amount: U256, from_purse: URef) {
+        system::transfer_from_purse_to_purse(
+            from_purse,
+            purse(),
+            U512::from(amount.as_u128()),
+            None,
+        )
+        .unwrap_or_revert();
+        self.mint(self.get_caller(), amount);
+    }
+
+    fn _transfer(&mut self, sender: Key, recipient: Key, amount: U256) -> Result<(), u32> {
+        let balance: U256 = Balances::instance().get(&sender);
+        if balance < amount {
+            return Err(Errors::InsufficientBalance as u32);
+        }
+        Balances::instance().set(&sender, balance - amount);
+        Balances::instance().set(&recipient, Balances::instance().get(&recipient) + amount);
+        Ok(())
+    }
+}
+
+/// Pair side of the mock token, reserves and tokens are set by the tests
+pub trait MOCKPAIR<Storage: ContractStorage>: MOCKTOKEN<Storage> {
+    fn get_reserves(&self) -> (U128, U128, u64) {
+        reserves()
+    }
+
+    fn set_reserves(&mut self, reserve0: U128, reserve1: U128) {
+        set_reserves(reserve0, reserve1, block_timestamp());
+    }
+
+    fn token0(&self) -> Key {
+        token0()
+    }
+
+    fn token1(&self) -> Key {
+        token1()
+    }
+
+    fn set_tokens(&mut self, token0: Key, token1: Key) {
+        set_token0(token0);
+        set_token1(token1);
+    }
+}
+
+/// Records pairs, the pair contracts themselves are deployed by the tests
+pub trait MOCKFACTORY<Storage: ContractStorage>: ContractContext<Storage> {
+    fn init(&mut self, contract_hash: Key, package_hash: Key) {
+        Pairs::init();
+        set_contract_hash(contract_hash);
+        set_package_hash(package_hash);
+    }
+
+    fn create_pair(&mut self, token_a: Key, token_b: Key, pair_hash: Key) {
+        Pairs::instance().set(&token_a, &token_b, pair_hash);
+        Pairs::instance().set(&token_b, &token_a, pair_hash);
+    }
+
+    fn get_pair(&self, token_a: Key, token_b: Key) -> Key {
+        Pairs::instance().get(&token_a, &token_b)
+    }
+}
+
+/// Swaps at scripted prices out of its own balances, tests fund it with the output tokens
+pub trait MOCKROUTER<Storage: ContractStorage>: ContractContext<Storage> {
+    fn init(&mut self, contract_hash: Key, package_hash: Key) {
+        Prices::init();
+        set_contract_hash(contract_hash);
+        set_package_hash(package_hash);
+    }
+
+    /// @param price output per PRICE_PRECISION of input
+    fn set_price(&mut self, token_in: Key, token_out: Key, price: U256) {
+        Prices::instance().set(&token_in, &token_out, price);
+    }
+
+    fn get_amounts_out(&self, amount_in: U256, path: Vec<Key>) -> Vec<U256> {
+        let mut amounts: Vec<U256> = Vec::with_capacity(path.len());
+        amounts.push(amount_in);
+        for hop in path.windows(2) {
+            let amount: U256 = amounts[amounts.len() - 1];
+            amounts.push(amount * Prices::instance().get(&hop[0], &hop[1]) / PRICE_PRECISION);
+        }
+        amounts
+    }
+
+    fn swap_exact_tokens_for_tokens(
+        &mut self,
+        amount_in: U256,
+        amount_out_min: U256,
+        path: Vec<String>,
+        to: Key,
+    ) -> Vec<U256> {
+        let path: Vec<Key> = path
+            .iter()
+            .map(|token| Key::from_formatted_str(token).unwrap_or_revert_with(Errors::InvalidPath))
+            .collect();
+        if path.len() < 2 {
+            runtime::revert(Errors::InvalidPath);
+        }
+        let amounts: Vec<U256> = self.get_amounts_out(amount_in, path.clone());
+        let amount_out: U256 = amounts[amounts.len() - 1];
+        if amount_out < amount_out_min {
+            runtime::revert(Errors::InsufficientOutputAmount);
+        }
+        self._transfer_from(path[0], self.get_caller(), package_hash(), amount_in);
+        self._transfer(path[path.len() - 1], to, amount_out);
+        amounts
+    }
+
+    /// @notice moves both desired amounts into the pair and mints the smaller one as LP tokens,
+    ///     reserves are left to the tests
+    fn add_liquidity(
+        &mut self,
+        token_a: Key,
+        token_b: Key,
+        amount_a_desired: U256,
+        amount_b_desired: U256,
+        to: Key,
+        pair: Option<Key>,
+    ) -> (U256, U256, U256) {
+        let pair: Key = pair.unwrap_or_revert_with(Errors::InvalidPath);
+        self._transfer_from(token_a, self.get_caller(), pair, amount_a_desired);
+        self._transfer_from(token_b, self.get_caller(), pair, amount_b_desired);
+        let liquidity: U256 = amount_a_desired.min(amount_b_desired);
+        let () = runtime::call_versioned_contract(
+            key_to_hash(pair, Errors::InvalidPath),
+            None,
+            "mint",
+            runtime_args! {
+                "to" => to,
+                "amount" => liquidity
+            },
+        );
+        (amount_a_desired, amount_b_desired, liquidity)
+    }
+
+    fn _transfer(&self, token: Key, recipient: Key, amount: U256) {
+        let ret: Result<(), u32> = runtime::call_versioned_contract(
+            key_to_hash(token, Errors::InvalidPath),
+            None,
+            "transfer",
+            runtime_args! {
+                "recipient" => recipient,
+                "amount" => amount
+            },
+        );
+        if ret.is_err() {
+            runtime::revert(Errors::TransferFailed);
+        }
+    }
+
+    fn _transfer_from(&self, token: Key, owner: Key, recipient: Key, amount: U256) {
+        let ret: Result<(), u32> = runtime::call_versioned_contract(
+            key_to_hash(token, Errors::InvalidPath),
+            None,
+            "transfer_from",
+            runtime_args! {
+                "owner" => owner,
+                "recipient" => recipient,
+                "amount" => amount
+            },
+        );
+        if ret.is_err() {
+            runtime::revert(Errors::TransferFromFailed);
+        }
+    }
+}
+
+/// Returns scripted inflations instead of the table of the liquidity guard
+pub trait MOCKLIQUIDITYGUARD<Storage: ContractStorage>: ContractContext<Storage> {
+    fn init(&mut self, contract_hash: Key, package_hash: Key) {
+        Inflations::init();
+        set_contract_hash(contract_hash);
+        set_package_hash(package_hash);
+    }
+
+    fn get_inflation(&self, amount: u32) -> U256 {
+        Inflations::instance().get(&amount)
+    }
+
+    fn set_inflation(&mut self, amount: u32, inflation: U256) {
+        Inflations::instance().set(&amount, inflation);
+    }
+}
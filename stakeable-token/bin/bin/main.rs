@@ -19,7 +19,16 @@ use casper_types::{
     EntryPoints, Group, Key, Parameter, RuntimeArgs, URef, U256, U512,
 };
 use casperlabs_contract_utils::{ContractContext, OnChainContractStorage};
-use stakeable_token_crate::{functions::*, transformer_gate_keeper, *};
+use stakeable_token_crate::{
+    access_control::ACCESSCONTROL,
+    functions::*,
+    pausable::PAUSABLE,
+    transformer_gate_keeper,
+    views::{
+        LiquidityStakeView, Portfolio, ReferralView, ReferrerSummaryView, StakeQuote, StakeView,
+    },
+    *,
+};
 
 #[derive(Default)]
 struct StakeableToken(OnChainContractStorage);
@@ -38,6 +47,8 @@ impl TIMING<OnChainContractStorage> for StakeableToken {}
 impl HELPER<OnChainContractStorage> for StakeableToken {}
 impl SNAPSHOT<OnChainContractStorage> for StakeableToken {}
 impl ERC20<OnChainContractStorage> for StakeableToken {}
+impl ACCESSCONTROL<OnChainContractStorage> for StakeableToken {}
+impl PAUSABLE<OnChainContractStorage> for StakeableToken {}
 
 impl StakeableToken {
     #[allow(clippy::too_many_arguments)]
@@ -92,6 +103,22 @@ fn constructor() {
     );
 }
 
+/// @notice steps the stored state up to the schema of this contract version
+/// @param _contractHash hash of this contract version
+#[no_mangle]
+fn migrate() {
+    let contract_hash: Key = runtime::get_named_arg("contract_hash");
+    StakeableToken::default().migrate(contract_hash);
+}
+
+/// @notice rewrites the stakes of a staker stored before schema version 11 in the current layout
+/// @param _staker address whose stakes are converted
+#[no_mangle]
+fn convert_stakes() {
+    let staker: Key = runtime::get_named_arg("staker");
+    StakeableToken::default().convert_stakes(staker);
+}
+
 /// @notice ability to define liquidity transformer contract
 /// @dev this method renounce transformerGateKeeper access
 /// @param _immutableTransformer contract address
@@ -102,6 +129,135 @@ fn set_liquidity_transfomer() {
     StakeableToken::default().set_liquidity_transfomer(immutable_transformer, transformer_purse);
 }
 
+/// @notice grants a role to an account, caller must hold the admin role
+/// @param _role name of the role (admin, parameter_manager, pauser, oracle_feeder)
+/// @param _account address receiving the role
+#[no_mangle]
+fn grant_role() {
+    let role: String = runtime::get_named_arg("role");
+    let account: Key = runtime::get_named_arg("account");
+    StakeableToken::default().grant_role(role, account);
+}
+
+/// @notice revokes a role from an account, caller must hold the admin role
+#[no_mangle]
+fn revoke_role() {
+    let role: String = runtime::get_named_arg("role");
+    let account: Key = runtime::get_named_arg("account");
+    StakeableToken::default().revoke_role(role, account);
+}
+
+/// @notice allows caller to give up a role it holds
+#[no_mangle]
+fn renounce_role() {
+    let role: String = runtime::get_named_arg("role");
+    StakeableToken::default().renounce_role(role);
+}
+
+#[no_mangle]
+fn has_role() {
+    let role: String = runtime::get_named_arg("role");
+    let account: Key = runtime::get_named_arg("account");
+    let ret: bool = StakeableToken::default().has_role(role, account);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// @notice pauses the given operations, caller must hold the pauser role
+/// @param _operations list of operations (stake, end, scrape, referral, liquidity, cspr)
+#[no_mangle]
+fn pause() {
+    let operations: Vec<String> = runtime::get_named_arg("operations");
+    StakeableToken::default().pause(operations);
+}
+
+/// @notice unpauses the given operations, caller must hold the pauser role
+#[no_mangle]
+fn unpause() {
+    let operations: Vec<String> = runtime::get_named_arg("operations");
+    StakeableToken::default().unpause(operations);
+}
+
+#[no_mangle]
+fn is_paused() {
+    let operation: String = runtime::get_named_arg("operation");
+    let ret: bool = StakeableToken::default().is_paused(operation);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn update_stable_usd() {
+    let stable_usd: Key = runtime::get_named_arg("stable_usd");
+    StakeableToken::default().update_stable_usd(stable_usd);
+}
+
+#[no_mangle]
+fn update_uniswap_router() {
+    let uniswap_router: Key = runtime::get_named_arg("uniswap_router");
+    StakeableToken::default().update_uniswap_router(uniswap_router);
+}
+
+#[no_mangle]
+fn update_uniswap_factory() {
+    let uniswap_factory: Key = runtime::get_named_arg("uniswap_factory");
+    StakeableToken::default().update_uniswap_factory(uniswap_factory);
+}
+
+#[no_mangle]
+fn update_uniswap_pair() {
+    let uniswap_pair: Key = runtime::get_named_arg("uniswap_pair");
+    StakeableToken::default().update_uniswap_pair(uniswap_pair);
+}
+
+#[no_mangle]
+fn update_liquidity_guard() {
+    let liquidity_guard: Key = runtime::get_named_arg("liquidity_guard");
+    StakeableToken::default().update_liquidity_guard(liquidity_guard);
+}
+
+/// @notice allows oracle feeder to set the fallback stable usd equivalent of one stakeable token
+#[no_mangle]
+fn feed_stable_usd_equivalent() {
+    let stable_usd_equivalent: U256 = runtime::get_named_arg("stable_usd_equivalent");
+    StakeableToken::default().feed_stable_usd_equivalent(stable_usd_equivalent);
+}
+
+/// @notice queues a new value for an economic parameter behind the timelock
+/// @param _name name of the parameter
+/// @param _value proposed value
+#[no_mangle]
+fn propose_parameter() {
+    let name: String = runtime::get_named_arg("name");
+    let value: U256 = runtime::get_named_arg("value");
+    StakeableToken::default().propose_parameter(name, value);
+}
+
+/// @notice applies a queued parameter value once its timelock has expired
+#[no_mangle]
+fn execute_parameter() {
+    let name: String = runtime::get_named_arg("name");
+    StakeableToken::default().execute_parameter(name);
+}
+
+#[no_mangle]
+fn cancel_parameter() {
+    let name: String = runtime::get_named_arg("name");
+    StakeableToken::default().cancel_parameter(name);
+}
+
+#[no_mangle]
+fn get_parameter() {
+    let name: String = runtime::get_named_arg("name");
+    let ret: U256 = StakeableToken::default().get_parameter(name);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn get_pending_parameter() {
+    let name: String = runtime::get_named_arg("name");
+    let ret: ParameterProposal = StakeableToken::default().get_pending_parameter(name);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
 /// @notice allows liquidityTransformer to mint supply
 /// @dev executed from liquidityTransformer upon PANCAKESWAP transfer and during reservation payout to contributors and referrers
 /// @param _investorAddress address for minting stakeable tokens
@@ -128,6 +284,36 @@ fn create_stake_with_cspr() {
     runtime::ret(CLValue::from_t((stake_id, start_day, referrer_id)).unwrap_or_revert());
 }
 
+/// @notice same as create_stake_with_cspr with the referrer looked up by its referral code
+/// @param _code referral code claimed by the referrer
+#[no_mangle]
+fn create_stake_with_cspr_by_code() {
+    let lock_days: u64 = runtime::get_named_arg("lock_days");
+    let code: String = runtime::get_named_arg("code");
+    let purse: URef = runtime::get_named_arg("purse");
+    let amount: U256 = runtime::get_named_arg("amount");
+    let (stake_id, start_day, referrer_id): (Vec<u32>, U256, Vec<u32>) =
+        StakeableToken::default().create_stake_with_cspr_by_code(lock_days, code, purse, amount);
+    runtime::ret(CLValue::from_t((stake_id, start_day, referrer_id)).unwrap_or_revert());
+}
+
+/// @notice allows to create a liquidity stake directly with CSPR, half of the wrapped amount is swapped
+///     for stakeable tokens and both sides are added as liquidity, received LP tokens are staked
+/// @param _minOut minimum stakeable tokens expected from swapping half of the amount
+/// @param _minLp minimum LP tokens expected from adding liquidity
+/// @param _deadline deadline for the router calls
+#[no_mangle]
+fn zap_liquidity_stake_with_cspr() {
+    let purse: URef = runtime::get_named_arg("purse");
+    let amount: U256 = runtime::get_named_arg("amount");
+    let min_out: U256 = runtime::get_named_arg("min_out");
+    let min_lp: U256 = runtime::get_named_arg("min_lp");
+    let deadline: U256 = runtime::get_named_arg("deadline");
+    let ret: Vec<u32> = StakeableToken::default()
+        .zap_liquidity_stake_with_cspr(purse, amount, min_out, min_lp, deadline);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
 #[no_mangle]
 fn get_pair_address() {
     let ret: Key = StakeableToken::default().get_pair_address();
@@ -402,6 +588,18 @@ fn referrer_interest_bulk() {
     StakeableToken::default().referrer_interest_bulk(referral_ids, scrape_days);
 }
 
+/// @notice A method for a referrer to collect the interest of all active referrals
+/// @param _cursor index of the first referral, the stored cursor is used when none
+/// @param _maxLinks amount of referrals visited in one call, 0 visits all of them
+#[no_mangle]
+fn collect_all_referral_interest() {
+    let cursor: Option<U256> = runtime::get_named_arg("cursor");
+    let max_links: U256 = runtime::get_named_arg("max_links");
+    let ret: (U256, U256) =
+        StakeableToken::default().collect_all_referral_interest(cursor, max_links);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
 #[no_mangle]
 fn check_referrals_by_id() {
     let referrer: Key = runtime::get_named_arg("referrer");
@@ -429,6 +627,119 @@ fn check_referrals_by_id() {
     runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
 }
 
+/// @notice Returns a referral link and the stake it refers to as a named struct
+/// @param _referrer address of the referrer
+/// @param _referralID identification number of the referral
+#[no_mangle]
+fn referral_view_v1() {
+    let referrer: Key = runtime::get_named_arg("referrer");
+    let referral_id: Vec<u32> = runtime::get_named_arg("referral_id");
+    let ret: ReferralView = StakeableToken::default().referral_view(referrer, referral_id);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// @notice Adds or removes referrers from the allowlist
+/// @param _referrers addresses of the referrers
+/// @param _allowed true to allow, false to remove from the allowlist
+#[no_mangle]
+fn set_allowed_referrers() {
+    let referrers: Vec<Key> = runtime::get_named_arg("referrers");
+    let allowed: bool = runtime::get_named_arg("allowed");
+    StakeableToken::default().set_allowed_referrers(referrers, allowed);
+}
+
+/// @notice Adds or removes referrers from the denylist
+/// @param _referrers addresses of the referrers
+/// @param _denied true to deny, false to remove from the denylist
+#[no_mangle]
+fn set_denied_referrers() {
+    let referrers: Vec<Key> = runtime::get_named_arg("referrers");
+    let denied: bool = runtime::get_named_arg("denied");
+    StakeableToken::default().set_denied_referrers(referrers, denied);
+}
+
+/// @notice Returns the critical mass of a referrer weighted by the remaining lock days
+/// @param _referrer address of the referrer
+#[no_mangle]
+fn decayed_critical_mass() {
+    let referrer: Key = runtime::get_named_arg("referrer");
+    let ret: U256 = StakeableToken::default().decayed_critical_mass(referrer);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn is_eligible_referrer() {
+    let referrer: Key = runtime::get_named_arg("referrer");
+    let ret: bool = StakeableToken::default().is_eligible_referrer(referrer);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// @notice Returns the running referral totals of a referrer
+/// @param _referrer address of the referrer
+#[no_mangle]
+fn referrer_summary() {
+    let referrer: Key = runtime::get_named_arg("referrer");
+    let ret: ReferrerSummaryView = StakeableToken::default().referrer_summary(referrer);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// @notice A method for an upline to collect rewards from a higher tier referral
+/// @param _tierLinkId unique bytes sequence reference to the tier link
+/// @param _scrapeDays amount of days to process, 0 processes all days
+#[no_mangle]
+fn tier_referrer_interest() {
+    let tier_link_id: Vec<u32> = runtime::get_named_arg("tier_link_id");
+    let scrape_days: U256 = runtime::get_named_arg("scrape_days");
+    StakeableToken::default().tier_referrer_interest(tier_link_id, scrape_days);
+}
+
+#[no_mangle]
+fn check_tier_link_by_id() {
+    let upline: Key = runtime::get_named_arg("upline");
+    let tier_link_id: Vec<u32> = runtime::get_named_arg("tier_link_id");
+    let (staker, stake_id, tier, tier_shares, tier_interest, is_active_link, is_active_stake) =
+        StakeableToken::default().check_tier_link_by_id(upline, tier_link_id);
+    let ret: Vec<String> = vec![
+        staker.to_formatted_string(),
+        format!("{:?}", typecast_to_string(stake_id)),
+        tier.to_string(),
+        tier_shares.to_string(),
+        tier_interest.to_string(),
+        is_active_link.to_string(),
+        is_active_stake.to_string(),
+    ];
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// @notice Returns one page of stakes, liquidity stakes, balance and referral interest of an account
+/// @param _account address of the account
+/// @param _stakeCursor index of the first stake of the page
+/// @param _liquidityCursor index of the first liquidity stake of the page
+/// @param _limit page size of each list
+#[no_mangle]
+fn portfolio() {
+    let account: Key = runtime::get_named_arg("account");
+    let stake_cursor: U256 = runtime::get_named_arg("stake_cursor");
+    let liquidity_cursor: U256 = runtime::get_named_arg("liquidity_cursor");
+    let limit: U256 = runtime::get_named_arg("limit");
+    let ret: Portfolio =
+        StakeableToken::default().portfolio(account, stake_cursor, liquidity_cursor, limit);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// @notice Projects shares and rewards of a stake without creating it
+/// @param _stakedAmount amount of STAKEABLE to stake.
+/// @param _lockDays amount of days it would be locked for.
+/// @param _referrer address of the referrer
+#[no_mangle]
+fn quote_stake() {
+    let staked_amount: U256 = runtime::get_named_arg("staked_amount");
+    let lock_days: u64 = runtime::get_named_arg("lock_days");
+    let referrer: Key = runtime::get_named_arg("referrer");
+    let ret: StakeQuote = StakeableToken::default().quote_stake(staked_amount, lock_days, referrer);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
 /// @notice A method for a staker to create multiple stakes
 /// @param _stakedAmount amount of STAKEABLE staked.
 /// @param _lockDays amount of days it is locked for.
@@ -459,6 +770,58 @@ fn create_stake() {
     runtime::ret(CLValue::from_t((stake_id, start_day, referral_id)).unwrap_or_revert());
 }
 
+/// @notice A method for a staker to create multiple stakes using referral codes
+/// @param _code referral codes claimed by the referrers
+#[no_mangle]
+fn create_stake_by_code_bulk() {
+    let staked_amount: Vec<U256> = runtime::get_named_arg("staked_amount");
+    let lock_days: Vec<u64> = runtime::get_named_arg("lock_days");
+    let code: Vec<String> = runtime::get_named_arg("code");
+    StakeableToken::default().create_stake_by_code_bulk(staked_amount, lock_days, code);
+}
+
+/// @notice A method for a staker to create a stake using a referral code
+/// @param _stakedAmount amount of STAKEABLE staked.
+/// @param _lockDays amount of days it is locked for.
+/// @param _code referral code claimed by the referrer
+#[no_mangle]
+fn create_stake_by_code() {
+    let staked_amount: U256 = runtime::get_named_arg("staked_amount");
+    let lock_days: u64 = runtime::get_named_arg("lock_days");
+    let code: String = runtime::get_named_arg("code");
+    let (stake_id, start_day, referral_id): (Vec<u32>, U256, Vec<u32>) =
+        StakeableToken::default().create_stake_by_code(staked_amount, lock_days, code);
+    runtime::ret(CLValue::from_t((stake_id, start_day, referral_id)).unwrap_or_revert());
+}
+
+/// @notice reserves a referral code for the caller
+/// @param _code 3 to 32 characters of lowercase letters, digits, '-' or '_'
+#[no_mangle]
+fn claim_referral_code() {
+    let code: String = runtime::get_named_arg("code");
+    StakeableToken::default().claim_referral_code(code);
+}
+
+/// @notice hands the caller's referral code over to another account
+#[no_mangle]
+fn transfer_referral_code() {
+    let recipient: Key = runtime::get_named_arg("recipient");
+    StakeableToken::default().transfer_referral_code(recipient);
+}
+
+/// @notice gives up the caller's referral code
+#[no_mangle]
+fn release_referral_code() {
+    StakeableToken::default().release_referral_code();
+}
+
+#[no_mangle]
+fn resolve_referral_code() {
+    let code: String = runtime::get_named_arg("code");
+    let ret: Key = StakeableToken::default().resolve_referral_code(code);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
 /// @notice A method for a staker to remove a stake belonging to his address by providing ID of a stake.
 /// @param stake_id unique bytes sequence reference to the stake
 #[no_mangle]
@@ -468,6 +831,15 @@ fn end_stake() {
     runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
 }
 
+/// @notice returns the staked amount without rewards, only while stake ends are paused
+/// @param stake_id unique bytes sequence reference to the stake
+#[no_mangle]
+fn emergency_end_stake() {
+    let stake_id: Vec<u32> = runtime::get_named_arg("stake_id");
+    let ret: U256 = StakeableToken::default().emergency_end_stake(stake_id);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
 /// @notice alloes to scrape interest from active stake
 /// @param stake_id unique bytes sequence reference to the stake
 /// @param scrape_days amount of days to proccess, 0 = all
@@ -528,6 +900,17 @@ fn check_stake_by_id() {
     runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
 }
 
+/// @notice Returns a stake with its reward and penalty as a named struct
+/// @param _staker address of the staker
+/// @param _stakeID identification number of the stake
+#[no_mangle]
+fn stake_view_v1() {
+    let staker: Key = runtime::get_named_arg("staker");
+    let stake_id: Vec<u32> = runtime::get_named_arg("stake_id");
+    let ret: StakeView = StakeableToken::default().stake_view(staker, stake_id);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
 /// @notice A method for a staker to create a liquidity stake
 /// @param _liquidityTokens amount of UNI-STAKEABLE staked.
 #[no_mangle]
@@ -547,6 +930,18 @@ fn end_liquidity_stake() {
     runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
 }
 
+/// @notice A method for a staker to withdraw part of a liquidity stake while keeping the rest staked
+/// @param _liquidityStakeID - identification number
+/// @param _lpAmount - amount of UNI-STAKEABLE to withdraw
+#[no_mangle]
+fn withdraw_liquidity_partial() {
+    let liquidity_stake_id: Vec<u32> = runtime::get_named_arg("liquidity_stake_id");
+    let lp_amount: U256 = runtime::get_named_arg("lp_amount");
+    let ret: U256 =
+        StakeableToken::default().withdraw_liquidity_partial(liquidity_stake_id, lp_amount);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
 /// @notice returns full view and details of a liquidity stake belonging to caller
 /// @param _liquidityStakeID - stakeID
 #[no_mangle]
@@ -565,6 +960,18 @@ fn check_liquidity_stake_by_id() {
     runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
 }
 
+/// @notice Returns a liquidity stake with its reward as a named struct
+/// @param _staker address of the staker
+/// @param _liquidityStakeID identification number of the liquidity stake
+#[no_mangle]
+fn liquidity_stake_view_v1() {
+    let staker: Key = runtime::get_named_arg("staker");
+    let liquidity_stake_id: Vec<u32> = runtime::get_named_arg("liquidity_stake_id");
+    let ret: LiquidityStakeView =
+        StakeableToken::default().liquidity_stake_view(staker, liquidity_stake_id);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
 #[no_mangle]
 fn generate_id() {
     let x: Key = runtime::get_named_arg("x");
@@ -627,13 +1034,46 @@ fn snapshots() {
 #[no_mangle]
 fn rsnapshots() {
     let key: U256 = runtime::get_named_arg("key");
-    runtime::ret(CLValue::from_t(Snapshots::instance().get(&key)).unwrap_or_revert());
+    runtime::ret(CLValue::from_t(RSnapshots::instance().get(&key)).unwrap_or_revert());
 }
 
 #[no_mangle]
 fn lsnapshots() {
     let key: U256 = runtime::get_named_arg("key");
-    runtime::ret(CLValue::from_t(Snapshots::instance().get(&key)).unwrap_or_revert());
+    runtime::ret(CLValue::from_t(LSnapshots::instance().get(&key)).unwrap_or_revert());
+}
+
+/// @notice Returns the regular snapshots for a range of days
+/// @param _fromDay first day of the range
+/// @param _toDay last day of the range, included
+#[no_mangle]
+fn snapshot_range() {
+    let from_day: U256 = runtime::get_named_arg("from_day");
+    let to_day: U256 = runtime::get_named_arg("to_day");
+    let ret: Vec<SnapShot> = StakeableToken::default().snapshot_range(from_day, to_day);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// @notice Returns the referral snapshots for a range of days
+/// @param _fromDay first day of the range
+/// @param _toDay last day of the range, included
+#[no_mangle]
+fn rsnapshot_range() {
+    let from_day: U256 = runtime::get_named_arg("from_day");
+    let to_day: U256 = runtime::get_named_arg("to_day");
+    let ret: Vec<RSnapShot> = StakeableToken::default().rsnapshot_range(from_day, to_day);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// @notice Returns the liquidity snapshots for a range of days
+/// @param _fromDay first day of the range
+/// @param _toDay last day of the range, included
+#[no_mangle]
+fn lsnapshot_range() {
+    let from_day: U256 = runtime::get_named_arg("from_day");
+    let to_day: U256 = runtime::get_named_arg("to_day");
+    let ret: Vec<LSnapShot> = StakeableToken::default().lsnapshot_range(from_day, to_day);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
 }
 
 #[no_mangle]
@@ -735,6 +1175,20 @@ fn get_entry_points() -> EntryPoints {
         EntryPointAccess::Groups(vec![Group::new("constructor")]),
         EntryPointType::Contract,
     ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "migrate",
+        vec![Parameter::new("contract_hash", CLType::Key)],
+        <()>::cl_type(),
+        EntryPointAccess::Groups(vec![Group::new("migrate")]),
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "convert_stakes",
+        vec![Parameter::new("staker", CLType::Key)],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
     entry_points.add_entry_point(EntryPoint::new(
         "set_liquidity_transfomer",
         vec![
@@ -753,58 +1207,231 @@ fn get_entry_points() -> EntryPoints {
         EntryPointType::Contract,
     ));
     entry_points.add_entry_point(EntryPoint::new(
-        "mint_supply",
+        "grant_role",
         vec![
-            Parameter::new("investor_address", CLType::Key),
-            Parameter::new("amount", CLType::U256),
+            Parameter::new("role", String::cl_type()),
+            Parameter::new("account", Key::cl_type()),
         ],
         <()>::cl_type(),
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
     entry_points.add_entry_point(EntryPoint::new(
-        "create_stake_with_cspr",
+        "revoke_role",
         vec![
-            Parameter::new("lock_days", CLType::U64),
-            Parameter::new("referrer", CLType::Key),
-            Parameter::new("purse", CLType::URef),
-            Parameter::new("amount", CLType::U256),
+            Parameter::new("role", String::cl_type()),
+            Parameter::new("account", Key::cl_type()),
         ],
-        CLType::Tuple3([
-            Box::new(CLType::List(Box::new(CLType::U32))),
-            Box::new(CLType::U256),
-            Box::new(CLType::List(Box::new(CLType::U32))),
-        ]),
+        <()>::cl_type(),
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
     entry_points.add_entry_point(EntryPoint::new(
-        "get_liquidity_rate",
-        vec![],
-        CLType::U256,
+        "renounce_role",
+        vec![Parameter::new("role", String::cl_type())],
+        <()>::cl_type(),
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
     entry_points.add_entry_point(EntryPoint::new(
-        "get_scspr",
-        vec![],
-        CLType::Key,
+        "has_role",
+        vec![
+            Parameter::new("role", String::cl_type()),
+            Parameter::new("account", Key::cl_type()),
+        ],
+        CLType::Bool,
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
     entry_points.add_entry_point(EntryPoint::new(
-        "get_uniswap_pair",
-        vec![],
-        CLType::Key,
+        "pause",
+        vec![Parameter::new(
+            "operations",
+            CLType::List(Box::new(String::cl_type())),
+        )],
+        <()>::cl_type(),
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
     entry_points.add_entry_point(EntryPoint::new(
-        "get_inflation_rate",
-        vec![],
-        CLType::U256,
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
+        "unpause",
+        vec![Parameter::new(
+            "operations",
+            CLType::List(Box::new(String::cl_type())),
+        )],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "is_paused",
+        vec![Parameter::new("operation", String::cl_type())],
+        CLType::Bool,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "update_stable_usd",
+        vec![Parameter::new("stable_usd", CLType::Key)],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "update_uniswap_router",
+        vec![Parameter::new("uniswap_router", CLType::Key)],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "update_uniswap_factory",
+        vec![Parameter::new("uniswap_factory", CLType::Key)],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "update_uniswap_pair",
+        vec![Parameter::new("uniswap_pair", CLType::Key)],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "update_liquidity_guard",
+        vec![Parameter::new("liquidity_guard", CLType::Key)],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "feed_stable_usd_equivalent",
+        vec![Parameter::new("stable_usd_equivalent", CLType::U256)],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "propose_parameter",
+        vec![
+            Parameter::new("name", String::cl_type()),
+            Parameter::new("value", CLType::U256),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "execute_parameter",
+        vec![Parameter::new("name", String::cl_type())],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "cancel_parameter",
+        vec![Parameter::new("name", String::cl_type())],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_parameter",
+        vec![Parameter::new("name", String::cl_type())],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_pending_parameter",
+        vec![Parameter::new("name", String::cl_type())],
+        ParameterProposal::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "mint_supply",
+        vec![
+            Parameter::new("investor_address", CLType::Key),
+            Parameter::new("amount", CLType::U256),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "create_stake_with_cspr",
+        vec![
+            Parameter::new("lock_days", CLType::U64),
+            Parameter::new("referrer", CLType::Key),
+            Parameter::new("purse", CLType::URef),
+            Parameter::new("amount", CLType::U256),
+        ],
+        CLType::Tuple3([
+            Box::new(CLType::List(Box::new(CLType::U32))),
+            Box::new(CLType::U256),
+            Box::new(CLType::List(Box::new(CLType::U32))),
+        ]),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "create_stake_with_cspr_by_code",
+        vec![
+            Parameter::new("lock_days", CLType::U64),
+            Parameter::new("code", CLType::String),
+            Parameter::new("purse", CLType::URef),
+            Parameter::new("amount", CLType::U256),
+        ],
+        CLType::Tuple3([
+            Box::new(CLType::List(Box::new(CLType::U32))),
+            Box::new(CLType::U256),
+            Box::new(CLType::List(Box::new(CLType::U32))),
+        ]),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "zap_liquidity_stake_with_cspr",
+        vec![
+            Parameter::new("purse", CLType::URef),
+            Parameter::new("amount", CLType::U256),
+            Parameter::new("min_out", CLType::U256),
+            Parameter::new("min_lp", CLType::U256),
+            Parameter::new("deadline", CLType::U256),
+        ],
+        CLType::List(Box::new(CLType::U32)),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_liquidity_rate",
+        vec![],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_scspr",
+        vec![],
+        CLType::Key,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_uniswap_pair",
+        vec![],
+        CLType::Key,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_inflation_rate",
+        vec![],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
     ));
     entry_points.add_entry_point(EntryPoint::new(
         "create_pair",
@@ -868,20 +1495,50 @@ fn get_entry_points() -> EntryPoints {
     entry_points.add_entry_point(EntryPoint::new(
         "snapshots",
         vec![Parameter::new("key", CLType::U256)],
-        CLType::List(Box::new(SnapShot::cl_type())),
+        SnapShot::cl_type(),
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
     entry_points.add_entry_point(EntryPoint::new(
         "rsnapshots",
         vec![Parameter::new("key", CLType::U256)],
-        CLType::List(Box::new(RSnapShot::cl_type())),
+        RSnapShot::cl_type(),
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
     entry_points.add_entry_point(EntryPoint::new(
         "lsnapshots",
         vec![Parameter::new("key", CLType::U256)],
+        LSnapShot::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "snapshot_range",
+        vec![
+            Parameter::new("from_day", CLType::U256),
+            Parameter::new("to_day", CLType::U256),
+        ],
+        CLType::List(Box::new(SnapShot::cl_type())),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "rsnapshot_range",
+        vec![
+            Parameter::new("from_day", CLType::U256),
+            Parameter::new("to_day", CLType::U256),
+        ],
+        CLType::List(Box::new(RSnapShot::cl_type())),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "lsnapshot_range",
+        vec![
+            Parameter::new("from_day", CLType::U256),
+            Parameter::new("to_day", CLType::U256),
+        ],
         CLType::List(Box::new(LSnapShot::cl_type())),
         EntryPointAccess::Public,
         EntryPointType::Contract,
@@ -896,6 +1553,16 @@ fn get_entry_points() -> EntryPoints {
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "stake_view_v1",
+        vec![
+            Parameter::new("stake_id", CLType::List(Box::new(CLType::U32))),
+            Parameter::new("staker", CLType::Key),
+        ],
+        StakeView::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
     entry_points.add_entry_point(EntryPoint::new(
         "generate_id",
         vec![
@@ -970,6 +1637,77 @@ fn get_entry_points() -> EntryPoints {
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "referral_view_v1",
+        vec![
+            Parameter::new("referral_id", CLType::List(Box::new(CLType::U32))),
+            Parameter::new("referrer", CLType::Key),
+        ],
+        ReferralView::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_allowed_referrers",
+        vec![
+            Parameter::new("referrers", CLType::List(Box::new(CLType::Key))),
+            Parameter::new("allowed", CLType::Bool),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_denied_referrers",
+        vec![
+            Parameter::new("referrers", CLType::List(Box::new(CLType::Key))),
+            Parameter::new("denied", CLType::Bool),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "decayed_critical_mass",
+        vec![Parameter::new("referrer", CLType::Key)],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "is_eligible_referrer",
+        vec![Parameter::new("referrer", CLType::Key)],
+        CLType::Bool,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "referrer_summary",
+        vec![Parameter::new("referrer", CLType::Key)],
+        ReferrerSummaryView::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "check_tier_link_by_id",
+        vec![
+            Parameter::new("tier_link_id", CLType::List(Box::new(CLType::U32))),
+            Parameter::new("upline", CLType::Key),
+        ],
+        CLType::List(Box::new(CLType::String)),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "tier_referrer_interest",
+        vec![
+            Parameter::new("tier_link_id", CLType::List(Box::new(CLType::U32))),
+            Parameter::new("scrape_days", CLType::U256),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
     entry_points.add_entry_point(EntryPoint::new(
         "current_stakeable_day",
         vec![],
@@ -1015,6 +1753,16 @@ fn get_entry_points() -> EntryPoints {
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "collect_all_referral_interest",
+        vec![
+            Parameter::new("cursor", CLType::Option(Box::new(CLType::U256))),
+            Parameter::new("max_links", CLType::U256),
+        ],
+        CLType::Tuple2([Box::new(CLType::U256), Box::new(CLType::U256)]),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
     entry_points.add_entry_point(EntryPoint::new(
         "referrer_interest_bulk",
         vec![
@@ -1039,6 +1787,29 @@ fn get_entry_points() -> EntryPoints {
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "portfolio",
+        vec![
+            Parameter::new("account", CLType::Key),
+            Parameter::new("stake_cursor", CLType::U256),
+            Parameter::new("liquidity_cursor", CLType::U256),
+            Parameter::new("limit", CLType::U256),
+        ],
+        Portfolio::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "quote_stake",
+        vec![
+            Parameter::new("staked_amount", CLType::U256),
+            Parameter::new("lock_days", u64::cl_type()),
+            Parameter::new("referrer", CLType::Key),
+        ],
+        StakeQuote::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
     entry_points.add_entry_point(EntryPoint::new(
         "create_stake",
         vec![
@@ -1054,6 +1825,60 @@ fn get_entry_points() -> EntryPoints {
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "create_stake_by_code_bulk",
+        vec![
+            Parameter::new("staked_amount", CLType::List(Box::new(CLType::U256))),
+            Parameter::new("lock_days", CLType::List(Box::new(CLType::U64))),
+            Parameter::new("code", CLType::List(Box::new(CLType::String))),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "create_stake_by_code",
+        vec![
+            Parameter::new("staked_amount", CLType::U256),
+            Parameter::new("lock_days", u64::cl_type()),
+            Parameter::new("code", CLType::String),
+        ],
+        CLType::Tuple3([
+            Box::new(CLType::List(Box::new(CLType::U32))),
+            Box::new(CLType::U256),
+            Box::new(CLType::List(Box::new(CLType::U32))),
+        ]),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "claim_referral_code",
+        vec![Parameter::new("code", CLType::String)],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "transfer_referral_code",
+        vec![Parameter::new("recipient", CLType::Key)],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "release_referral_code",
+        vec![],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "resolve_referral_code",
+        vec![Parameter::new("code", CLType::String)],
+        CLType::Key,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
     entry_points.add_entry_point(EntryPoint::new(
         "end_stake",
         vec![Parameter::new(
@@ -1064,6 +1889,16 @@ fn get_entry_points() -> EntryPoints {
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "emergency_end_stake",
+        vec![Parameter::new(
+            "stake_id",
+            CLType::List(Box::new(CLType::U32)),
+        )],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
     entry_points.add_entry_point(EntryPoint::new(
         "check_mature_stake",
         vec![
@@ -1091,6 +1926,16 @@ fn get_entry_points() -> EntryPoints {
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "withdraw_liquidity_partial",
+        vec![
+            Parameter::new("liquidity_stake_id", CLType::List(Box::new(CLType::U32))),
+            Parameter::new("lp_amount", CLType::U256),
+        ],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
     entry_points.add_entry_point(EntryPoint::new(
         "check_liquidity_stake_by_id",
         vec![
@@ -1101,6 +1946,16 @@ fn get_entry_points() -> EntryPoints {
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "liquidity_stake_view_v1",
+        vec![
+            Parameter::new("staker", CLType::Key),
+            Parameter::new("liquidity_stake_id", CLType::List(Box::new(CLType::U32))),
+        ],
+        LiquidityStakeView::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
     entry_points.add_entry_point(EntryPoint::new(
         "get_pair_address",
         vec![],
@@ -1348,9 +2203,35 @@ pub extern "C" fn call() {
                 .into_hash()
                 .unwrap()
                 .into();
-        // create new version and install it
+        let old_contract_hash: ContractHash =
+            runtime::get_key(&format!("{}_contract_hash", contract_name))
+                .unwrap_or_revert()
+                .into_hash()
+                .unwrap_or_revert()
+                .into();
+        // create new version and install it, the execution engine carries the named keys
+        // holding the state over from the current version, so it must still be enabled here
         let (contract_hash, _): (ContractHash, _) =
             storage::add_contract_version(package_hash, get_entry_points(), Default::default());
+        // the old version must not write to the shared state anymore
+        storage::disable_contract_version(package_hash, old_contract_hash).unwrap_or_revert();
+
+        // Add the migrate group to the package hash with a single URef.
+        storage::create_contract_user_group(package_hash, "migrate", 1, Default::default())
+            .unwrap_or_revert();
+
+        // Convert the stored state to the layout of the new version
+        let _: () = runtime::call_versioned_contract(
+            package_hash,
+            None,
+            "migrate",
+            runtime_args! {
+                "contract_hash" => Key::from(contract_hash)
+            },
+        );
+
+        // Remove the migrate group, so no one can call it until the next upgrade.
+        storage::remove_contract_user_group(package_hash, "migrate").unwrap_or_revert();
 
         // update contract hash
         runtime::put_key(
@@ -12,7 +12,13 @@ use casper_contract::{
 use casper_types::{
     bytesrepr::ToBytes, runtime_args, ApiError, CLTyped, Key, RuntimeArgs, URef, U256, U512,
 };
-use common::keys::*;
+use common::{
+    keys::*,
+    views::{
+        LiquidityStakeView, Portfolio, ReferralView, ReferrerSummaryView, StakeQuote, StakeView,
+    },
+};
+use num_traits::AsPrimitive;
 
 // Key is the same a destination
 fn store<T: CLTyped + ToBytes>(key: &str, value: T) {
@@ -58,6 +64,42 @@ pub extern "C" fn call() {
             );
             store(CREATE_STAKE_WITH_CSPR, ret);
         }
+        CREATE_STAKE_WITH_CSPR_BY_CODE => {
+            let lock_days: u64 = runtime::get_named_arg("lock_days");
+            let code: String = runtime::get_named_arg("code");
+            let amount: U512 = runtime::get_named_arg("amount");
+            let ret: (Vec<u32>, U256, Vec<u32>) = runtime::call_versioned_contract(
+                package_hash.into_hash().unwrap_or_revert().into(),
+                None,
+                CREATE_STAKE_WITH_CSPR_BY_CODE,
+                runtime_args! {
+                    "lock_days" => lock_days,
+                    "code" => code,
+                    "purse" => purse(amount),
+                    "amount" => <U512 as AsPrimitive<U256>>::as_(amount)
+                },
+            );
+            store(CREATE_STAKE_WITH_CSPR_BY_CODE, ret);
+        }
+        ZAP_LIQUIDITY_STAKE_WITH_CSPR => {
+            let amount: U512 = runtime::get_named_arg("amount");
+            let min_out: U256 = runtime::get_named_arg("min_out");
+            let min_lp: U256 = runtime::get_named_arg("min_lp");
+            let deadline: U256 = runtime::get_named_arg("deadline");
+            let ret: Vec<u32> = runtime::call_versioned_contract(
+                package_hash.into_hash().unwrap_or_revert().into(),
+                None,
+                ZAP_LIQUIDITY_STAKE_WITH_CSPR,
+                runtime_args! {
+                    "purse" => purse(amount),
+                    "amount" => <U512 as AsPrimitive<U256>>::as_(amount),
+                    "min_out" => min_out,
+                    "min_lp" => min_lp,
+                    "deadline" => deadline
+                },
+            );
+            store(ZAP_LIQUIDITY_STAKE_WITH_CSPR, ret);
+        }
         TRANSFER => {
             let recipient: Key = runtime::get_named_arg("recipient");
             let amount: U256 = runtime::get_named_arg("amount");
@@ -102,6 +144,161 @@ pub extern "C" fn call() {
             );
             store(CHECK_REFERRALS_BY_ID, ret);
         }
+        REFERRAL_VIEW_V1 => {
+            let referrer: Key = runtime::get_named_arg("referrer");
+            let referral_id: Vec<u32> = runtime::get_named_arg("referral_id");
+            let ret: ReferralView = runtime::call_versioned_contract(
+                package_hash.into_hash().unwrap_or_revert().into(),
+                None,
+                REFERRAL_VIEW_V1,
+                runtime_args! {
+                    "referrer" => referrer,
+                    "referral_id" => referral_id
+                },
+            );
+            store(REFERRAL_VIEW_V1, ret);
+        }
+        CHECK_TIER_LINK_BY_ID => {
+            let upline: Key = runtime::get_named_arg("upline");
+            let tier_link_id: Vec<u32> = runtime::get_named_arg("tier_link_id");
+            let ret: Vec<String> = runtime::call_versioned_contract(
+                package_hash.into_hash().unwrap_or_revert().into(),
+                None,
+                CHECK_TIER_LINK_BY_ID,
+                runtime_args! {
+                    "tier_link_id" => tier_link_id,
+                    "upline" => upline
+                },
+            );
+            store(CHECK_TIER_LINK_BY_ID, ret);
+        }
+        COLLECT_ALL_REFERRAL_INTEREST => {
+            let cursor: Option<U256> = runtime::get_named_arg("cursor");
+            let max_links: U256 = runtime::get_named_arg("max_links");
+            let ret: (U256, U256) = runtime::call_versioned_contract(
+                package_hash.into_hash().unwrap_or_revert().into(),
+                None,
+                COLLECT_ALL_REFERRAL_INTEREST,
+                runtime_args! {
+                    "cursor" => cursor,
+                    "max_links" => max_links
+                },
+            );
+            store(COLLECT_ALL_REFERRAL_INTEREST, ret);
+        }
+        REFERRER_SUMMARY => {
+            let referrer: Key = runtime::get_named_arg("referrer");
+            let ret: ReferrerSummaryView = runtime::call_versioned_contract(
+                package_hash.into_hash().unwrap_or_revert().into(),
+                None,
+                REFERRER_SUMMARY,
+                runtime_args! {
+                    "referrer" => referrer
+                },
+            );
+            store(REFERRER_SUMMARY, ret);
+        }
+        DECAYED_CRITICAL_MASS => {
+            let referrer: Key = runtime::get_named_arg("referrer");
+            let ret: U256 = runtime::call_versioned_contract(
+                package_hash.into_hash().unwrap_or_revert().into(),
+                None,
+                DECAYED_CRITICAL_MASS,
+                runtime_args! {
+                    "referrer" => referrer
+                },
+            );
+            store(DECAYED_CRITICAL_MASS, ret);
+        }
+        IS_ELIGIBLE_REFERRER => {
+            let referrer: Key = runtime::get_named_arg("referrer");
+            let ret: bool = runtime::call_versioned_contract(
+                package_hash.into_hash().unwrap_or_revert().into(),
+                None,
+                IS_ELIGIBLE_REFERRER,
+                runtime_args! {
+                    "referrer" => referrer
+                },
+            );
+            store(IS_ELIGIBLE_REFERRER, ret);
+        }
+        SNAPSHOT_RANGE => {
+            let from_day: U256 = runtime::get_named_arg("from_day");
+            let to_day: U256 = runtime::get_named_arg("to_day");
+            // records are decoded as tuples with the byte layout of the snapshot structs
+            let ret: Vec<(U256, U256, U256)> = runtime::call_versioned_contract(
+                package_hash.into_hash().unwrap_or_revert().into(),
+                None,
+                SNAPSHOT_RANGE,
+                runtime_args! {
+                    "from_day" => from_day,
+                    "to_day" => to_day
+                },
+            );
+            store(SNAPSHOT_RANGE, ret);
+        }
+        RSNAPSHOT_RANGE => {
+            let from_day: U256 = runtime::get_named_arg("from_day");
+            let to_day: U256 = runtime::get_named_arg("to_day");
+            let ret: Vec<(U256, U256, U256)> = runtime::call_versioned_contract(
+                package_hash.into_hash().unwrap_or_revert().into(),
+                None,
+                RSNAPSHOT_RANGE,
+                runtime_args! {
+                    "from_day" => from_day,
+                    "to_day" => to_day
+                },
+            );
+            store(RSNAPSHOT_RANGE, ret);
+        }
+        LSNAPSHOT_RANGE => {
+            let from_day: U256 = runtime::get_named_arg("from_day");
+            let to_day: U256 = runtime::get_named_arg("to_day");
+            let ret: Vec<(U256, U256)> = runtime::call_versioned_contract(
+                package_hash.into_hash().unwrap_or_revert().into(),
+                None,
+                LSNAPSHOT_RANGE,
+                runtime_args! {
+                    "from_day" => from_day,
+                    "to_day" => to_day
+                },
+            );
+            store(LSNAPSHOT_RANGE, ret);
+        }
+        PORTFOLIO => {
+            let account: Key = runtime::get_named_arg("account");
+            let stake_cursor: U256 = runtime::get_named_arg("stake_cursor");
+            let liquidity_cursor: U256 = runtime::get_named_arg("liquidity_cursor");
+            let limit: U256 = runtime::get_named_arg("limit");
+            let ret: Portfolio = runtime::call_versioned_contract(
+                package_hash.into_hash().unwrap_or_revert().into(),
+                None,
+                PORTFOLIO,
+                runtime_args! {
+                    "account" => account,
+                    "stake_cursor" => stake_cursor,
+                    "liquidity_cursor" => liquidity_cursor,
+                    "limit" => limit
+                },
+            );
+            store(PORTFOLIO, ret);
+        }
+        QUOTE_STAKE => {
+            let staked_amount: U256 = runtime::get_named_arg("staked_amount");
+            let lock_days: u64 = runtime::get_named_arg("lock_days");
+            let referrer: Key = runtime::get_named_arg("referrer");
+            let ret: StakeQuote = runtime::call_versioned_contract(
+                package_hash.into_hash().unwrap_or_revert().into(),
+                None,
+                QUOTE_STAKE,
+                runtime_args! {
+                    "staked_amount" => staked_amount,
+                    "lock_days" => lock_days,
+                    "referrer" => referrer
+                },
+            );
+            store(QUOTE_STAKE, ret);
+        }
         CREATE_STAKE => {
             let staked_amount: U256 = runtime::get_named_arg("staked_amount");
             let lock_days: u64 = runtime::get_named_arg("lock_days");
@@ -118,6 +315,34 @@ pub extern "C" fn call() {
             );
             store(CREATE_STAKE, ret);
         }
+        CREATE_STAKE_BY_CODE => {
+            let staked_amount: U256 = runtime::get_named_arg("staked_amount");
+            let lock_days: u64 = runtime::get_named_arg("lock_days");
+            let code: String = runtime::get_named_arg("code");
+            let ret: (Vec<u32>, U256, Vec<u32>) = runtime::call_versioned_contract(
+                package_hash.into_hash().unwrap_or_revert().into(),
+                None,
+                CREATE_STAKE_BY_CODE,
+                runtime_args! {
+                    "staked_amount" => staked_amount,
+                    "lock_days" => lock_days,
+                    "code" => code
+                },
+            );
+            store(CREATE_STAKE_BY_CODE, ret);
+        }
+        RESOLVE_REFERRAL_CODE => {
+            let code: String = runtime::get_named_arg("code");
+            let ret: Key = runtime::call_versioned_contract(
+                package_hash.into_hash().unwrap_or_revert().into(),
+                None,
+                RESOLVE_REFERRAL_CODE,
+                runtime_args! {
+                    "code" => code
+                },
+            );
+            store(RESOLVE_REFERRAL_CODE, ret);
+        }
         END_STAKE => {
             let stake_id: Vec<u32> = runtime::get_named_arg("stake_id");
             let ret: U256 = runtime::call_versioned_contract(
@@ -173,6 +398,20 @@ pub extern "C" fn call() {
             );
             store(CHECK_STAKE_BY_ID, ret);
         }
+        STAKE_VIEW_V1 => {
+            let staker: Key = runtime::get_named_arg("staker");
+            let stake_id: Vec<u32> = runtime::get_named_arg("stake_id");
+            let ret: StakeView = runtime::call_versioned_contract(
+                package_hash.into_hash().unwrap_or_revert().into(),
+                None,
+                STAKE_VIEW_V1,
+                runtime_args! {
+                    "staker" => staker,
+                    "stake_id" => stake_id
+                },
+            );
+            store(STAKE_VIEW_V1, ret);
+        }
         CREATE_LIQUIDITY_STAKE => {
             let liquidity_tokens: U256 = runtime::get_named_arg("liquidity_tokens");
             let ret: Vec<u32> = runtime::call_versioned_contract(
@@ -197,6 +436,20 @@ pub extern "C" fn call() {
             );
             store(END_LIQUIDITY_STAKE, ret);
         }
+        WITHDRAW_LIQUIDITY_PARTIAL => {
+            let liquidity_stake_id: Vec<u32> = runtime::get_named_arg("liquidity_stake_id");
+            let lp_amount: U256 = runtime::get_named_arg("lp_amount");
+            let ret: U256 = runtime::call_versioned_contract(
+                package_hash.into_hash().unwrap_or_revert().into(),
+                None,
+                WITHDRAW_LIQUIDITY_PARTIAL,
+                runtime_args! {
+                    "liquidity_stake_id" => liquidity_stake_id,
+                    "lp_amount" => lp_amount
+                },
+            );
+            store(WITHDRAW_LIQUIDITY_PARTIAL, ret);
+        }
         CHECK_LIQUIDITY_STAKE_BY_ID => {
             let staker: Key = runtime::get_named_arg("staker");
             let liquidity_stake_id: Vec<u32> = runtime::get_named_arg("liquidity_stake_id");
@@ -211,6 +464,58 @@ pub extern "C" fn call() {
             );
             store(CHECK_LIQUIDITY_STAKE_BY_ID, ret);
         }
+        LIQUIDITY_STAKE_VIEW_V1 => {
+            let staker: Key = runtime::get_named_arg("staker");
+            let liquidity_stake_id: Vec<u32> = runtime::get_named_arg("liquidity_stake_id");
+            let ret: LiquidityStakeView = runtime::call_versioned_contract(
+                package_hash.into_hash().unwrap_or_revert().into(),
+                None,
+                LIQUIDITY_STAKE_VIEW_V1,
+                runtime_args! {
+                    "staker" => staker,
+                    "liquidity_stake_id" => liquidity_stake_id
+                },
+            );
+            store(LIQUIDITY_STAKE_VIEW_V1, ret);
+        }
+        HAS_ROLE => {
+            let role: String = runtime::get_named_arg("role");
+            let account: Key = runtime::get_named_arg("account");
+            let ret: bool = runtime::call_versioned_contract(
+                package_hash.into_hash().unwrap_or_revert().into(),
+                None,
+                HAS_ROLE,
+                runtime_args! {
+                    "role" => role,
+                    "account" => account
+                },
+            );
+            store(HAS_ROLE, ret);
+        }
+        IS_PAUSED => {
+            let operation: String = runtime::get_named_arg("operation");
+            let ret: bool = runtime::call_versioned_contract(
+                package_hash.into_hash().unwrap_or_revert().into(),
+                None,
+                IS_PAUSED,
+                runtime_args! {
+                    "operation" => operation
+                },
+            );
+            store(IS_PAUSED, ret);
+        }
+        GET_PARAMETER => {
+            let name: String = runtime::get_named_arg("name");
+            let ret: U256 = runtime::call_versioned_contract(
+                package_hash.into_hash().unwrap_or_revert().into(),
+                None,
+                GET_PARAMETER,
+                runtime_args! {
+                    "name" => name
+                },
+            );
+            store(GET_PARAMETER, ret);
+        }
         GET_STABLE_USD_EQUIVALENT => {
             let ret: U256 = runtime::call_versioned_contract(
                 package_hash.into_hash().unwrap_or_revert().into(),
@@ -2,9 +2,32 @@ use casper_types::{Key, URef};
 use casperlabs_contract_utils::{get_key, set_key};
 use common::{
     functions::zero_address,
-    keys::{LIQUIDITY_TRANSFORMER, LIQUIDITY_TRANSFORMER_PURSE, TRANSFORMER_GATE_KEEPER},
+    keys::{
+        LIQUIDITY_TRANSFORMER, LIQUIDITY_TRANSFORMER_PURSE, SCHEMA_VERSION, TRANSFORMER_GATE_KEEPER,
+    },
 };
 
+// layout version of the stored state, bump it together with a new step in
+// `STAKEABLETOKEN::_migrate_step` whenever a stored struct such as `Stake` changes
+// 0 - baseline release, installed before `schema_version` was stored
+// 1 - initial upgradeable release with roles, pausing and timelocked parameters
+// 2 - events are emitted through the Casper Event Standard
+// 3 - referral link, critical mass, daily snapshot and liquidity rate events
+// 4 - referral code registry
+// 5 - multi-tier referral rewards
+// 6 - referral collection cursor
+// 7 - referrer summary aggregates, links created before are not counted
+// 8 - anti-sybil referrer controls
+// 9 - decaying critical mass, stakes created before do not decay
+// 10 - liquidity stake scrape days in their own dictionary, `LiquidityStake` keeps the
+//      layout of the initial release
+// 11 - stakes keep their tier shares, stored in `stakes_v11_dict`, older stakes are
+//      converted from `stakes_dict` and `StakeTierShares` by `convert_stakes`, the tier
+//      links of a stake are kept with it, scrapes of older stakes leave their tier links as they are
+pub const CURRENT_SCHEMA_VERSION: u32 = 11;
+
+pub const PORTFOLIO_PAGE_LIMIT: u64 = 50;
+
 pub fn set_liquidity_transformer(liquidity_transformer: Key, transformer_purse: URef) {
     set_key(LIQUIDITY_TRANSFORMER, liquidity_transformer);
     set_key(LIQUIDITY_TRANSFORMER_PURSE, transformer_purse);
@@ -22,3 +45,10 @@ pub fn set_transformer_gate_keeper(transformer_gate_keeper: Key) {
 pub fn transformer_gate_keeper() -> Key {
     get_key(TRANSFORMER_GATE_KEEPER).unwrap_or_else(zero_address)
 }
+
+pub fn set_schema_version(schema_version: u32) {
+    set_key(SCHEMA_VERSION, schema_version);
+}
+pub fn schema_version() -> u32 {
+    get_key(SCHEMA_VERSION).unwrap_or_default()
+}
@@ -6,8 +6,12 @@ use casper_contract::{
 use casper_types::{runtime_args, Key, RuntimeArgs, URef, U256};
 use casperlabs_contract_utils::{ContractContext, ContractStorage};
 use common::{
+    access_control::{ACCESSCONTROL, ADMIN_ROLE, ORACLE_FEEDER_ROLE},
     errors::Errors,
+    events::{emit, init_events, update_event_schemas, Events},
     functions::{set_package_hash, *},
+    pausable::{CSPR_OPERATION, LIQUIDITY_OPERATION, PAUSABLE, STAKE_OPERATION},
+    views::{Portfolio, PortfolioLiquidityStake, PortfolioStake},
 };
 use liquidity_token::*;
 use num_traits::AsPrimitive;
@@ -50,6 +54,7 @@ pub trait STAKEABLETOKEN<Storage: ContractStorage>:
         set_contract_hash(contract_hash);
         set_package_hash(package_hash);
         set_purse(system::create_purse());
+        set_schema_version(CURRENT_SCHEMA_VERSION);
     }
 
     fn set_liquidity_transfomer(&self, immutable_transformer: Key, transformer_purse: URef) {
@@ -60,6 +65,138 @@ pub trait STAKEABLETOKEN<Storage: ContractStorage>:
         set_transformer_gate_keeper(account_zero_address());
     }
 
+    fn update_stable_usd(&self, stable_usd: Key) {
+        self.only_role(ADMIN_ROLE);
+        set_stable_usd(stable_usd);
+    }
+
+    fn update_uniswap_router(&self, uniswap_router: Key) {
+        self.only_role(ADMIN_ROLE);
+        set_uniswap_router(uniswap_router);
+    }
+
+    fn update_uniswap_factory(&self, uniswap_factory: Key) {
+        self.only_role(ADMIN_ROLE);
+        set_uniswap_factory(uniswap_factory);
+    }
+
+    fn update_uniswap_pair(&self, uniswap_pair: Key) {
+        self.only_role(ADMIN_ROLE);
+        set_uniswap_pair(uniswap_pair);
+    }
+
+    fn update_liquidity_guard(&self, liquidity_guard: Key) {
+        self.only_role(ADMIN_ROLE);
+        set_liquidity_guard(liquidity_guard);
+    }
+
+    /// @notice fallback price used when the router returns no amounts for the stable usd path
+    fn feed_stable_usd_equivalent(&self, stable_usd_equivalent: U256) {
+        self.only_role(ORACLE_FEEDER_ROLE);
+        set_latest_stable_usd_equivalent(stable_usd_equivalent);
+    }
+
+    /// @notice runs after a new contract version was added to the package,
+    ///     steps the stored state up to `CURRENT_SCHEMA_VERSION`
+    /// @dev only callable by the installer through the `migrate` group
+    /// @param _contractHash hash of the newly added contract version
+    fn migrate(&mut self, contract_hash: Key) {
+        let from_version = schema_version();
+        if from_version > CURRENT_SCHEMA_VERSION {
+            runtime::revert(Errors::UnsupportedSchemaVersion);
+        }
+        for version in from_version..CURRENT_SCHEMA_VERSION {
+            self._migrate_step(version);
+        }
+        set_contract_hash(contract_hash);
+        set_schema_version(CURRENT_SCHEMA_VERSION);
+        emit(&Events::Migrated {
+            contract_hash,
+            from_version,
+            to_version: CURRENT_SCHEMA_VERSION,
+        });
+    }
+
+    /// @notice converts the state stored under `version` to `version + 1`
+    /// @dev dictionaries cannot be iterated, a step that changes the layout of a
+    ///     stored struct adds a dictionary for the new layout, records stored before
+    ///     are read from the old one until `convert_stakes` rewrites them
+    fn _migrate_step(&mut self, version: u32) {
+        match version {
+            0 => {
+                // the installer of the baseline release is the first admin
+                ACCESSCONTROL::init(self, self.get_caller());
+                PAUSABLE::init(self);
+                Parameters::init();
+                PendingParameters::init();
+            }
+            1 => init_events(),
+            2 => update_event_schemas(),
+            3 => {
+                ReferralCodes::init();
+                ReferrerCodes::init();
+                update_event_schemas();
+            }
+            4 => {
+                Uplines::init();
+                TierLinkCount::init();
+                TierLinks::init();
+                StakeTierShares::init();
+                TierSharesToEnd::init();
+                TSnapshots::init();
+                update_event_schemas();
+            }
+            5 => {
+                ReferralCursors::init();
+                update_event_schemas();
+            }
+            6 => {
+                ReferrerSummaries::init();
+                ReferrerSharesStarting::init();
+                ReferrerSharesEnding::init();
+                // links are marked as counted from the version that introduced the summaries
+                SummaryCounted::init();
+            }
+            7 => {
+                AllowedReferrers::init();
+                DeniedReferrers::init();
+                AccountStaked::init();
+                FirstStakeDay::init();
+                ReferrerCooldownEnds::init();
+                update_event_schemas();
+            }
+            8 => {
+                DecayingCriticalMass::init();
+                DecayingMassEnding::init();
+                // stakes are marked as recorded from the version that introduced the decay
+                DecayingMassRecorded::init();
+            }
+            9 => LiquidityScrapes::init(),
+            10 => {
+                Stakes::init();
+                StakeTierLinks::init();
+            }
+            _ => runtime::revert(Errors::MissingMigration),
+        }
+    }
+
+    /// @notice rewrites the stakes of `staker` stored before schema version 11
+    ///     in the current layout, stakes already in it are left untouched
+    /// @dev anyone can call it, the converted stake holds the same values
+    /// @param _staker address whose stakes are converted
+    fn convert_stakes(&self, staker: Key) {
+        let stake_count: U256 = StakeCount::instance().get(&staker);
+        let mut index: U256 = 0.into();
+        while index < stake_count {
+            let stake_id: Vec<u32> = self.generate_id(staker, index, 0x01);
+            if Stakes::instance().is_legacy(&staker, &stake_id) {
+                let stake: Stake = Stakes::instance().get(&staker, &stake_id);
+                Stakes::instance().set(&staker, &stake_id, stake);
+            }
+            index += 1.into();
+        }
+    }
+
     fn mint_supply(&mut self, investor_address: Key, amount: U256) {
         if self.get_caller() != liquidity_transformer().0 {
             runtime::revert(Errors::WrongTransformer);
@@ -74,6 +211,26 @@ pub trait STAKEABLETOKEN<Storage: ContractStorage>:
         _purse: URef,
         amount: U256,
     ) -> (Vec<u32>, U256, Vec<u32>) {
+        self.when_not_paused(CSPR_OPERATION);
+        self.when_not_paused(STAKE_OPERATION);
+        self._deposit_cspr(_purse, amount);
+        self._create_stake_with_scspr(self.get_caller(), amount, lock_days, referrer)
+    }
+
+    /// @notice same as create_stake_with_cspr with the referrer looked up by its referral code
+    fn create_stake_with_cspr_by_code(
+        &mut self,
+        lock_days: u64,
+        code: String,
+        _purse: URef,
+        amount: U256,
+    ) -> (Vec<u32>, U256, Vec<u32>) {
+        let referrer = self._referrer_by_code(&code);
+        self.create_stake_with_cspr(lock_days, referrer, _purse, amount)
+    }
+
+    /// @notice pulls CSPR from the provided purse and wraps it into SCSPR held by the contract
+    fn _deposit_cspr(&self, _purse: URef, amount: U256) {
         // Payable
         system::transfer_from_purse_to_purse(
             _purse,
@@ -91,7 +248,111 @@ pub trait STAKEABLETOKEN<Storage: ContractStorage>:
                 "purse" => purse()
             },
         );
-        self._create_stake_with_scspr(self.get_caller(), amount, lock_days, referrer)
+    }
+
+    /// @notice wraps CSPR to SCSPR, swaps half of it for STAKEABLE, adds both sides as liquidity
+    ///     and opens a liquidity stake with the received LP tokens, unused dust is returned to caller
+    /// @param _purse purse holding the CSPR to be used
+    /// @param amount amount of CSPR to zap
+    /// @param min_out minimum amount of STAKEABLE the swapped half must return, also the
+    ///     minimum of both sides added as liquidity in proportion to the swap result
+    /// @param min_lp minimum amount of LP tokens that must be minted
+    /// @param deadline deadline passed to the router for swap and add liquidity
+    fn zap_liquidity_stake_with_cspr(
+        &mut self,
+        _purse: URef,
+        amount: U256,
+        min_out: U256,
+        min_lp: U256,
+        deadline: U256,
+    ) -> Vec<u32> {
+        self.when_not_paused(CSPR_OPERATION);
+        self.when_not_paused(LIQUIDITY_OPERATION);
+        self.snapshot_trigger();
+        if !is_liquidity_guard_active() {
+            runtime::revert(Errors::LiquidityGuardIsNotActive);
+        }
+        self._deposit_cspr(_purse, amount);
+        let swap_amount: U256 = amount / 2;
+        let scspr_amount: U256 = amount
+            .checked_sub(swap_amount)
+            .unwrap_or_revert_with(Errors::SubtractionUnderflow1);
+        let () = runtime::call_versioned_contract(
+            key_to_hash(scspr(), Errors::InvalidHash11),
+            None,
+            "approve",
+            runtime_args! {
+                "spender" => uniswap_router(),
+                "amount" => amount,
+            },
+        );
+        let path = vec![
+            scspr().to_formatted_string(),
+            package_hash().to_formatted_string(),
+        ];
+        let amounts: Vec<U256> = runtime::call_versioned_contract(
+            key_to_hash(uniswap_router(), Errors::InvalidHash12),
+            None,
+            "swap_exact_tokens_for_tokens",
+            runtime_args! {
+                "amount_in" => swap_amount,
+                "amount_out_min" => min_out,
+                "path" => path,
+                "to" => package_hash(),
+                "deadline" => deadline
+            },
+        );
+        let stakeable_amount: U256 = amounts[1];
+        // the slippage accepted on the swap applies to both sides of the liquidity
+        let scspr_min: U256 = scspr_amount
+            .checked_mul(min_out)
+            .unwrap_or_revert_with(Errors::MultiplicationOverflow15)
+            .checked_div(stakeable_amount)
+            .unwrap_or_revert_with(Errors::DivisionByZero16);
+        self._approve(package_hash(), uniswap_router(), stakeable_amount);
+        let (scspr_used, stakeable_used, liquidity): (U256, U256, U256) =
+            runtime::call_versioned_contract(
+                key_to_hash(uniswap_router(), Errors::InvalidHash12),
+                None,
+                "add_liquidity",
+                runtime_args! {
+                    "token_a" => scspr(),
+                    "token_b" => package_hash(),
+                    "amount_a_desired" => scspr_amount,
+                    "amount_b_desired" => stakeable_amount,
+                    "amount_a_min" => scspr_min,
+                    "amount_b_min" => min_out,
+                    "to" => package_hash(),
+                    "pair" => Some(uniswap_pair()),
+                    "deadline" => deadline
+                },
+            );
+        if liquidity < min_lp {
+            runtime::revert(Errors::LiquidityBelowMinimum);
+        }
+        let () = runtime::call_versioned_contract(
+            key_to_hash(scspr(), Errors::InvalidHash11),
+            None,
+            "approve",
+            runtime_args! {
+                "spender" => uniswap_router(),
+                "amount" => U256::from(0),
+            },
+        );
+        self._approve(package_hash(), uniswap_router(), 0.into());
+        // return dust
+        if scspr_amount > scspr_used {
+            self._transfer(scspr(), self.get_caller(), scspr_amount - scspr_used);
+        }
+        if stakeable_amount > stakeable_used {
+            self.make_transfer(
+                package_hash(),
+                self.get_caller(),
+                stakeable_amount - stakeable_used,
+            )
+            .unwrap_or_revert_with(Errors::TransferFailed);
+        }
+        self._create_liquidity_stake(self.get_caller(), liquidity)
     }
 
     fn _create_stake_with_scspr(
@@ -144,4 +405,111 @@ pub trait STAKEABLETOKEN<Storage: ContractStorage>:
     fn get_synthetic_token_address(&self) -> Key {
         scspr()
     }
+
+    /// @notice returns one page of an account's stakes and liquidity stakes with pending
+    /// rewards and penalties, the balance and the claimable referral interest
+    /// @param _account address of the account
+    /// @param _stakeCursor index of the first stake of the page
+    /// @param _liquidityCursor index of the first liquidity stake of the page
+    /// @param _limit page size of each list, capped at `PORTFOLIO_PAGE_LIMIT`
+    fn portfolio(
+        &self,
+        account: Key,
+        stake_cursor: U256,
+        liquidity_cursor: U256,
+        limit: U256,
+    ) -> Portfolio {
+        let limit: U256 = if limit == 0.into() || limit > PORTFOLIO_PAGE_LIMIT.into() {
+            PORTFOLIO_PAGE_LIMIT.into()
+        } else {
+            limit
+        };
+        let mut portfolio: Portfolio = Portfolio {
+            balance: self.balance_of(account),
+            claimable_referral_interest: self.referrer_summary(account).claimable,
+            stake_count: StakeCount::instance().get(&account),
+            liquidity_stake_count: LiquidityStakeCount::instance().get(&account),
+            ..Default::default()
+        };
+        let stake_end: U256 = stake_cursor
+            .saturating_add(limit)
+            .min(portfolio.stake_count);
+        let mut index: U256 = stake_cursor;
+        while index < stake_end {
+            let stake_id: Vec<u32> = self.generate_id(account, index, 0x01);
+            let stake: Stake = Stakes::instance().get(&account, &stake_id);
+            let view: PortfolioStake = PortfolioStake {
+                stake_id,
+                staked_amount: stake.staked_amount,
+                stakes_shares: stake.stakes_shares,
+                start_day: stake.start_day,
+                final_day: stake.final_day,
+                close_day: stake.close_day,
+                is_active: stake.is_active,
+                is_mature: self._is_mature_stake(stake),
+                reward_amount: self._check_reward_amount(stake),
+                penalty_amount: if stake.is_active {
+                    self._calculate_penalty_amount(stake)
+                } else {
+                    0.into()
+                },
+            };
+            if view.is_active {
+                portfolio.total_staked = portfolio
+                    .total_staked
+                    .checked_add(view.staked_amount)
+                    .unwrap_or_revert_with(Errors::AdditionOverflow20);
+            }
+            portfolio.total_rewards = portfolio
+                .total_rewards
+                .checked_add(view.reward_amount)
+                .unwrap_or_revert_with(Errors::AdditionOverflow20);
+            portfolio.total_penalties = portfolio
+                .total_penalties
+                .checked_add(view.penalty_amount)
+                .unwrap_or_revert_with(Errors::AdditionOverflow20);
+            portfolio.stakes.push(view);
+            index += 1.into();
+        }
+        let liquidity_end: U256 = liquidity_cursor
+            .saturating_add(limit)
+            .min(portfolio.liquidity_stake_count);
+        index = liquidity_cursor;
+        while index < liquidity_end {
+            let liquidity_stake_id: Vec<u32> = self.generate_id(account, index, 0x03);
+            let (start_day, staked_amount, reward_amount, close_day, is_active) =
+                self.check_liquidity_stake_by_id(account, liquidity_stake_id.clone());
+            if is_active {
+                portfolio.total_liquidity_staked = portfolio
+                    .total_liquidity_staked
+                    .checked_add(staked_amount)
+                    .unwrap_or_revert_with(Errors::AdditionOverflow20);
+            }
+            portfolio.total_liquidity_rewards = portfolio
+                .total_liquidity_rewards
+                .checked_add(reward_amount)
+                .unwrap_or_revert_with(Errors::AdditionOverflow20);
+            portfolio.liquidity_stakes.push(PortfolioLiquidityStake {
+                liquidity_stake_id,
+                staked_amount,
+                reward_amount,
+                start_day: start_day.as_u64(),
+                close_day: close_day.as_u64(),
+                is_active,
+            });
+            index += 1.into();
+        }
+        // each list pages on its own, 0 once it is exhausted
+        portfolio.next_stake_cursor = if stake_end < portfolio.stake_count {
+            stake_end
+        } else {
+            0.into()
+        };
+        portfolio.next_liquidity_cursor = if liquidity_end < portfolio.liquidity_stake_count {
+            liquidity_end
+        } else {
+            0.into()
+        };
+        portfolio
+    }
 }
@@ -1,7 +1,26 @@
-use casper_types::{account::AccountHash, runtime_args, Key, RuntimeArgs, U256, U512};
+use casper_types::{
+    account::AccountHash,
+    bytesrepr::{self, Bytes, FromBytes},
+    runtime_args, ContractPackageHash, Key, RuntimeArgs, U256, U512,
+};
 use casperlabs_test_env::{TestContract, TestEnv};
+use economics::{simulator::Simulator, PoolSnapshot};
 use num_traits::AsPrimitive;
-use tests_common::{data::Globals, deploys::*, helpers::*, keys::*};
+use tests_common::{
+    access_control::*,
+    data::Globals,
+    deploys::*,
+    events,
+    helpers::*,
+    keys::*,
+    mocks::*,
+    pausable::*,
+    scenario::Scenario,
+    views::{LiquidityStakeView, Portfolio, ReferrerSummaryView, StakeQuote, StakeView},
+};
+
+mod benchmarks;
+mod invariants;
 
 #[allow(clippy::type_complexity)]
 fn deploy() -> (
@@ -265,6 +284,12 @@ fn forward_liquidity(
 }
 
 fn init() -> (TestEnv, AccountHash, TestContract, u64) {
+    let (env, owner, wise, _, time) = init_with_pair();
+    (env, owner, wise, time)
+}
+
+/// Same as `init` but also returns the stakeable pair whose LP tokens back liquidity stakes
+fn init_with_pair() -> (TestEnv, AccountHash, TestContract, TestContract, u64) {
     let (
         env,
         liquidity_transformer,
@@ -276,7 +301,7 @@ fn init() -> (TestEnv, AccountHash, TestContract, u64) {
         wise,
         scspr,
         uniswap_factory,
-        _,
+        pair_stakeable,
         flashswapper,
         liquidity_guard,
         time,
@@ -333,7 +358,106 @@ fn init() -> (TestEnv, AccountHash, TestContract, u64) {
         &wcspr,
         time,
     );
-    (env, owner, wise, time)
+    (env, owner, wise, pair_stakeable, time)
+}
+
+/// Stakeable token on the mock DEX with the liquidity guard on, the router swaps one SCSPR
+/// for two stakeable tokens out of its own balance
+fn init_mock_dex() -> (TestEnv, AccountHash, Mocks, TestContract, u64) {
+    let time = now();
+    let env = TestEnv::new();
+    let owner = env.next_user();
+    let mocks = Mocks::deploy(&env, owner, time);
+    let wise = mocks.deploy_stakeable(&env, time - (2 * MILLI_SECONDS_IN_DAY));
+    wise.call_contract(
+        owner,
+        "mint_supply",
+        runtime_args! {
+            "investor_address" => Key::Hash(mocks.router.package_hash()),
+            "amount" => RESERVED_WISE
+        },
+        time,
+    );
+    mocks.set_price(
+        Key::Hash(mocks.scspr.package_hash()),
+        Key::Hash(wise.package_hash()),
+        (2 * PRICE_PRECISION).into(),
+        time,
+    );
+    // nothing of the supply is on the pair, which turns the liquidity guard on
+    wise.call_contract(owner, "manual_daily_snapshot", runtime_args! {}, time);
+    (env, owner, mocks, wise, time)
+}
+
+fn liquidity_stake_view(
+    env: &TestEnv,
+    owner: AccountHash,
+    wise: &TestContract,
+    liquidity_stake_id: Vec<u32>,
+    time: u64,
+) -> LiquidityStakeView {
+    call(
+        env,
+        owner,
+        SESSION_WASM_STAKEABLE,
+        runtime_args! {
+            ENTRYPOINT => LIQUIDITY_STAKE_VIEW_V1,
+            PACKAGE_HASH => Key::Hash(wise.package_hash()),
+            "staker" => Key::Account(owner),
+            "liquidity_stake_id" => liquidity_stake_id
+        },
+        time,
+    );
+    result_key(env, owner, LIQUIDITY_STAKE_VIEW_V1)
+}
+
+/// Events stored by the Casper Event Standard in emission order, each with its struct name
+fn stored_events(wise: &TestContract) -> Vec<(String, Bytes)> {
+    let events_length: u32 = wise.query_named_key("__events_length".into());
+    (0..events_length)
+        .map(|index| {
+            let event: Bytes = wise
+                .query_dictionary("__events", index.to_string())
+                .expect("Event not stored");
+            let (name, _): (String, _) = String::from_bytes(&event).unwrap();
+            (name.trim_start_matches("event_").to_string(), event)
+        })
+        .collect()
+}
+
+const TIMELOCK_DELAY: u64 = 7 * MILLI_SECONDS_IN_DAY;
+
+/// Makes `owner` a parameter manager and queues `value` for `name`
+fn propose_parameter(wise: &TestContract, owner: AccountHash, name: &str, value: U256, time: u64) {
+    wise.call_contract(
+        owner,
+        "grant_role",
+        runtime_args! {
+            "role" => PARAMETER_MANAGER_ROLE.to_string(),
+            "account" => Key::Account(owner)
+        },
+        time,
+    );
+    wise.call_contract(
+        owner,
+        "propose_parameter",
+        runtime_args! {
+            "name" => name.to_string(),
+            "value" => value
+        },
+        time,
+    );
+}
+
+fn execute_parameter(wise: &TestContract, owner: AccountHash, name: &str, time: u64) {
+    wise.call_contract(
+        owner,
+        "execute_parameter",
+        runtime_args! {
+            "name" => name.to_string()
+        },
+        time,
+    );
 }
 
 fn default_check(wise: &TestContract, owner: AccountHash) {
@@ -618,3 +742,2329 @@ mod t2 {
         );
     }
 }
+
+mod t3 {
+    use crate::tests::*;
+
+    fn has_role(
+        env: &TestEnv,
+        owner: AccountHash,
+        wise: &TestContract,
+        role: &str,
+        account: Key,
+        time: u64,
+    ) -> bool {
+        call(
+            env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => HAS_ROLE,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "role" => role.to_string(),
+                "account" => account
+            },
+            time,
+        );
+        result_key(env, owner, HAS_ROLE)
+    }
+
+    #[test]
+    fn should_be_able_to_grant_and_revoke_roles() {
+        let (env, owner, wise, time) = init();
+        let user = env.next_user();
+        assert!(
+            has_role(&env, owner, &wise, ADMIN_ROLE, Key::Account(owner), time),
+            "Deployer is not admin"
+        );
+        assert!(
+            !has_role(&env, owner, &wise, PAUSER_ROLE, Key::Account(user), time),
+            "User already pauser"
+        );
+        wise.call_contract(
+            owner,
+            "grant_role",
+            runtime_args! {
+                "role" => PAUSER_ROLE.to_string(),
+                "account" => Key::Account(user)
+            },
+            time,
+        );
+        assert!(
+            has_role(&env, owner, &wise, PAUSER_ROLE, Key::Account(user), time),
+            "Role not granted"
+        );
+        wise.call_contract(
+            user,
+            "renounce_role",
+            runtime_args! {
+                "role" => PAUSER_ROLE.to_string()
+            },
+            time,
+        );
+        assert!(
+            !has_role(&env, owner, &wise, PAUSER_ROLE, Key::Account(user), time),
+            "Role not renounced"
+        );
+    }
+
+    fn is_paused(
+        env: &TestEnv,
+        owner: AccountHash,
+        wise: &TestContract,
+        operation: &str,
+        time: u64,
+    ) -> bool {
+        call(
+            env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => IS_PAUSED,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "operation" => operation.to_string()
+            },
+            time,
+        );
+        result_key(env, owner, IS_PAUSED)
+    }
+
+    #[test]
+    fn should_be_able_to_pause_and_unpause_operations() {
+        let (env, owner, wise, time) = init();
+        wise.call_contract(
+            owner,
+            "grant_role",
+            runtime_args! {
+                "role" => PAUSER_ROLE.to_string(),
+                "account" => Key::Account(owner)
+            },
+            time,
+        );
+        wise.call_contract(
+            owner,
+            "pause",
+            runtime_args! {
+                "operations" => vec![STAKE_OPERATION.to_string(), END_OPERATION.to_string()]
+            },
+            time,
+        );
+        assert!(
+            is_paused(&env, owner, &wise, STAKE_OPERATION, time),
+            "Stake not paused"
+        );
+        assert!(
+            is_paused(&env, owner, &wise, END_OPERATION, time),
+            "End not paused"
+        );
+        assert!(
+            !is_paused(&env, owner, &wise, SCRAPE_OPERATION, time),
+            "Scrape paused"
+        );
+        wise.call_contract(
+            owner,
+            "unpause",
+            runtime_args! {
+                "operations" => vec![STAKE_OPERATION.to_string()]
+            },
+            time,
+        );
+        assert!(
+            !is_paused(&env, owner, &wise, STAKE_OPERATION, time),
+            "Stake not unpaused"
+        );
+    }
+
+    #[test]
+    fn should_keep_stakes_when_upgraded() {
+        let (env, owner, wise, time) = init();
+        call(
+            &env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => CREATE_STAKE,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "staked_amount" => ONEHUNDRED_CSPR,
+                "lock_days" => 20u64,
+                "referrer" => account_zero_address()
+            },
+            time,
+        );
+        let (stake_id, _, _): (Vec<u32>, U256, Vec<u32>) = result_key(&env, owner, CREATE_STAKE);
+        let check_stake = |contract: &TestContract| -> Vec<String> {
+            call(
+                &env,
+                owner,
+                SESSION_WASM_STAKEABLE,
+                runtime_args! {
+                    ENTRYPOINT => CHECK_STAKE_BY_ID,
+                    PACKAGE_HASH => Key::Hash(contract.package_hash()),
+                    "staker" => Key::Account(owner),
+                    "stake_id" => stake_id.clone()
+                },
+                time,
+            );
+            result_key(&env, owner, CHECK_STAKE_BY_ID)
+        };
+        let stake: Vec<String> = check_stake(&wise);
+        let globals: Globals = wise.query_named_key(GLOBALS.into());
+        // installing under the same name adds a new version to the existing package
+        let upgraded = TestContract::new(
+            &env,
+            "stakeable-token.wasm",
+            "stakeable-token",
+            owner,
+            runtime_args! {},
+            time,
+        );
+        assert_eq!(
+            upgraded.package_hash(),
+            wise.package_hash(),
+            "Package changed"
+        );
+        let schema_version: u32 = upgraded.query_named_key(SCHEMA_VERSION.into());
+        assert_eq!(schema_version, 11, "Schema version not stored");
+        let upgraded_globals: Globals = upgraded.query_named_key(GLOBALS.into());
+        assert_eq!(upgraded_globals, globals, "Globals not kept");
+        assert_eq!(check_stake(&upgraded), stake, "Stake not kept");
+    }
+
+    #[test]
+    fn should_migrate_baseline_deployment() {
+        let time = now();
+        let env = TestEnv::new();
+        let owner = env.next_user();
+        let mocks = Mocks::deploy(&env, owner, time);
+        // built by `make build-stakeable-token-baseline`
+        let wise = mocks.deploy_stakeable_wasm(
+            &env,
+            "stakeable-token-baseline.wasm",
+            time - (2 * MILLI_SECONDS_IN_DAY),
+        );
+        wise.call_contract(
+            owner,
+            "mint_supply",
+            runtime_args! {
+                "investor_address" => Key::Account(owner),
+                "amount" => RESERVED_WISE
+            },
+            time,
+        );
+        call(
+            &env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => CREATE_STAKE,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "staked_amount" => ONEHUNDRED_CSPR,
+                "lock_days" => 20u64,
+                "referrer" => account_zero_address()
+            },
+            time,
+        );
+        let (stake_id, _, _): (Vec<u32>, U256, Vec<u32>) = result_key(&env, owner, CREATE_STAKE);
+        // the baseline release stores stakes in the legacy layout
+        let legacy_item = dictionary_keys::legacy_stakes(&Key::Account(owner), &stake_id);
+        let stake: dictionary_keys::LegacyStake = wise
+            .query_dictionary(legacy_item.dictionary_name, legacy_item.item_key)
+            .unwrap();
+        let globals: Globals = wise.query_named_key(GLOBALS.into());
+        let upgraded = TestContract::new(
+            &env,
+            "stakeable-token.wasm",
+            "stakeable-token",
+            owner,
+            runtime_args! {},
+            time,
+        );
+        assert_eq!(
+            upgraded.package_hash(),
+            wise.package_hash(),
+            "Package changed"
+        );
+        let schema_version: u32 = upgraded.query_named_key(SCHEMA_VERSION.into());
+        assert_eq!(schema_version, 11, "State not migrated");
+        let upgraded_globals: Globals = upgraded.query_named_key(GLOBALS.into());
+        assert_eq!(upgraded_globals, globals, "Globals not kept");
+        let item = dictionary_keys::stakes(&Key::Account(owner), &stake_id);
+        let unconverted: Option<dictionary_keys::Stake> =
+            upgraded.query_dictionary(item.dictionary_name, item.item_key.clone());
+        assert!(
+            unconverted.is_none(),
+            "Stake converted before convert_stakes"
+        );
+        upgraded.call_contract(
+            owner,
+            "convert_stakes",
+            runtime_args! {
+                "staker" => Key::Account(owner)
+            },
+            time,
+        );
+        let upgraded_stake: dictionary_keys::Stake = upgraded
+            .query_dictionary(item.dictionary_name, item.item_key)
+            .expect("Stake not converted to the new layout");
+        assert_eq!(
+            upgraded_stake.staked_amount, stake.staked_amount,
+            "Staked amount not kept"
+        );
+        assert_eq!(
+            upgraded_stake.stakes_shares, stake.stakes_shares,
+            "Stake shares not kept"
+        );
+        assert_eq!(
+            upgraded_stake.final_day, stake.final_day,
+            "Final day not kept"
+        );
+        assert_eq!(upgraded_stake.referrer, stake.referrer, "Referrer not kept");
+        assert!(upgraded_stake.is_active, "Stake not active");
+        assert_eq!(
+            upgraded_stake.tier_shares,
+            U256::zero(),
+            "Tier shares booked for a stake without referrer"
+        );
+        assert!(
+            has_role(
+                &env,
+                owner,
+                &upgraded,
+                ADMIN_ROLE,
+                Key::Account(owner),
+                time
+            ),
+            "Installer is not admin"
+        );
+        // ending the stake reads the pause flags and parameters added by the migration
+        call(
+            &env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => END_STAKE,
+                PACKAGE_HASH => Key::Hash(upgraded.package_hash()),
+                "stake_id" => stake_id
+            },
+            time,
+        );
+        let balance: U256 = upgraded
+            .query_dictionary("balances", owner.to_string())
+            .unwrap();
+        assert_eq!(balance, RESERVED_WISE, "Stake not ended after migration");
+    }
+
+    #[test]
+    fn should_skip_links_created_before_the_referrer_summaries() {
+        let time = now();
+        let env = TestEnv::new();
+        let owner = env.next_user();
+        let referrer = env.next_user();
+        let mocks = Mocks::deploy(&env, owner, time);
+        // built by `make build-stakeable-token-baseline`
+        let wise = mocks.deploy_stakeable_wasm(
+            &env,
+            "stakeable-token-baseline.wasm",
+            time - (2 * MILLI_SECONDS_IN_DAY),
+        );
+        wise.call_contract(
+            owner,
+            "mint_supply",
+            runtime_args! {
+                "investor_address" => Key::Account(owner),
+                "amount" => RESERVED_WISE
+            },
+            time,
+        );
+        // the $10,000 critical mass threshold at the default 1:1 prices
+        let threshold: U256 = 10_000_000_000_000u64.into();
+        let create_stake = |contract: &TestContract| -> Vec<u32> {
+            call(
+                &env,
+                owner,
+                SESSION_WASM_STAKEABLE,
+                runtime_args! {
+                    ENTRYPOINT => CREATE_STAKE,
+                    PACKAGE_HASH => Key::Hash(contract.package_hash()),
+                    "staked_amount" => threshold,
+                    "lock_days" => 365u64,
+                    "referrer" => Key::Account(referrer)
+                },
+                time,
+            );
+            let (stake_id, _, _): (Vec<u32>, U256, Vec<u32>) =
+                result_key(&env, owner, CREATE_STAKE);
+            stake_id
+        };
+        let end_stake = |contract: &TestContract, stake_id: Vec<u32>| {
+            call(
+                &env,
+                owner,
+                SESSION_WASM_STAKEABLE,
+                runtime_args! {
+                    ENTRYPOINT => END_STAKE,
+                    PACKAGE_HASH => Key::Hash(contract.package_hash()),
+                    "stake_id" => stake_id
+                },
+                time,
+            );
+        };
+        let summary = |contract: &TestContract| -> ReferrerSummaryView {
+            call(
+                &env,
+                owner,
+                SESSION_WASM_STAKEABLE,
+                runtime_args! {
+                    ENTRYPOINT => REFERRER_SUMMARY,
+                    PACKAGE_HASH => Key::Hash(contract.package_hash()),
+                    "referrer" => Key::Account(referrer)
+                },
+                time,
+            );
+            result_key(&env, owner, REFERRER_SUMMARY)
+        };
+        // the first stake activates the referrer, the second one links to it
+        create_stake(&wise);
+        let legacy_stake_id: Vec<u32> = create_stake(&wise);
+        let upgraded = TestContract::new(
+            &env,
+            "stakeable-token.wasm",
+            "stakeable-token",
+            owner,
+            runtime_args! {},
+            time,
+        );
+        let stake_id: Vec<u32> = create_stake(&upgraded);
+        let counted: ReferrerSummaryView = summary(&upgraded);
+        assert_eq!(counted.active_links, 1.into(), "Legacy link counted");
+        end_stake(&upgraded, legacy_stake_id);
+        let after_legacy_end: ReferrerSummaryView = summary(&upgraded);
+        assert_eq!(
+            after_legacy_end.active_links, counted.active_links,
+            "Legacy link removed from the summary"
+        );
+        assert_eq!(
+            after_legacy_end.ended_links, ZERO,
+            "Legacy link counted as ended"
+        );
+        end_stake(&upgraded, stake_id);
+        let ended: ReferrerSummaryView = summary(&upgraded);
+        assert_eq!(ended.active_links, ZERO, "Link still active");
+        assert_eq!(ended.ended_links, 1.into(), "Link not ended");
+    }
+
+    #[test]
+    fn should_keep_decaying_mass_of_stakes_created_after_the_upgrade() {
+        let time = now();
+        let env = TestEnv::new();
+        let owner = env.next_user();
+        let referrer = env.next_user();
+        let mocks = Mocks::deploy(&env, owner, time);
+        // built by `make build-stakeable-token-baseline`
+        let wise = mocks.deploy_stakeable_wasm(
+            &env,
+            "stakeable-token-baseline.wasm",
+            time - (2 * MILLI_SECONDS_IN_DAY),
+        );
+        wise.call_contract(
+            owner,
+            "mint_supply",
+            runtime_args! {
+                "investor_address" => Key::Account(owner),
+                "amount" => RESERVED_WISE
+            },
+            time,
+        );
+        let threshold: U256 = 10_000_000_000_000u64.into();
+        let create_stake = |contract: &TestContract| -> Vec<u32> {
+            call(
+                &env,
+                owner,
+                SESSION_WASM_STAKEABLE,
+                runtime_args! {
+                    ENTRYPOINT => CREATE_STAKE,
+                    PACKAGE_HASH => Key::Hash(contract.package_hash()),
+                    "staked_amount" => threshold,
+                    "lock_days" => 365u64,
+                    "referrer" => Key::Account(referrer)
+                },
+                time,
+            );
+            let (stake_id, _, _): (Vec<u32>, U256, Vec<u32>) =
+                result_key(&env, owner, CREATE_STAKE);
+            stake_id
+        };
+        let decayed = |contract: &TestContract| -> U256 {
+            call(
+                &env,
+                owner,
+                SESSION_WASM_STAKEABLE,
+                runtime_args! {
+                    ENTRYPOINT => DECAYED_CRITICAL_MASS,
+                    PACKAGE_HASH => Key::Hash(contract.package_hash()),
+                    "referrer" => Key::Account(referrer)
+                },
+                time,
+            );
+            result_key(&env, owner, DECAYED_CRITICAL_MASS)
+        };
+        // stakes of the baseline release never added a decaying weight
+        let legacy_stake_id: Vec<u32> = create_stake(&wise);
+        let upgraded = TestContract::new(
+            &env,
+            "stakeable-token.wasm",
+            "stakeable-token",
+            owner,
+            runtime_args! {},
+            time,
+        );
+        create_stake(&upgraded);
+        let recorded: U256 = decayed(&upgraded);
+        assert!(recorded > ZERO, "Weight of the new stake not recorded");
+        call(
+            &env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => END_STAKE,
+                PACKAGE_HASH => Key::Hash(upgraded.package_hash()),
+                "stake_id" => legacy_stake_id
+            },
+            time,
+        );
+        assert_eq!(
+            decayed(&upgraded),
+            recorded,
+            "Ending a legacy stake removed the weight of a new one"
+        );
+    }
+
+    #[test]
+    fn should_emit_events_through_the_event_standard() {
+        let (env, owner, wise, time) = init();
+        let ces_version: String = wise.query_named_key("__events_ces_version".into());
+        assert!(!ces_version.is_empty(), "Event schemas not registered");
+        call(
+            &env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => CREATE_STAKE,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "staked_amount" => ONEHUNDRED_CSPR,
+                "lock_days" => 20u64,
+                "referrer" => account_zero_address()
+            },
+            time,
+        );
+        let (stake_id, start_day, _): (Vec<u32>, U256, Vec<u32>) =
+            result_key(&env, owner, CREATE_STAKE);
+        let item = dictionary_keys::stakes(&Key::Account(owner), &stake_id);
+        let stake: dictionary_keys::Stake = wise
+            .query_dictionary(item.dictionary_name, item.item_key)
+            .unwrap();
+        let (_, event) = stored_events(&wise)
+            .into_iter()
+            .rev()
+            .find(|(name, _)| name == "StakeStart")
+            .expect("No StakeStart event stored");
+        let event: events::StakeStart = bytesrepr::deserialize(event.into()).unwrap();
+        assert_eq!(
+            event,
+            events::StakeStart {
+                stake_id,
+                staker_address: Key::Account(owner),
+                referral_address: account_zero_address(),
+                staked_amount: ONEHUNDRED_CSPR,
+                stakes_shares: 1002739726000u64.into(),
+                referral_shares: 0.into(),
+                start_day,
+                lock_days: 20.into(),
+                dai_equivalent: stake.dai_equivalent,
+            },
+            "Wrong StakeStart fields"
+        );
+    }
+
+    fn resolve_referral_code(
+        env: &TestEnv,
+        owner: AccountHash,
+        wise: &TestContract,
+        code: &str,
+        time: u64,
+    ) -> Key {
+        call(
+            env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => RESOLVE_REFERRAL_CODE,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "code" => code.to_string()
+            },
+            time,
+        );
+        result_key(env, owner, RESOLVE_REFERRAL_CODE)
+    }
+
+    #[test]
+    fn should_be_able_to_create_stake_by_referral_code() {
+        let (env, owner, wise, time) = init();
+        let user = env.next_user();
+        wise.call_contract(
+            owner,
+            "claim_referral_code",
+            runtime_args! {
+                "code" => "wise".to_string()
+            },
+            time,
+        );
+        assert_eq!(
+            resolve_referral_code(&env, owner, &wise, "wise", time),
+            Key::Account(owner),
+            "Code not claimed"
+        );
+        wise.call_contract(
+            owner,
+            "transfer_referral_code",
+            runtime_args! {
+                "recipient" => Key::Account(user)
+            },
+            time,
+        );
+        assert_eq!(
+            resolve_referral_code(&env, owner, &wise, "wise", time),
+            Key::Account(user),
+            "Code not transferred"
+        );
+        call(
+            &env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => CREATE_STAKE_BY_CODE,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "staked_amount" => ONEHUNDRED_CSPR,
+                "lock_days" => 20u64,
+                "code" => "wise".to_string()
+            },
+            time,
+        );
+        // same stake without a referrer gets 1002739726000 shares
+        let ret: Globals = wise.query_named_key(GLOBALS.into());
+        assert!(
+            ret.total_shares > 1002739726000u64.into(),
+            "Referrer bonus not applied"
+        );
+        wise.call_contract(user, "release_referral_code", runtime_args! {}, time);
+        assert_eq!(
+            resolve_referral_code(&env, owner, &wise, "wise", time),
+            account_zero_address(),
+            "Code not released"
+        );
+    }
+
+    #[test]
+    fn should_collect_all_referral_interest_without_links() {
+        let (env, owner, wise, time) = init();
+        call(
+            &env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => COLLECT_ALL_REFERRAL_INTEREST,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "cursor" => None::<U256>,
+                "max_links" => U256::from(10)
+            },
+            time,
+        );
+        let (next_cursor, reward_amount): (U256, U256) =
+            result_key(&env, owner, COLLECT_ALL_REFERRAL_INTEREST);
+        assert_eq!(next_cursor, 0.into(), "Cursor not reset");
+        assert_eq!(reward_amount, 0.into(), "Reward collected without links");
+    }
+
+    #[test]
+    fn should_return_empty_referrer_summary() {
+        let (env, owner, wise, time) = init();
+        let user = env.next_user();
+        call(
+            &env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => REFERRER_SUMMARY,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "referrer" => Key::Account(user)
+            },
+            time,
+        );
+        let ret: ReferrerSummaryView = result_key(&env, owner, REFERRER_SUMMARY);
+        assert_eq!(ret.total_collected, ZERO, "Collected without links");
+        assert_eq!(ret.claimable, ZERO, "Claimable without links");
+        assert_eq!(ret.active_links, ZERO, "Active links without stakes");
+        // no critical mass yet, the whole threshold is missing
+        assert_eq!(
+            ret.threshold_distance,
+            10_000_000_000_000u64.into(),
+            "Wrong threshold distance"
+        );
+    }
+
+    fn is_eligible_referrer(
+        env: &TestEnv,
+        owner: AccountHash,
+        wise: &TestContract,
+        referrer: Key,
+        time: u64,
+    ) -> bool {
+        call(
+            env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => IS_ELIGIBLE_REFERRER,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "referrer" => referrer
+            },
+            time,
+        );
+        result_key(env, owner, IS_ELIGIBLE_REFERRER)
+    }
+
+    #[test]
+    fn should_be_able_to_deny_and_allow_referrers() {
+        let (env, owner, wise, time) = init();
+        let user = Key::Account(env.next_user());
+        assert!(
+            is_eligible_referrer(&env, owner, &wise, user, time),
+            "Referrer not eligible by default"
+        );
+        wise.call_contract(
+            owner,
+            "grant_role",
+            runtime_args! {
+                "role" => REFERRER_MANAGER_ROLE.to_string(),
+                "account" => Key::Account(owner)
+            },
+            time,
+        );
+        wise.call_contract(
+            owner,
+            "set_denied_referrers",
+            runtime_args! {
+                "referrers" => vec![user],
+                "denied" => true
+            },
+            time,
+        );
+        assert!(
+            !is_eligible_referrer(&env, owner, &wise, user, time),
+            "Denied referrer eligible"
+        );
+        wise.call_contract(
+            owner,
+            "set_denied_referrers",
+            runtime_args! {
+                "referrers" => vec![user],
+                "denied" => false
+            },
+            time,
+        );
+        assert!(
+            is_eligible_referrer(&env, owner, &wise, user, time),
+            "Referrer still denied"
+        );
+    }
+
+    #[test]
+    fn should_track_decaying_critical_mass() {
+        let (env, owner, wise, time) = init();
+        let user = Key::Account(env.next_user());
+        call(
+            &env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => CREATE_STAKE,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "staked_amount" => ONEHUNDRED_CSPR,
+                "lock_days" => 20u64,
+                "referrer" => user
+            },
+            time,
+        );
+        call(
+            &env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => DECAYED_CRITICAL_MASS,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "referrer" => user
+            },
+            time,
+        );
+        let decayed: U256 = result_key(&env, owner, DECAYED_CRITICAL_MASS);
+        call(
+            &env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => REFERRER_SUMMARY,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "referrer" => user
+            },
+            time,
+        );
+        let summary: ReferrerSummaryView = result_key(&env, owner, REFERRER_SUMMARY);
+        let total_amount: U256 = summary.critical_mass;
+        // nothing has decayed before the stake started, only rounding is lost
+        assert!(decayed <= total_amount, "Decayed mass above total");
+        assert!(decayed + 1 >= total_amount, "Mass decayed before start");
+    }
+
+    #[test]
+    fn should_quote_stake_without_changing_state() {
+        let (env, owner, wise, time) = init();
+        let globals: Globals = wise.query_named_key(GLOBALS.into());
+        call(
+            &env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => QUOTE_STAKE,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "staked_amount" => ONEHUNDRED_CSPR,
+                "lock_days" => 20u64,
+                "referrer" => account_zero_address()
+            },
+            time,
+        );
+        let quote: StakeQuote = result_key(&env, owner, QUOTE_STAKE);
+        // same shares a real stake without a referrer gets
+        assert_eq!(
+            quote.stakes_shares,
+            1002739726000u64.into(),
+            "Wrong projected shares"
+        );
+        assert_eq!(
+            quote.referrer_shares, ZERO,
+            "Referrer shares without referrer"
+        );
+        assert_eq!(
+            quote.referral_bonus, ZERO,
+            "Referral bonus without referrer"
+        );
+        assert_eq!(
+            wise.query_named_key::<Globals>(GLOBALS.into()),
+            globals,
+            "Quote changed state"
+        );
+    }
+
+    #[test]
+    fn should_return_account_portfolio() {
+        let (env, owner, wise, time) = init();
+        call(
+            &env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => CREATE_STAKE,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "staked_amount" => ONEHUNDRED_CSPR,
+                "lock_days" => 20u64,
+                "referrer" => account_zero_address()
+            },
+            time,
+        );
+        call(
+            &env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => PORTFOLIO,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "account" => Key::Account(owner),
+                "stake_cursor" => U256::from(0),
+                "liquidity_cursor" => U256::from(0),
+                "limit" => U256::from(10)
+            },
+            time,
+        );
+        let portfolio: Portfolio = result_key(&env, owner, PORTFOLIO);
+        assert_eq!(
+            portfolio.balance,
+            RESERVED_WISE - ONEHUNDRED_CSPR,
+            "Wrong balance"
+        );
+        assert_eq!(portfolio.stakes.len(), 1, "Stake missing");
+        assert_eq!(
+            portfolio.stakes[0].staked_amount, ONEHUNDRED_CSPR,
+            "Wrong staked amount"
+        );
+        assert!(portfolio.stakes[0].is_active, "Stake not active");
+        assert_eq!(portfolio.total_staked, ONEHUNDRED_CSPR, "Wrong total");
+        assert_eq!(
+            portfolio.next_stake_cursor,
+            0.into(),
+            "Unexpected next page"
+        );
+        assert_eq!(
+            portfolio.next_liquidity_cursor,
+            0.into(),
+            "Unexpected next liquidity page"
+        );
+    }
+
+    #[test]
+    fn should_page_stakes_with_their_own_cursor() {
+        let (env, owner, wise, time) = init();
+        for _ in 0..3 {
+            call(
+                &env,
+                owner,
+                SESSION_WASM_STAKEABLE,
+                runtime_args! {
+                    ENTRYPOINT => CREATE_STAKE,
+                    PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                    "staked_amount" => ONEHUNDRED_CSPR,
+                    "lock_days" => 20u64,
+                    "referrer" => account_zero_address()
+                },
+                time,
+            );
+        }
+        let portfolio = |stake_cursor: U256, limit: U256| -> Portfolio {
+            call(
+                &env,
+                owner,
+                SESSION_WASM_STAKEABLE,
+                runtime_args! {
+                    ENTRYPOINT => PORTFOLIO,
+                    PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                    "account" => Key::Account(owner),
+                    "stake_cursor" => stake_cursor,
+                    "liquidity_cursor" => U256::MAX,
+                    "limit" => limit
+                },
+                time,
+            );
+            result_key(&env, owner, PORTFOLIO)
+        };
+        let first: Portfolio = portfolio(ZERO, 2.into());
+        assert_eq!(first.stakes.len(), 2, "Wrong first page");
+        assert_eq!(first.next_stake_cursor, 2.into(), "Wrong next cursor");
+        assert!(
+            first.liquidity_stakes.is_empty(),
+            "Liquidity cursor ignored"
+        );
+        assert_eq!(
+            first.next_liquidity_cursor, ZERO,
+            "Liquidity stakes not exhausted"
+        );
+        let last: Portfolio = portfolio(first.next_stake_cursor, 2.into());
+        assert_eq!(last.stakes.len(), 1, "Wrong last page");
+        assert_eq!(last.next_stake_cursor, ZERO, "Stakes not exhausted");
+        assert_ne!(
+            last.stakes[0].stake_id, first.stakes[0].stake_id,
+            "Stake repeated"
+        );
+        // a cursor and limit near the top of U256 return an empty page instead of overflowing
+        let past_end: Portfolio = portfolio(U256::MAX, U256::MAX);
+        assert!(past_end.stakes.is_empty(), "Stakes past the end");
+        assert_eq!(past_end.next_stake_cursor, ZERO, "Cursor past the end");
+    }
+
+    #[test]
+    fn should_return_stake_view_matching_tuple_endpoint() {
+        let (env, owner, wise, time) = init();
+        call(
+            &env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => CREATE_STAKE,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "staked_amount" => ONEHUNDRED_CSPR,
+                "lock_days" => 20u64,
+                "referrer" => account_zero_address()
+            },
+            time,
+        );
+        let (stake_id, _, _): (Vec<u32>, U256, Vec<u32>) = result_key(&env, owner, CREATE_STAKE);
+        for entrypoint in [CHECK_STAKE_BY_ID, STAKE_VIEW_V1] {
+            call(
+                &env,
+                owner,
+                SESSION_WASM_STAKEABLE,
+                runtime_args! {
+                    ENTRYPOINT => entrypoint,
+                    PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                    "staker" => Key::Account(owner),
+                    "stake_id" => stake_id.clone()
+                },
+                time,
+            );
+        }
+        let stake: Vec<String> = result_key(&env, owner, CHECK_STAKE_BY_ID);
+        let view: StakeView = result_key(&env, owner, STAKE_VIEW_V1);
+        assert_eq!(view.staker, Key::Account(owner), "Wrong staker");
+        assert_eq!(view.stake_id, stake_id, "Wrong stake id");
+        assert_eq!(view.lock_days, 20, "Wrong lock days");
+        assert_eq!(view.staked_amount, ONEHUNDRED_CSPR, "Wrong staked amount");
+        assert_eq!(view.referrer, account_zero_address(), "Wrong referrer");
+        assert_eq!(
+            vec![
+                view.start_day.to_string(),
+                view.lock_days.to_string(),
+                view.final_day.to_string(),
+                view.close_day.to_string(),
+                view.scrape_day.to_string(),
+                view.staked_amount.to_string(),
+                view.stakes_shares.to_string(),
+                view.reward_amount.to_string(),
+                view.penalty_amount.to_string(),
+                view.is_active.to_string(),
+                view.is_mature.to_string(),
+            ],
+            stake,
+            "View differs from tuple endpoint"
+        );
+    }
+
+    #[test]
+    fn should_return_snapshot_range_of_each_pool() {
+        let (env, owner, wise, time) = init();
+        call(
+            &env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => CREATE_STAKE,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "staked_amount" => ONEHUNDRED_CSPR,
+                "lock_days" => 20u64,
+                "referrer" => account_zero_address()
+            },
+            time + (3 * MILLI_SECONDS_IN_DAY),
+        );
+        for entrypoint in [SNAPSHOT_RANGE, RSNAPSHOT_RANGE] {
+            call(
+                &env,
+                owner,
+                SESSION_WASM_STAKEABLE,
+                runtime_args! {
+                    ENTRYPOINT => entrypoint,
+                    PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                    "from_day" => U256::from(0),
+                    "to_day" => U256::from(2)
+                },
+                time + (3 * MILLI_SECONDS_IN_DAY),
+            );
+            let snapshots: Vec<(U256, U256, U256)> = result_key(&env, owner, entrypoint);
+            assert_eq!(snapshots.len(), 3, "Wrong range length");
+        }
+        call(
+            &env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => LSNAPSHOT_RANGE,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "from_day" => U256::from(1),
+                "to_day" => U256::from(2)
+            },
+            time + (3 * MILLI_SECONDS_IN_DAY),
+        );
+        let snapshots: Vec<(U256, U256)> = result_key(&env, owner, LSNAPSHOT_RANGE);
+        assert_eq!(snapshots.len(), 2, "Wrong range length");
+    }
+
+    #[test]
+    fn should_run_against_mock_dex() {
+        let time = now();
+        let env = TestEnv::new();
+        let owner = env.next_user();
+        let mocks = Mocks::deploy(&env, owner, time);
+        let wise = mocks.deploy_stakeable(&env, time - (2 * MILLI_SECONDS_IN_DAY));
+        wise.call_contract(
+            owner,
+            "mint_supply",
+            runtime_args! {
+                "investor_address" => Key::Account(owner),
+                "amount" => RESERVED_WISE
+            },
+            time,
+        );
+        mocks.set_price(
+            Key::Hash(wise.package_hash()),
+            Key::Hash(mocks.scspr.package_hash()),
+            (2 * PRICE_PRECISION).into(),
+            time,
+        );
+        mocks.set_price(
+            Key::Hash(mocks.wcspr.package_hash()),
+            Key::Hash(mocks.stable_usd.package_hash()),
+            (3 * PRICE_PRECISION).into(),
+            time,
+        );
+        call(
+            &env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => GET_STABLE_USD_EQUIVALENT,
+                PACKAGE_HASH => Key::Hash(wise.package_hash())
+            },
+            time,
+        );
+        let ret: U256 = result_key(&env, owner, GET_STABLE_USD_EQUIVALENT);
+        assert_eq!(ret, 6_000_000_000u64.into(), "Wrong scripted quote");
+        // empty reserves, nothing of the supply is on the pair
+        wise.call_contract(owner, "manual_daily_snapshot", runtime_args! {}, time);
+        let ret: bool = wise.query_named_key(IS_LIQUIDITY_GUARD_ACTIVE.into());
+        assert!(ret, "Liquidity guard not enabled");
+        mocks.set_reserves(
+            RESERVED_WISE.as_u128().into(),
+            RESERVED_WISE.as_u128().into(),
+            time,
+        );
+        wise.call_contract(
+            owner,
+            "manual_daily_snapshot",
+            runtime_args! {},
+            time + MILLI_SECONDS_IN_DAY,
+        );
+        let ret: bool = wise.query_named_key(IS_LIQUIDITY_GUARD_ACTIVE.into());
+        assert!(!ret, "Liquidity guard not disabled");
+        call(
+            &env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => CREATE_STAKE,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "staked_amount" => ONEHUNDRED_CSPR,
+                "lock_days" => 20u64,
+                "referrer" => account_zero_address()
+            },
+            time + MILLI_SECONDS_IN_DAY,
+        );
+        let ret: Globals = wise.query_named_key(GLOBALS.into());
+        assert_eq!(ret.total_staked, ONEHUNDRED_CSPR, "Stake not created");
+    }
+
+    #[test]
+    fn should_play_referred_stake_scenario() {
+        let (env, owner, _, wise, time) = init_mock_dex();
+        // a stake at the $10,000 critical mass threshold activates its referrer, at the
+        // default 1:1 prices, and earns referrer shares with a lock of at least 365 days
+        let threshold: U256 = 10_000_000_000_000u64.into();
+        wise.call_contract(
+            owner,
+            "mint_supply",
+            runtime_args! {
+                "investor_address" => Key::Account(owner),
+                "amount" => threshold
+            },
+            time,
+        );
+        Scenario::new(&env, &wise, owner, time)
+            .account("alice", threshold)
+            .account("bob", ZERO)
+            .stake_referred("alice", "savings", threshold, 365, "bob")
+            .expect_balance("alice", ZERO)
+            .expect_globals(|globals| {
+                assert_eq!(globals.total_staked, threshold, "Stake not counted");
+                assert!(
+                    globals.referral_shares > ZERO,
+                    "Referrer shares not counted"
+                );
+            })
+            .advance_days(5)
+            .scrape("savings", 0)
+            .expect_last_reward(|reward| assert!(reward > ZERO, "Nothing scraped"))
+            .advance_days(17)
+            .snapshot()
+            .end_stake("savings")
+            .expect_globals(|globals| {
+                assert_eq!(globals.total_staked, ZERO, "Stake still counted");
+                assert_eq!(globals.total_shares, ZERO, "Shares still counted");
+                assert_eq!(
+                    globals.referral_shares, ZERO,
+                    "Referrer shares still counted"
+                );
+            })
+            .collect_referrals("bob")
+            .expect_last_reward(|reward| assert!(reward > ZERO, "No referral interest"));
+    }
+
+    #[test]
+    fn should_read_stake_through_derived_dictionary_key() {
+        let (env, owner, wise, time) = init();
+        call(
+            &env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => CREATE_STAKE,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "staked_amount" => ONEHUNDRED_CSPR,
+                "lock_days" => 20u64,
+                "referrer" => account_zero_address()
+            },
+            time,
+        );
+        let (stake_id, _, _): (Vec<u32>, U256, Vec<u32>) = result_key(&env, owner, CREATE_STAKE);
+        let item = dictionary_keys::stakes(&Key::Account(owner), &stake_id);
+        let stake: dictionary_keys::Stake = wise
+            .query_dictionary(item.dictionary_name, item.item_key)
+            .expect("Stake not found under derived key");
+        assert_eq!(stake.staked_amount, ONEHUNDRED_CSPR, "Wrong staked amount");
+        assert_eq!(stake.lock_days, 20, "Wrong lock days");
+        assert!(stake.is_active, "Stake not active");
+        let item = dictionary_keys::stake_count(&Key::Account(owner));
+        let count: U256 = wise
+            .query_dictionary(item.dictionary_name, item.item_key)
+            .unwrap_or_default();
+        assert_eq!(count, 1.into(), "Wrong stake count");
+    }
+}
+
+mod t4 {
+    use crate::tests::*;
+
+    fn zap(
+        env: &TestEnv,
+        owner: AccountHash,
+        wise: &TestContract,
+        min_out: U256,
+        min_lp: U256,
+        time: u64,
+    ) -> Vec<u32> {
+        call(
+            env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => ZAP_LIQUIDITY_STAKE_WITH_CSPR,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "amount" => U512::from(10_000_000_000u64),
+                "min_out" => min_out,
+                "min_lp" => min_lp,
+                "deadline" => U256::from(time + MILLI_SECONDS_IN_DAY)
+            },
+            time,
+        );
+        result_key(env, owner, ZAP_LIQUIDITY_STAKE_WITH_CSPR)
+    }
+
+    #[test]
+    fn should_zap_cspr_into_liquidity_stake() {
+        let (env, owner, mocks, wise, time) = init_mock_dex();
+        let total_supply: U256 = wise.query_named_key("total_supply".into());
+        // 5 SCSPR are swapped for 10 stakeable tokens, both sides mint 5 LP tokens
+        let liquidity_stake_id = zap(
+            &env,
+            owner,
+            &wise,
+            10_000_000_000u64.into(),
+            5_000_000_000u64.into(),
+            time,
+        );
+        let view = liquidity_stake_view(&env, owner, &wise, liquidity_stake_id, time);
+        assert_eq!(
+            view.staked_amount,
+            5_000_000_000u64.into(),
+            "Wrong LP amount"
+        );
+        assert!(view.is_active, "Liquidity stake not active");
+        let globals: Globals = wise.query_named_key(GLOBALS.into());
+        assert_eq!(
+            globals.liquidity_shares,
+            5_000_000_000u64.into(),
+            "Liquidity shares not booked"
+        );
+        let lp_balance: U256 = mocks
+            .pair
+            .query_dictionary(
+                "balances",
+                ContractPackageHash::new(wise.package_hash()).to_string(),
+            )
+            .unwrap_or_default();
+        assert_eq!(lp_balance, 5_000_000_000u64.into(), "LP tokens not held");
+        let ret: U256 = wise.query_named_key("total_supply".into());
+        assert_eq!(ret, total_supply, "Zap changed the total supply");
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_not_zap_below_min_out() {
+        let (env, owner, _, wise, time) = init_mock_dex();
+        zap(&env, owner, &wise, 10_000_000_001u64.into(), ZERO, time);
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_not_zap_below_min_lp() {
+        let (env, owner, _, wise, time) = init_mock_dex();
+        zap(&env, owner, &wise, ZERO, 5_000_000_001u64.into(), time);
+    }
+
+    /// Liquidity stake of `staked_amount` LP tokens the mock pair mints to the owner
+    fn create_liquidity_stake(
+        env: &TestEnv,
+        owner: AccountHash,
+        mocks: &Mocks,
+        wise: &TestContract,
+        staked_amount: U256,
+        time: u64,
+    ) -> Vec<u32> {
+        mocks.mint(&mocks.pair, Key::Account(owner), staked_amount, time);
+        mocks.pair.call_contract(
+            owner,
+            "approve",
+            runtime_args! {
+                "spender" => Key::Hash(wise.package_hash()),
+                "amount" => staked_amount
+            },
+            time,
+        );
+        call(
+            env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => CREATE_LIQUIDITY_STAKE,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "liquidity_tokens" => staked_amount
+            },
+            time,
+        );
+        result_key(env, owner, CREATE_LIQUIDITY_STAKE)
+    }
+
+    fn withdraw_liquidity_partial(
+        env: &TestEnv,
+        owner: AccountHash,
+        wise: &TestContract,
+        liquidity_stake_id: Vec<u32>,
+        lp_amount: U256,
+        time: u64,
+    ) -> U256 {
+        call(
+            env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => WITHDRAW_LIQUIDITY_PARTIAL,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "liquidity_stake_id" => liquidity_stake_id,
+                "lp_amount" => lp_amount
+            },
+            time,
+        );
+        result_key(env, owner, WITHDRAW_LIQUIDITY_PARTIAL)
+    }
+
+    fn lp_balance(mocks: &Mocks, owner: AccountHash) -> U256 {
+        mocks
+            .pair
+            .query_dictionary("balances", owner.to_string())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn should_withdraw_part_of_liquidity_stake() {
+        let (env, owner, mocks, wise, time) = init_mock_dex();
+        let liquidity_stake_id =
+            create_liquidity_stake(&env, owner, &mocks, &wise, ONEHUNDRED_CSPR, time);
+        assert_eq!(lp_balance(&mocks, owner), ZERO, "LP tokens not staked");
+        let withdraw_time = time + 4 * MILLI_SECONDS_IN_DAY;
+        let reward = withdraw_liquidity_partial(
+            &env,
+            owner,
+            &wise,
+            liquidity_stake_id.clone(),
+            40_000_000_000u64.into(),
+            withdraw_time,
+        );
+        assert!(reward > ZERO, "No rewards settled");
+        assert_eq!(
+            lp_balance(&mocks, owner),
+            40_000_000_000u64.into(),
+            "LP tokens not returned"
+        );
+        let view = liquidity_stake_view(
+            &env,
+            owner,
+            &wise,
+            liquidity_stake_id.clone(),
+            withdraw_time,
+        );
+        assert_eq!(
+            view.staked_amount,
+            60_000_000_000u64.into(),
+            "Wrong remaining LP tokens"
+        );
+        assert!(view.is_active, "Liquidity stake closed");
+        // settled days are not counted again
+        assert_eq!(view.reward_amount, reward, "Settled rewards pending again");
+        let globals: Globals = wise.query_named_key(GLOBALS.into());
+        assert_eq!(
+            globals.liquidity_shares,
+            60_000_000_000u64.into(),
+            "Liquidity shares not reduced"
+        );
+        let balance: U256 = wise
+            .query_dictionary("balances", key_to_str(&Key::Account(owner)))
+            .unwrap_or_default();
+        // the remaining tokens keep earning from the withdrawal on
+        call(
+            &env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => END_LIQUIDITY_STAKE,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "liquidity_stake_id" => liquidity_stake_id.clone()
+            },
+            withdraw_time + 2 * MILLI_SECONDS_IN_DAY,
+        );
+        let end_reward: U256 = result_key(&env, owner, END_LIQUIDITY_STAKE);
+        assert!(end_reward > ZERO, "Remaining tokens earned nothing");
+        let ret: U256 = wise
+            .query_dictionary("balances", key_to_str(&Key::Account(owner)))
+            .unwrap_or_default();
+        assert_eq!(
+            ret - balance,
+            end_reward,
+            "End returned more than it minted"
+        );
+        let view = liquidity_stake_view(
+            &env,
+            owner,
+            &wise,
+            liquidity_stake_id,
+            withdraw_time + 2 * MILLI_SECONDS_IN_DAY,
+        );
+        assert_eq!(
+            view.reward_amount,
+            reward + end_reward,
+            "Wrong total reward of the closed stake"
+        );
+        assert_eq!(
+            lp_balance(&mocks, owner),
+            ONEHUNDRED_CSPR,
+            "LP tokens not returned on end"
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_not_withdraw_whole_liquidity_stake_partially() {
+        let (env, owner, mocks, wise, time) = init_mock_dex();
+        let liquidity_stake_id =
+            create_liquidity_stake(&env, owner, &mocks, &wise, ONEHUNDRED_CSPR, time);
+        withdraw_liquidity_partial(
+            &env,
+            owner,
+            &wise,
+            liquidity_stake_id,
+            ONEHUNDRED_CSPR,
+            time + MILLI_SECONDS_IN_DAY,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_not_withdraw_more_than_staked() {
+        let (env, owner, mocks, wise, time) = init_mock_dex();
+        let liquidity_stake_id =
+            create_liquidity_stake(&env, owner, &mocks, &wise, ONEHUNDRED_CSPR, time);
+        withdraw_liquidity_partial(
+            &env,
+            owner,
+            &wise,
+            liquidity_stake_id,
+            ONEHUNDRED_CSPR + 1,
+            time + MILLI_SECONDS_IN_DAY,
+        );
+    }
+
+    fn get_parameter(
+        env: &TestEnv,
+        owner: AccountHash,
+        wise: &TestContract,
+        name: &str,
+        time: u64,
+    ) -> U256 {
+        call(
+            env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => GET_PARAMETER,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "name" => name.to_string()
+            },
+            time,
+        );
+        result_key(env, owner, GET_PARAMETER)
+    }
+
+    #[test]
+    fn should_inflate_referral_pool_at_executed_referrals_rate() {
+        let (env, owner, wise, time) = init();
+        let referrer = env.next_user();
+        let rate: U256 = U256::from(economics::REFERRALS_RATE) * 2;
+        propose_parameter(&wise, owner, "referrals_rate", rate, time);
+        assert_eq!(
+            get_parameter(&env, owner, &wise, "referrals_rate", time),
+            economics::REFERRALS_RATE.into(),
+            "Applied before execution"
+        );
+        let time = time + TIMELOCK_DELAY;
+        execute_parameter(&wise, owner, "referrals_rate", time);
+        assert_eq!(
+            get_parameter(&env, owner, &wise, "referrals_rate", time),
+            rate,
+            "Parameter not applied"
+        );
+        // a referred stake of a year puts shares into the referral pool
+        call(
+            &env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => CREATE_STAKE,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "staked_amount" => ONEHUNDRED_CSPR,
+                "lock_days" => 365u64,
+                "referrer" => Key::Account(referrer)
+            },
+            time,
+        );
+        wise.call_contract(
+            owner,
+            "manual_daily_snapshot",
+            runtime_args! {},
+            time + MILLI_SECONDS_IN_DAY,
+        );
+        let globals: Globals = wise.query_named_key(GLOBALS.into());
+        assert!(globals.referral_shares > ZERO, "No referral shares");
+        let total_supply: U256 = wise.query_named_key("total_supply".into());
+        let item = dictionary_keys::rsnapshots(globals.current_stakeable_day - 1);
+        let rsnapshot: dictionary_keys::RSnapShot = wise
+            .query_dictionary(item.dictionary_name, item.item_key)
+            .expect("Referral snapshot not taken");
+        let expected = economics::daily_snapshot(
+            globals.referral_shares,
+            ZERO,
+            ZERO,
+            economics::pool_inflation(globals.total_staked, total_supply, rate).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            rsnapshot.inflation_amount, expected.inflation_amount,
+            "Referral pool not inflated at the executed rate"
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_not_execute_parameter_before_timelock() {
+        let (_, owner, wise, time) = init();
+        propose_parameter(&wise, owner, "share_price_cap", 120.into(), time);
+        execute_parameter(&wise, owner, "share_price_cap", time + TIMELOCK_DELAY - 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_not_propose_zero_referrals_rate() {
+        let (_, owner, wise, time) = init();
+        propose_parameter(&wise, owner, "referrals_rate", ZERO, time);
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_not_propose_decreasing_share_price_cap() {
+        let (_, owner, wise, time) = init();
+        propose_parameter(&wise, owner, "share_price_cap", 99.into(), time);
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_not_propose_unknown_parameter() {
+        let (_, owner, wise, time) = init();
+        propose_parameter(&wise, owner, "unknown_parameter", 1.into(), time);
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_not_stake_below_executed_min_stake_amount() {
+        let (env, owner, wise, time) = init();
+        propose_parameter(&wise, owner, "min_stake_amount", ONEHUNDRED_CSPR + 1, time);
+        let time = time + TIMELOCK_DELAY;
+        execute_parameter(&wise, owner, "min_stake_amount", time);
+        call(
+            &env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => CREATE_STAKE,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "staked_amount" => ONEHUNDRED_CSPR,
+                "lock_days" => 20u64,
+                "referrer" => account_zero_address()
+            },
+            time,
+        );
+    }
+
+    fn pause(wise: &TestContract, owner: AccountHash, operation: &str, time: u64) {
+        wise.call_contract(
+            owner,
+            "grant_role",
+            runtime_args! {
+                "role" => PAUSER_ROLE.to_string(),
+                "account" => Key::Account(owner)
+            },
+            time,
+        );
+        wise.call_contract(
+            owner,
+            "pause",
+            runtime_args! {
+                "operations" => vec![operation.to_string()]
+            },
+            time,
+        );
+    }
+
+    fn create_stake(env: &TestEnv, owner: AccountHash, wise: &TestContract, time: u64) -> Vec<u32> {
+        call(
+            env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => CREATE_STAKE,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "staked_amount" => ONEHUNDRED_CSPR,
+                "lock_days" => 20u64,
+                "referrer" => account_zero_address()
+            },
+            time,
+        );
+        let (stake_id, _, _): (Vec<u32>, U256, Vec<u32>) = result_key(env, owner, CREATE_STAKE);
+        stake_id
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_not_stake_while_paused() {
+        let (env, owner, wise, time) = init();
+        pause(&wise, owner, STAKE_OPERATION, time);
+        create_stake(&env, owner, &wise, time);
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_not_end_stake_while_paused() {
+        let (env, owner, wise, time) = init();
+        let stake_id = create_stake(&env, owner, &wise, time);
+        pause(&wise, owner, END_OPERATION, time);
+        call(
+            &env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => END_STAKE,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "stake_id" => stake_id
+            },
+            time,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_not_emergency_end_stake_while_not_paused() {
+        let (env, owner, wise, time) = init();
+        let stake_id = create_stake(&env, owner, &wise, time);
+        wise.call_contract(
+            owner,
+            "emergency_end_stake",
+            runtime_args! {
+                "stake_id" => stake_id
+            },
+            time,
+        );
+    }
+
+    #[test]
+    fn should_emergency_end_stake_without_penalty_while_paused() {
+        let (env, owner, wise, time) = init();
+        let stake_id = create_stake(&env, owner, &wise, time);
+        // an immature stake ended normally at this point would be penalized
+        let time = time + 5 * MILLI_SECONDS_IN_DAY;
+        pause(&wise, owner, END_OPERATION, time);
+        wise.call_contract(
+            owner,
+            "emergency_end_stake",
+            runtime_args! {
+                "stake_id" => stake_id.clone()
+            },
+            time,
+        );
+        let balance: U256 = wise
+            .query_dictionary("balances", owner.to_string())
+            .unwrap();
+        assert_eq!(
+            balance, RESERVED_WISE,
+            "Principal not returned without penalty or reward"
+        );
+        let ret: Globals = wise.query_named_key(GLOBALS.into());
+        assert_eq!(ret.total_staked, 0.into(), "Total staked not decreased");
+        assert_eq!(ret.total_shares, 0.into(), "Total shares not decreased");
+        assert_eq!(ret.referral_shares, 0.into(), "Referral shares changed");
+        let item = dictionary_keys::stakes(&Key::Account(owner), &stake_id);
+        let stake: dictionary_keys::Stake = wise
+            .query_dictionary(item.dictionary_name, item.item_key)
+            .unwrap();
+        assert!(!stake.is_active, "Stake still active");
+        assert_eq!(stake.reward_amount, 0.into(), "Stake rewarded");
+    }
+
+    #[test]
+    fn should_store_referral_critical_mass_snapshot_and_rate_events() {
+        let (env, owner, _, wise, time) = init_mock_dex();
+        let referrer = Key::Account(env.next_user());
+        // the $10,000 critical mass threshold at the default 1:1 prices
+        let staked_amount: U256 = 10_000_000_000_000u64.into();
+        wise.call_contract(
+            owner,
+            "mint_supply",
+            runtime_args! {
+                "investor_address" => Key::Account(owner),
+                "amount" => staked_amount
+            },
+            time,
+        );
+        call(
+            &env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => CREATE_STAKE,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "staked_amount" => staked_amount,
+                "lock_days" => 365u64,
+                "referrer" => referrer
+            },
+            time,
+        );
+        let (stake_id, _, _): (Vec<u32>, U256, Vec<u32>) = result_key(&env, owner, CREATE_STAKE);
+        // ending after the start day takes the stake out of the critical mass again
+        call(
+            &env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => END_STAKE,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "stake_id" => stake_id
+            },
+            time + 2 * MILLI_SECONDS_IN_DAY,
+        );
+        let names: Vec<String> = stored_events(&wise)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        for event in [
+            "ReferralLinkCreated",
+            "CriticalMassActivated",
+            "CriticalMassReset",
+            "DailySnapshot",
+            "LiquidityRatesAdjusted",
+        ] {
+            assert!(
+                names.iter().any(|name| name == event),
+                "No {} event stored",
+                event
+            );
+        }
+    }
+
+    #[test]
+    fn should_fund_tier_links_from_referral_inflation() {
+        let (env, owner, _, wise, time) = init_mock_dex();
+        let upline = env.next_user();
+        let referrer = env.next_user();
+        propose_parameter(&wise, owner, "referral_tier_depth", 2.into(), time);
+        let time = time + TIMELOCK_DELAY;
+        execute_parameter(&wise, owner, "referral_tier_depth", time);
+        // the $10,000 critical mass threshold at the default 1:1 prices
+        let threshold: U256 = 10_000_000_000_000u64.into();
+        for investor in [referrer, owner] {
+            wise.call_contract(
+                owner,
+                "mint_supply",
+                runtime_args! {
+                    "investor_address" => Key::Account(investor),
+                    "amount" => threshold
+                },
+                time,
+            );
+        }
+        // each referred stake activates its referrer, the second one earns referrer shares
+        // and tier 2 shares for the upline
+        let mut stake_id: Vec<u32> = Vec::new();
+        for (staker, referred_by) in [(referrer, upline), (owner, referrer)] {
+            call(
+                &env,
+                staker,
+                SESSION_WASM_STAKEABLE,
+                runtime_args! {
+                    ENTRYPOINT => CREATE_STAKE,
+                    PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                    "staked_amount" => threshold,
+                    "lock_days" => 365u64,
+                    "referrer" => Key::Account(referred_by)
+                },
+                time,
+            );
+            let (id, _, _): (Vec<u32>, U256, Vec<u32>) = result_key(&env, staker, CREATE_STAKE);
+            stake_id = id;
+        }
+        wise.call_contract(
+            owner,
+            "manual_daily_snapshot",
+            runtime_args! {},
+            time + MILLI_SECONDS_IN_DAY,
+        );
+        let globals: Globals = wise.query_named_key(GLOBALS.into());
+        let total_supply: U256 = wise.query_named_key("total_supply".into());
+        let day: U256 = globals.current_stakeable_day - 1;
+        let item = dictionary_keys::rsnapshots(day);
+        let rsnapshot: dictionary_keys::RSnapShot = wise
+            .query_dictionary(item.dictionary_name, item.item_key)
+            .expect("Referral snapshot not taken");
+        let item = dictionary_keys::tsnapshots(day);
+        let tsnapshot: dictionary_keys::TSnapShot = wise
+            .query_dictionary(item.dictionary_name, item.item_key)
+            .expect("Tier snapshot not taken");
+        assert!(tsnapshot.total_shares > ZERO, "No tier shares");
+        // what all referral shares earned in a day before tiers existed
+        let referral_inflation: U256 = economics::pool_inflation(
+            globals.total_staked,
+            total_supply,
+            economics::REFERRALS_RATE.into(),
+        )
+        .unwrap();
+        let precision: U256 = economics::PRECISION_RATE.into();
+        let paid_out: U256 = rsnapshot.total_shares * precision / rsnapshot.inflation_amount
+            + tsnapshot.total_shares * precision / tsnapshot.inflation_amount;
+        assert!(
+            paid_out <= referral_inflation,
+            "Tiers inflate the referral pool"
+        );
+        assert!(
+            paid_out + 2 >= referral_inflation,
+            "Referral inflation not paid out"
+        );
+        // scraping before maturity forfeits tier shares with the referrer shares
+        let item = dictionary_keys::stake_tier_links(&Key::Account(owner), &stake_id);
+        let tier_links: Vec<(Key, Vec<u32>)> = wise
+            .query_dictionary(item.dictionary_name, item.item_key)
+            .expect("Tier links not kept with the stake");
+        assert_eq!(tier_links.len(), 1, "Not one tier link");
+        let (tier_upline, tier_link_id) = tier_links[0].clone();
+        assert_eq!(tier_upline, Key::Account(upline), "Wrong upline");
+        let tier_link = |contract: &TestContract| -> dictionary_keys::TierLink {
+            let item = dictionary_keys::tier_links(&tier_upline, &tier_link_id);
+            contract
+                .query_dictionary(item.dictionary_name, item.item_key)
+                .unwrap()
+        };
+        let stake = |contract: &TestContract| -> dictionary_keys::Stake {
+            let item = dictionary_keys::stakes(&Key::Account(owner), &stake_id);
+            contract
+                .query_dictionary(item.dictionary_name, item.item_key)
+                .unwrap()
+        };
+        let before: dictionary_keys::Stake = stake(&wise);
+        let link_before: dictionary_keys::TierLink = tier_link(&wise);
+        let tier_shares_before: U256 = wise.query_named_key(TIER_SHARES.into());
+        assert_eq!(
+            before.tier_shares, link_before.tier_shares,
+            "Stake tier shares differ from its link"
+        );
+        call(
+            &env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => SCRAPE_INTEREST,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "stake_id" => stake_id.clone(),
+                "scrape_days" => 0u64
+            },
+            time + 3 * MILLI_SECONDS_IN_DAY,
+        );
+        let after: dictionary_keys::Stake = stake(&wise);
+        let link_after: dictionary_keys::TierLink = tier_link(&wise);
+        let tier_shares_after: U256 = wise.query_named_key(TIER_SHARES.into());
+        assert!(
+            after.referrer_shares < before.referrer_shares,
+            "Scrape not penalized"
+        );
+        let forfeited: U256 = link_before.tier_shares - link_after.tier_shares;
+        assert_eq!(
+            forfeited,
+            link_before.tier_shares * (before.referrer_shares - after.referrer_shares)
+                / before.referrer_shares,
+            "Tier link not lowered with the referrer shares"
+        );
+        assert!(forfeited > ZERO, "Tier shares not forfeited");
+        assert_eq!(
+            after.tier_shares, link_after.tier_shares,
+            "Stake tier shares not lowered"
+        );
+        assert_eq!(
+            tier_shares_before - tier_shares_after,
+            forfeited,
+            "Tier pool still counts forfeited shares"
+        );
+    }
+
+    fn collect_all(
+        env: &TestEnv,
+        referrer: AccountHash,
+        wise: &TestContract,
+        max_links: u64,
+        time: u64,
+    ) -> (U256, U256) {
+        call(
+            env,
+            referrer,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => COLLECT_ALL_REFERRAL_INTEREST,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "cursor" => None::<U256>,
+                "max_links" => U256::from(max_links)
+            },
+            time,
+        );
+        result_key(env, referrer, COLLECT_ALL_REFERRAL_INTEREST)
+    }
+
+    #[test]
+    fn should_resume_collecting_referral_links_at_the_stored_cursor() {
+        let (env, owner, _, wise, time) = init_mock_dex();
+        let referrer = env.next_user();
+        // the first stake reaches the $10,000 threshold, so each stake links to the referrer
+        let staked_amount: U256 = 10_000_000_000_000u64.into();
+        wise.call_contract(
+            owner,
+            "mint_supply",
+            runtime_args! {
+                "investor_address" => Key::Account(owner),
+                "amount" => staked_amount * 3
+            },
+            time,
+        );
+        let mut referral_ids: Vec<Vec<u32>> = Vec::new();
+        for _ in 0..3 {
+            call(
+                &env,
+                owner,
+                SESSION_WASM_STAKEABLE,
+                runtime_args! {
+                    ENTRYPOINT => CREATE_STAKE,
+                    PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                    "staked_amount" => staked_amount,
+                    "lock_days" => 365u64,
+                    "referrer" => Key::Account(referrer)
+                },
+                time,
+            );
+            let (_, _, referral_id): (Vec<u32>, U256, Vec<u32>) =
+                result_key(&env, owner, CREATE_STAKE);
+            referral_ids.push(referral_id);
+        }
+        let time = time + 3 * MILLI_SECONDS_IN_DAY;
+        let (next_cursor, first_reward) = collect_all(&env, referrer, &wise, 2, time);
+        assert_eq!(next_cursor, 2.into(), "Cursor not stored after two links");
+        assert!(
+            first_reward > ZERO,
+            "Nothing collected from the first links"
+        );
+        let (next_cursor, second_reward) = collect_all(&env, referrer, &wise, 2, time);
+        assert_eq!(next_cursor, ZERO, "Cursor not reset after the last link");
+        assert!(second_reward > ZERO, "Last link not collected");
+        let mut collected: U256 = ZERO;
+        for referral_id in referral_ids {
+            let item = dictionary_keys::referrer_links(&Key::Account(referrer), &referral_id);
+            let link: dictionary_keys::ReferrerLink = wise
+                .query_dictionary(item.dictionary_name, item.item_key)
+                .unwrap();
+            assert!(link.reward_amount > ZERO, "Link skipped");
+            collected += link.reward_amount;
+        }
+        assert_eq!(
+            collected,
+            first_reward + second_reward,
+            "Link collected twice"
+        );
+    }
+
+    fn claimable_referral_interest(
+        env: &TestEnv,
+        owner: AccountHash,
+        wise: &TestContract,
+        referrer: AccountHash,
+        time: u64,
+    ) -> U256 {
+        call(
+            env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => REFERRER_SUMMARY,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "referrer" => Key::Account(referrer)
+            },
+            time,
+        );
+        let ret: ReferrerSummaryView = result_key(env, owner, REFERRER_SUMMARY);
+        ret.claimable
+    }
+
+    #[test]
+    fn should_summarize_what_collecting_referral_links_pays() {
+        let (env, owner, _, wise, time) = init_mock_dex();
+        let referrer = env.next_user();
+        // the first stake reaches the $10,000 threshold, the second links two days later
+        let threshold: U256 = 10_000_000_000_000u64.into();
+        wise.call_contract(
+            owner,
+            "mint_supply",
+            runtime_args! {
+                "investor_address" => Key::Account(owner),
+                "amount" => threshold + ONEHUNDRED_CSPR
+            },
+            time,
+        );
+        for (day, amount) in [(0, threshold), (2, ONEHUNDRED_CSPR)] {
+            call(
+                &env,
+                owner,
+                SESSION_WASM_STAKEABLE,
+                runtime_args! {
+                    ENTRYPOINT => CREATE_STAKE,
+                    PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                    "staked_amount" => amount,
+                    "lock_days" => 365u64,
+                    "referrer" => Key::Account(referrer)
+                },
+                time + day * MILLI_SECONDS_IN_DAY,
+            );
+        }
+        let time = time + 6 * MILLI_SECONDS_IN_DAY;
+        // the summary only reads snapshots, collecting would take the missing ones first
+        wise.call_contract(owner, "manual_daily_snapshot", runtime_args! {}, time);
+        let claimable: U256 = claimable_referral_interest(&env, owner, &wise, referrer, time);
+        assert!(claimable > ZERO, "Nothing claimable after activation");
+        let (_, collected) = collect_all(&env, referrer, &wise, 10, time);
+        // the summary divides the summed shares once a day, each link rounds down on its own
+        assert!(collected <= claimable, "Summary below collected interest");
+        assert!(
+            claimable - collected <= 2 * 6,
+            "Summary counts days that are not paid"
+        );
+        assert_eq!(
+            claimable_referral_interest(&env, owner, &wise, referrer, time),
+            claimable - collected,
+            "Collected interest still claimable"
+        );
+    }
+
+    #[test]
+    fn should_count_referral_links_from_the_end_of_the_referrer_cooldown() {
+        let (env, owner, _, wise, time) = init_mock_dex();
+        let referrer = env.next_user();
+        propose_parameter(&wise, owner, "referrer_cooldown", 5.into(), time);
+        let time = time + TIMELOCK_DELAY;
+        execute_parameter(&wise, owner, "referrer_cooldown", time);
+        // the $10,000 critical mass threshold at the default 1:1 prices
+        let threshold: U256 = 10_000_000_000_000u64.into();
+        for (investor, amount) in [
+            (referrer, ONEHUNDRED_CSPR),
+            (owner, threshold + ONEHUNDRED_CSPR),
+        ] {
+            wise.call_contract(
+                owner,
+                "mint_supply",
+                runtime_args! {
+                    "investor_address" => Key::Account(investor),
+                    "amount" => amount
+                },
+                time,
+            );
+        }
+        create_stake(&env, referrer, &wise, time);
+        let item = dictionary_keys::first_stake_day(&Key::Account(referrer));
+        let first_stake_day: U256 = wise
+            .query_dictionary(item.dictionary_name, item.item_key)
+            .unwrap();
+        // one referred stake inside the cooldown, one after it
+        let mut referral_ids: Vec<Vec<u32>> = Vec::new();
+        for (day, amount) in [(0, threshold), (7, ONEHUNDRED_CSPR)] {
+            call(
+                &env,
+                owner,
+                SESSION_WASM_STAKEABLE,
+                runtime_args! {
+                    ENTRYPOINT => CREATE_STAKE,
+                    PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                    "staked_amount" => amount,
+                    "lock_days" => 365u64,
+                    "referrer" => Key::Account(referrer)
+                },
+                time + day * MILLI_SECONDS_IN_DAY,
+            );
+            let (_, _, referral_id): (Vec<u32>, U256, Vec<u32>) =
+                result_key(&env, owner, CREATE_STAKE);
+            assert!(!referral_id.is_empty(), "Referral link not recorded");
+            referral_ids.push(referral_id);
+        }
+        let item = dictionary_keys::referrer_cooldown_ends(&Key::Account(referrer));
+        let cooldown_end: U256 = wise
+            .query_dictionary(item.dictionary_name, item.item_key)
+            .unwrap();
+        assert_eq!(cooldown_end, first_stake_day + 5, "Wrong cooldown end");
+        let time = time + 12 * MILLI_SECONDS_IN_DAY;
+        let (_, reward_amount) = collect_all(&env, referrer, &wise, 10, time);
+        assert!(reward_amount > ZERO, "Nothing collected after the cooldown");
+        let globals: Globals = wise.query_named_key(GLOBALS.into());
+        // the first link starts at the end of the cooldown, the second with its stake
+        for (referral_id, start_day) in referral_ids.iter().zip([cooldown_end, first_stake_day + 7])
+        {
+            let item = dictionary_keys::referrer_links(&Key::Account(referrer), referral_id);
+            let link: dictionary_keys::ReferrerLink = wise
+                .query_dictionary(item.dictionary_name, item.item_key)
+                .unwrap();
+            assert_eq!(
+                link.processed_days,
+                globals.current_stakeable_day - start_day,
+                "Link counted from the wrong day"
+            );
+            assert!(link.reward_amount > ZERO, "Link not paid");
+        }
+    }
+
+    #[test]
+    fn should_match_simulator_globals_snapshots_and_rewards() {
+        // default `referral_tier_2_rate` in basis points
+        const TIER_2_RATE: u32 = 2_000;
+        let (env, owner, mocks, wise, time) = init_mock_dex();
+        let upline = env.next_user();
+        let referrer = env.next_user();
+        propose_parameter(&wise, owner, "referral_tier_depth", 2.into(), time);
+        let time = time + TIMELOCK_DELAY;
+        execute_parameter(&wise, owner, "referral_tier_depth", time);
+        // the $10,000 critical mass threshold at the default 1:1 prices
+        let threshold: U256 = 10_000_000_000_000u64.into();
+        for (investor, amount) in [(referrer, threshold), (owner, threshold + ONEHUNDRED_CSPR)] {
+            wise.call_contract(
+                owner,
+                "mint_supply",
+                runtime_args! {
+                    "investor_address" => Key::Account(investor),
+                    "amount" => amount
+                },
+                time,
+            );
+        }
+        wise.call_contract(owner, "manual_daily_snapshot", runtime_args! {}, time);
+        // the simulator takes over the state of the deployment before the first stake
+        let globals: Globals = wise.query_named_key(GLOBALS.into());
+        let start_day: u64 = globals.current_stakeable_day.as_u64();
+        let guard: &TestContract = &mocks.liquidity_guard;
+        let mut simulator = Simulator::new(
+            wise.query_named_key("total_supply".into()),
+            |rate: u32| -> U256 {
+                guard
+                    .query_dictionary(INFLATION_LN, rate.to_string())
+                    .unwrap_or_default()
+            },
+        );
+        simulator.day = start_day;
+        simulator.share_price = globals.share_price;
+        simulator.inflation_rate = wise.query_named_key(INFLATION_RATE.into());
+        simulator.liquidity_rate = wise.query_named_key(LIQUIDITY_RATE.into());
+        simulator.is_liquidity_guard_active =
+            wise.query_named_key(IS_LIQUIDITY_GUARD_ACTIVE.into());
+        for pool in [
+            &mut simulator.snapshots,
+            &mut simulator.rsnapshots,
+            &mut simulator.tsnapshots,
+            &mut simulator.lsnapshots,
+        ] {
+            pool.resize(start_day as usize, PoolSnapshot::default());
+        }
+
+        // the referrer activates the upline, the owner activates the referrer and books
+        // tier 2 shares for the upline
+        let mut stakes: Vec<(AccountHash, Vec<u32>, usize)> = Vec::new();
+        for (staker, amount, lock_days, referred_by) in [
+            (referrer, threshold, 365u64, Some(upline)),
+            (owner, threshold, 365, Some(referrer)),
+            (owner, ONEHUNDRED_CSPR, 20, None),
+        ] {
+            call(
+                &env,
+                staker,
+                SESSION_WASM_STAKEABLE,
+                runtime_args! {
+                    ENTRYPOINT => CREATE_STAKE,
+                    PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                    "staked_amount" => amount,
+                    "lock_days" => lock_days,
+                    "referrer" => referred_by.map_or(account_zero_address(), Key::Account)
+                },
+                time,
+            );
+            let (stake_id, _, _): (Vec<u32>, U256, Vec<u32>) =
+                result_key(&env, staker, CREATE_STAKE);
+            let index: usize = simulator
+                .create_stake(
+                    amount,
+                    lock_days,
+                    referred_by.is_some(),
+                    referred_by.is_some(),
+                )
+                .unwrap();
+            stakes.push((staker, stake_id, index));
+        }
+        simulator.add_tier_links(1, &[TIER_2_RATE]).unwrap();
+
+        let time = time + 5 * MILLI_SECONDS_IN_DAY;
+        simulator.advance_days(5).unwrap();
+        call(
+            &env,
+            owner,
+            SESSION_WASM_STAKEABLE,
+            runtime_args! {
+                ENTRYPOINT => END_STAKE,
+                PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                "stake_id" => stakes[2].1.clone()
+            },
+            time,
+        );
+        let reward_amount: U256 = result_key(&env, owner, END_STAKE);
+        let (simulated_reward, _) = simulator.end_stake(stakes[2].2).unwrap();
+        assert_eq!(reward_amount, simulated_reward, "Early end reward differs");
+
+        let time = time + 5 * MILLI_SECONDS_IN_DAY;
+        simulator.advance_days(5).unwrap();
+        wise.call_contract(owner, "manual_daily_snapshot", runtime_args! {}, time);
+        let globals: Globals = wise.query_named_key(GLOBALS.into());
+        assert_eq!(globals.current_stakeable_day, simulator.day.into());
+        assert_eq!(
+            globals.total_staked, simulator.total_staked,
+            "Total staked differs"
+        );
+        assert_eq!(
+            globals.total_shares, simulator.total_shares,
+            "Total shares differ"
+        );
+        assert_eq!(
+            globals.referral_shares, simulator.referral_shares,
+            "Referral shares differ"
+        );
+        assert_eq!(
+            globals.share_price, simulator.share_price,
+            "Share price differs"
+        );
+        assert_eq!(
+            wise.query_named_key::<U256>("total_supply".into()),
+            simulator.total_supply,
+            "Total supply differs"
+        );
+        assert!(simulator.tier_shares > ZERO, "No tier shares simulated");
+        for day in start_day..simulator.day {
+            let item = dictionary_keys::snapshots(day.into());
+            let snapshot: dictionary_keys::SnapShot = wise
+                .query_dictionary(item.dictionary_name, item.item_key)
+                .unwrap();
+            let item = dictionary_keys::rsnapshots(day.into());
+            let rsnapshot: dictionary_keys::RSnapShot = wise
+                .query_dictionary(item.dictionary_name, item.item_key)
+                .unwrap();
+            let item = dictionary_keys::tsnapshots(day.into());
+            let tsnapshot: dictionary_keys::TSnapShot = wise
+                .query_dictionary(item.dictionary_name, item.item_key)
+                .unwrap();
+            for (pool, stored, simulated) in [
+                (
+                    "regular",
+                    (snapshot.total_shares, snapshot.inflation_amount),
+                    simulator.snapshots[day as usize],
+                ),
+                (
+                    "referral",
+                    (rsnapshot.total_shares, rsnapshot.inflation_amount),
+                    simulator.rsnapshots[day as usize],
+                ),
+                (
+                    "tier",
+                    (tsnapshot.total_shares, tsnapshot.inflation_amount),
+                    simulator.tsnapshots[day as usize],
+                ),
+            ] {
+                assert_eq!(
+                    stored,
+                    (simulated.total_shares, simulated.inflation_amount),
+                    "{} snapshot of day {} differs",
+                    pool,
+                    day
+                );
+            }
+        }
+        for (staker, stake_id, index) in stakes {
+            call(
+                &env,
+                staker,
+                SESSION_WASM_STAKEABLE,
+                runtime_args! {
+                    ENTRYPOINT => STAKE_VIEW_V1,
+                    PACKAGE_HASH => Key::Hash(wise.package_hash()),
+                    "staker" => Key::Account(staker),
+                    "stake_id" => stake_id
+                },
+                time,
+            );
+            let view: StakeView = result_key(&env, staker, STAKE_VIEW_V1);
+            let stake = simulator.stakes[index];
+            assert_eq!(
+                view.stakes_shares, stake.stakes_shares,
+                "Stake shares differ"
+            );
+            assert_eq!(
+                view.referrer_shares, stake.referrer_shares,
+                "Referrer shares differ"
+            );
+            assert_eq!(
+                view.reward_amount,
+                simulator.reward_of(&stake).unwrap(),
+                "Stake reward differs"
+            );
+        }
+    }
+}
@@ -0,0 +1,419 @@
+//! Records the gas used by the stakeable entry points across lock lengths, snapshot backlogs
+//! and referral link counts, and fails when an entry point got noticeably more expensive than
+//! in `gas-baseline.csv`. A missing baseline file or a measurement without a baseline row fails
+//! the run as well. Runs on the raw engine builder against the mocks, `TestEnv` does not
+//! report gas. Regenerate the baseline after an intended change with
+//! `UPDATE_GAS_BASELINE=1 cargo test -p stakeable-token-tests benchmarks`.
+
+use crate::tests::*;
+use casper_engine_test_support::{
+    ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_ACCOUNT_ADDR,
+    PRODUCTION_RUN_GENESIS_REQUEST,
+};
+use casper_types::{bytesrepr::FromBytes, system::mint, CLTyped, ContractPackageHash};
+use std::{collections::BTreeMap, fs};
+
+// fixed so runs are comparable, the launch time is taken from the install block
+const LAUNCH: u64 = 1_640_995_200_000;
+// a regression has to cost this much more than the baseline to fail the run
+const TOLERANCE_PERCENT: u64 = 5;
+// manual snapshots are taken in steps of this many days while time moves
+const SNAPSHOT_STEP_DAYS: u64 = 30;
+
+const LOCK_DAYS: [u64; 4] = [1, 30, 180, 365];
+const IDLE_DAYS: [u64; 4] = [1, 10, 30, 90];
+const REFERRAL_LINKS: [u64; 3] = [1, 10, 50];
+
+fn baseline_path() -> String {
+    format!("{}/gas-baseline.csv", env!("CARGO_MANIFEST_DIR"))
+}
+
+struct Bench {
+    builder: InMemoryWasmTestBuilder,
+    owner: AccountHash,
+    wise: Key,
+    pair: Key,
+    time: u64,
+    users: u8,
+    results: BTreeMap<(String, String), U512>,
+}
+
+impl Bench {
+    fn new() -> Bench {
+        let mut builder = InMemoryWasmTestBuilder::default();
+        builder.run_genesis(&*PRODUCTION_RUN_GENESIS_REQUEST);
+        let mut bench = Bench {
+            builder,
+            owner: *DEFAULT_ACCOUNT_ADDR,
+            wise: zero_address(),
+            pair: zero_address(),
+            time: LAUNCH,
+            users: 0,
+            results: BTreeMap::new(),
+        };
+        let stable_usd: Key = bench.install("mock-token.wasm", "mock-stable-usd", runtime_args! {});
+        let scspr: Key = bench.install("mock-token.wasm", "mock-scspr", runtime_args! {});
+        let wcspr: Key = bench.install("mock-token.wasm", "mock-wcspr", runtime_args! {});
+        let router: Key = bench.install("mock-router.wasm", "mock-router", runtime_args! {});
+        let factory: Key = bench.install("mock-factory.wasm", "mock-factory", runtime_args! {});
+        let liquidity_guard: Key = bench.install(
+            "mock-liquidity-guard.wasm",
+            "mock-liquidity-guard",
+            runtime_args! {},
+        );
+        bench.pair = bench.install("mock-token.wasm", "mock-pair", runtime_args! {});
+        bench.wise = bench.install(
+            "stakeable-token.wasm",
+            "stakeable-token",
+            runtime_args! {
+                "stable_usd" => stable_usd,
+                "scspr" => scspr,
+                "wcspr" => wcspr,
+                "uniswap_router" => router,
+                "uniswap_factory" => factory,
+                "uniswap_pair" => bench.pair,
+                "liquidity_guard" => liquidity_guard,
+                "amount" => STAKEABLE_AMOUNT
+            },
+        );
+        bench.call_contract(
+            bench.owner,
+            bench.pair,
+            "set_tokens",
+            runtime_args! {
+                "token0" => bench.wise,
+                "token1" => scspr
+            },
+        );
+        bench.run(
+            bench.owner,
+            SESSION_WASM_MOCKS,
+            runtime_args! {
+                ENTRYPOINT => SET_LIQUIDITY_TRANSFOMER,
+                PACKAGE_HASH => bench.wise,
+                "immutable_transformer" => Key::Account(bench.owner)
+            },
+        );
+        bench.call_contract(
+            bench.owner,
+            bench.wise,
+            "mint_supply",
+            runtime_args! {
+                "investor_address" => Key::Account(bench.owner),
+                "amount" => RESERVED_WISE * 100u64
+            },
+        );
+        bench.time += 2 * MILLI_SECONDS_IN_DAY;
+        bench
+    }
+
+    fn run(&mut self, sender: AccountHash, wasm: &str, mut args: RuntimeArgs) {
+        args.insert("contract_name", "call".to_string()).unwrap();
+        let request = ExecuteRequestBuilder::standard(sender, wasm, args)
+            .with_block_time(self.time)
+            .build();
+        self.builder.exec(request).expect_success().commit();
+    }
+
+    fn install(&mut self, wasm: &str, contract_name: &str, mut args: RuntimeArgs) -> Key {
+        args.insert("contract_name", contract_name.to_string())
+            .unwrap();
+        let request = ExecuteRequestBuilder::standard(self.owner, wasm, args)
+            .with_block_time(self.time)
+            .build();
+        self.builder.exec(request).expect_success().commit();
+        *self
+            .builder
+            .get_expected_account(self.owner)
+            .named_keys()
+            .get(&format!("{}_package_hash", contract_name))
+            .unwrap()
+    }
+
+    fn call_contract(
+        &mut self,
+        sender: AccountHash,
+        package: Key,
+        entry_point: &str,
+        args: RuntimeArgs,
+    ) {
+        let request = ExecuteRequestBuilder::versioned_contract_call_by_hash(
+            sender,
+            ContractPackageHash::new(package.into_hash().unwrap()),
+            None,
+            entry_point,
+            args,
+        )
+        .with_block_time(self.time)
+        .build();
+        self.builder.exec(request).expect_success().commit();
+    }
+
+    fn session<T: CLTyped + FromBytes>(
+        &mut self,
+        sender: AccountHash,
+        entrypoint: &str,
+        mut args: RuntimeArgs,
+    ) -> T {
+        args.insert(ENTRYPOINT, entrypoint.to_string()).unwrap();
+        args.insert(PACKAGE_HASH, self.wise).unwrap();
+        self.run(sender, SESSION_WASM_STAKEABLE, args);
+        self.builder
+            .query(None, Key::Account(sender), &[entrypoint.to_string()])
+            .unwrap()
+            .as_cl_value()
+            .cloned()
+            .unwrap()
+            .into_t()
+            .unwrap()
+    }
+
+    /// Gas of the deploy executed last
+    fn record(&mut self, entry_point: &str, scenario: String) {
+        let gas: U512 = self.builder.last_exec_gas_cost().value();
+        self.results
+            .insert((entry_point.to_string(), scenario), gas);
+    }
+
+    /// Funded account without tokens
+    fn account(&mut self) -> AccountHash {
+        self.users += 1;
+        let account = AccountHash::new([self.users; 32]);
+        let request = ExecuteRequestBuilder::transfer(
+            self.owner,
+            runtime_args! {
+                mint::ARG_AMOUNT => U512::from(1_000_000_000_000_000u64),
+                mint::ARG_TARGET => account,
+                mint::ARG_ID => Option::<u64>::None
+            },
+        )
+        .build();
+        self.builder.exec(request).expect_success().commit();
+        account
+    }
+
+    fn snapshot(&mut self) {
+        self.call_contract(
+            self.owner,
+            self.wise,
+            "manual_daily_snapshot",
+            runtime_args! {},
+        );
+    }
+
+    /// Moves time and keeps the snapshots up to date, so only the measured call pays for them
+    fn advance(&mut self, days: u64) {
+        let mut left: u64 = days;
+        while left > 0 {
+            let step: u64 = left.min(SNAPSHOT_STEP_DAYS);
+            self.time += step * MILLI_SECONDS_IN_DAY;
+            self.snapshot();
+            left -= step;
+        }
+    }
+
+    /// Moves time without snapshots, the next call catches up on the backlog
+    fn idle(&mut self, days: u64) {
+        self.time += days * MILLI_SECONDS_IN_DAY;
+    }
+
+    fn create_stake(&mut self, lock_days: u64, referrer: Key) -> Vec<u32> {
+        let (stake_id, _, _): (Vec<u32>, U256, Vec<u32>) = self.session(
+            self.owner,
+            CREATE_STAKE,
+            runtime_args! {
+                "staked_amount" => ONEHUNDRED_CSPR,
+                "lock_days" => lock_days,
+                "referrer" => referrer
+            },
+        );
+        stake_id
+    }
+
+    fn lock_lengths(&mut self) {
+        for lock_days in LOCK_DAYS {
+            let stake_id: Vec<u32> = self.create_stake(lock_days, account_zero_address());
+            self.record(CREATE_STAKE, format!("lock_days={}", lock_days));
+            // scraping needs the stake to have started
+            let mut waited: u64 = 0;
+            if lock_days / 2 > 1 {
+                waited = lock_days / 2;
+                self.advance(waited);
+                let _: Vec<String> = self.session(
+                    self.owner,
+                    SCRAPE_INTEREST,
+                    runtime_args! {
+                        "stake_id" => stake_id.clone(),
+                        "scrape_days" => 0u64
+                    },
+                );
+                self.record(SCRAPE_INTEREST, format!("lock_days={}", lock_days));
+            }
+            self.advance(lock_days + 1 - waited);
+            let _: U256 = self.session(
+                self.owner,
+                END_STAKE,
+                runtime_args! {
+                    "stake_id" => stake_id
+                },
+            );
+            self.record(END_STAKE, format!("lock_days={}", lock_days));
+        }
+    }
+
+    fn snapshot_backlogs(&mut self) {
+        for idle_days in IDLE_DAYS {
+            self.idle(idle_days);
+            self.snapshot();
+            self.record("manual_daily_snapshot", format!("idle_days={}", idle_days));
+            self.idle(idle_days);
+            self.create_stake(30, account_zero_address());
+            self.record(CREATE_STAKE, format!("idle_days={}", idle_days));
+        }
+    }
+
+    fn referral_links(&mut self) {
+        for links in REFERRAL_LINKS {
+            let referrer: AccountHash = self.account();
+            for _ in 0..links {
+                self.create_stake(30, Key::Account(referrer));
+            }
+            self.advance(2);
+            let _: (U256, U256) = self.session(
+                referrer,
+                COLLECT_ALL_REFERRAL_INTEREST,
+                runtime_args! {
+                    "cursor" => None::<U256>,
+                    "max_links" => U256::from(0)
+                },
+            );
+            self.record(
+                COLLECT_ALL_REFERRAL_INTEREST,
+                format!("referral_links={}", links),
+            );
+        }
+    }
+
+    fn transfers_and_liquidity(&mut self) {
+        let recipient: AccountHash = self.account();
+        self.call_contract(
+            self.owner,
+            self.wise,
+            "transfer",
+            runtime_args! {
+                "recipient" => Key::Account(recipient),
+                "amount" => ONEHUNDRED_CSPR
+            },
+        );
+        self.record("transfer", "base".into());
+        // empty reserves keep the liquidity guard active
+        self.call_contract(
+            self.owner,
+            self.pair,
+            "mint",
+            runtime_args! {
+                "to" => Key::Account(self.owner),
+                "amount" => ONEHUNDRED_CSPR
+            },
+        );
+        self.call_contract(
+            self.owner,
+            self.pair,
+            "approve",
+            runtime_args! {
+                "spender" => self.wise,
+                "amount" => ONEHUNDRED_CSPR
+            },
+        );
+        let liquidity_stake_id: Vec<u32> = self.session(
+            self.owner,
+            CREATE_LIQUIDITY_STAKE,
+            runtime_args! {
+                "liquidity_tokens" => ONEHUNDRED_CSPR
+            },
+        );
+        self.record(CREATE_LIQUIDITY_STAKE, "base".into());
+        self.advance(30);
+        let _: U256 = self.session(
+            self.owner,
+            END_LIQUIDITY_STAKE,
+            runtime_args! {
+                "liquidity_stake_id" => liquidity_stake_id
+            },
+        );
+        self.record(END_LIQUIDITY_STAKE, "liquidity_days=30".into());
+    }
+}
+
+fn read_baseline() -> Option<BTreeMap<(String, String), U512>> {
+    let baseline: String = fs::read_to_string(baseline_path()).ok()?;
+    Some(
+        baseline
+            .lines()
+            .skip(1)
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                let fields: Vec<&str> = line.split(',').collect();
+                (
+                    (fields[0].to_string(), fields[1].to_string()),
+                    U512::from_dec_str(fields[2]).unwrap(),
+                )
+            })
+            .collect(),
+    )
+}
+
+fn write_baseline(results: &BTreeMap<(String, String), U512>) {
+    let mut baseline: String = "entry_point,scenario,gas\n".to_string();
+    for ((entry_point, scenario), gas) in results {
+        baseline.push_str(&format!("{},{},{}\n", entry_point, scenario, gas));
+    }
+    fs::write(baseline_path(), baseline).unwrap();
+}
+
+#[test]
+fn should_not_regress_gas_costs() {
+    let mut bench = Bench::new();
+    bench.lock_lengths();
+    bench.snapshot_backlogs();
+    bench.referral_links();
+    bench.transfers_and_liquidity();
+
+    if std::env::var("UPDATE_GAS_BASELINE").is_ok() {
+        write_baseline(&bench.results);
+        println!("gas baseline written to {}", baseline_path());
+        return;
+    }
+    let baseline = read_baseline().unwrap_or_else(|| {
+        panic!(
+            "No gas baseline at {}, run with UPDATE_GAS_BASELINE=1 to write it",
+            baseline_path()
+        )
+    });
+    let mut regressions: Vec<String> = Vec::new();
+    for ((entry_point, scenario), gas) in &bench.results {
+        match baseline.get(&(entry_point.clone(), scenario.clone())) {
+            Some(expected) => {
+                println!(
+                    "{} {}: {} (baseline {})",
+                    entry_point, scenario, gas, expected
+                );
+                if *gas * 100 > *expected * (100 + TOLERANCE_PERCENT) {
+                    regressions.push(format!(
+                        "{} {}: {} > {} + {}%",
+                        entry_point, scenario, gas, expected, TOLERANCE_PERCENT
+                    ));
+                }
+            }
+            None => regressions.push(format!(
+                "{} {}: {} is not in the baseline",
+                entry_point, scenario, gas
+            )),
+        }
+    }
+    assert!(
+        regressions.is_empty(),
+        "Gas regressions or missing baseline entries, run with UPDATE_GAS_BASELINE=1 if intended:\n{}",
+        regressions.join("\n")
+    );
+}
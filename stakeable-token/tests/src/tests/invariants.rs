@@ -0,0 +1,570 @@
+//! Runs random sequences of stake actions against one deployment and checks the stake
+//! accounting after every step. proptest shrinks a failing sequence and stores its seed in
+//! `proptest-regressions/tests/invariants.txt`, commit that file so every later run replays
+//! it first. Shrunk sequences worth keeping readable are added to `regressions` below.
+
+use crate::tests::*;
+use casper_types::{bytesrepr::FromBytes, CLTyped};
+use proptest::{prelude::*, test_runner::TestCaseError};
+use tests_common::views::{LiquidityStakeView, ReferrerSummaryView};
+
+// every case deploys the whole environment, raise with PROPTEST_CASES for longer runs
+const DEFAULT_CASES: u32 = 32;
+const MAX_SHRINK_ITERS: u32 = 64;
+const MAX_ACTIONS: usize = 12;
+const MAX_WAIT_DAYS: u64 = 10;
+const MAX_SCRAPE_DAYS: u64 = 30;
+
+/// Stakes and liquidity stakes are picked by `index % count`, actions without a valid
+/// target are skipped so shrinking never turns a sequence into a reverting one.
+#[derive(Debug, Clone)]
+enum Action {
+    CreateStake {
+        tenths: u64,
+        lock_days: u64,
+        referred: bool,
+    },
+    EndStake {
+        index: usize,
+    },
+    ScrapeInterest {
+        index: usize,
+        scrape_days: u64,
+    },
+    CollectReferralInterest,
+    CreateLiquidityStake {
+        twentieths: u64,
+    },
+    EndLiquidityStake {
+        index: usize,
+    },
+    Wait {
+        days: u64,
+    },
+}
+
+fn action() -> impl Strategy<Value = Action> {
+    prop_oneof![
+        3 => (1..=20u64, prop_oneof![1..=60u64, 365..=400u64], any::<bool>()).prop_map(
+            |(tenths, lock_days, referred)| Action::CreateStake {
+                tenths,
+                lock_days,
+                referred
+            }
+        ),
+        2 => any::<usize>().prop_map(|index| Action::EndStake { index }),
+        2 => (any::<usize>(), 0..=MAX_SCRAPE_DAYS)
+            .prop_map(|(index, scrape_days)| Action::ScrapeInterest { index, scrape_days }),
+        1 => Just(Action::CollectReferralInterest),
+        1 => (1..=20u64).prop_map(|twentieths| Action::CreateLiquidityStake { twentieths }),
+        1 => any::<usize>().prop_map(|index| Action::EndLiquidityStake { index }),
+        3 => (0..=MAX_WAIT_DAYS).prop_map(|days| Action::Wait { days }),
+    ]
+}
+
+/// Index 0 is the stake that activates the referrer, sequences start at index 1
+fn regressions() -> Vec<Vec<Action>> {
+    vec![
+        // immature scrape followed by an early end of the same stake
+        vec![
+            Action::CreateStake {
+                tenths: 10,
+                lock_days: 20,
+                referred: false,
+            },
+            Action::Wait { days: 3 },
+            Action::ScrapeInterest {
+                index: 1,
+                scrape_days: 0,
+            },
+            Action::Wait { days: 2 },
+            Action::EndStake { index: 1 },
+        ],
+        // short stake ended past its final day, then a referred long stake ended early
+        vec![
+            Action::CreateStake {
+                tenths: 20,
+                lock_days: 365,
+                referred: true,
+            },
+            Action::CreateStake {
+                tenths: 1,
+                lock_days: 1,
+                referred: false,
+            },
+            Action::Wait { days: 5 },
+            Action::CollectReferralInterest,
+            Action::EndStake { index: 2 },
+            Action::EndStake { index: 1 },
+        ],
+    ]
+}
+
+fn cases() -> u32 {
+    std::env::var("PROPTEST_CASES")
+        .ok()
+        .and_then(|cases| cases.parse().ok())
+        .unwrap_or(DEFAULT_CASES)
+}
+
+fn sum(values: impl Iterator<Item = U256>) -> U256 {
+    values.fold(0.into(), |sum, value| sum + value)
+}
+
+/// Most a pool can pay out over the snapshots, a stake earns `shares * PRECISION_RATE /
+/// inflation_amount` a day so all stakes of a day together earn at most this. `shares` must
+/// be the shares the pool pays on, which are not always the shares the snapshot splits
+fn budget(snapshots: impl Iterator<Item = (U256, U256)>) -> U256 {
+    sum(snapshots
+        .filter(|(_, inflation_amount)| *inflation_amount > 0.into())
+        .map(|(total_shares, inflation_amount)| {
+            total_shares * economics::PRECISION_RATE / inflation_amount
+        }))
+}
+
+struct Harness {
+    env: TestEnv,
+    owner: AccountHash,
+    referrer: AccountHash,
+    wise: TestContract,
+    pair: TestContract,
+    time: u64,
+    stakes: Vec<Vec<u32>>,
+    liquidity_stakes: Vec<Vec<u32>>,
+    regular_minted: U256,
+    referral_minted: U256,
+    liquidity_minted: U256,
+    snapshot_day: U256,
+}
+
+impl Harness {
+    fn new() -> Harness {
+        let (env, owner, wise, pair, time) = init_with_pair();
+        let referrer = env.next_user();
+        let mut harness = Harness {
+            env,
+            owner,
+            referrer,
+            wise,
+            pair,
+            time,
+            stakes: Vec::new(),
+            liquidity_stakes: Vec::new(),
+            regular_minted: 0.into(),
+            referral_minted: 0.into(),
+            liquidity_minted: 0.into(),
+            snapshot_day: 0.into(),
+        };
+        harness.activate_referrer();
+        harness.sync();
+        harness.snapshot_day = harness.globals().current_stakeable_day;
+        harness
+    }
+
+    /// lowers the critical mass threshold so the first referred stake activates the referrer,
+    /// without it referred stakes never pay out and the referral budget is never tested
+    fn activate_referrer(&mut self) {
+        propose_parameter(
+            &self.wise,
+            self.owner,
+            "threshold_limit",
+            1.into(),
+            self.time,
+        );
+        self.time += TIMELOCK_DELAY;
+        execute_parameter(&self.wise, self.owner, "threshold_limit", self.time);
+        self.apply(&Action::CreateStake {
+            tenths: 1,
+            lock_days: 365,
+            referred: true,
+        });
+        let summary: ReferrerSummaryView = self.session(
+            self.owner,
+            REFERRER_SUMMARY,
+            runtime_args! {
+                "referrer" => Key::Account(self.referrer)
+            },
+        );
+        assert!(summary.activation_day > 0.into(), "Referrer not activated");
+    }
+
+    fn session<T: CLTyped + FromBytes>(
+        &self,
+        sender: AccountHash,
+        entrypoint: &str,
+        mut args: RuntimeArgs,
+    ) -> T {
+        args.insert(ENTRYPOINT, entrypoint.to_string()).unwrap();
+        args.insert(PACKAGE_HASH, Key::Hash(self.wise.package_hash()))
+            .unwrap();
+        call(&self.env, sender, SESSION_WASM_STAKEABLE, args, self.time);
+        result_key(&self.env, sender, entrypoint)
+    }
+
+    fn globals(&self) -> Globals {
+        self.wise.query_named_key(GLOBALS.into())
+    }
+
+    fn balance(&self, token: &TestContract) -> U256 {
+        token
+            .query_dictionary("balances", key_to_str(&Key::Account(self.owner)))
+            .unwrap_or_default()
+    }
+
+    fn stake_views(&self) -> Vec<StakeView> {
+        self.stakes
+            .iter()
+            .map(|stake_id| {
+                self.session(
+                    self.owner,
+                    STAKE_VIEW_V1,
+                    runtime_args! {
+                        "staker" => Key::Account(self.owner),
+                        "stake_id" => stake_id.clone()
+                    },
+                )
+            })
+            .collect()
+    }
+
+    fn liquidity_stake_views(&self) -> Vec<LiquidityStakeView> {
+        self.liquidity_stakes
+            .iter()
+            .map(|liquidity_stake_id| {
+                self.session(
+                    self.owner,
+                    LIQUIDITY_STAKE_VIEW_V1,
+                    runtime_args! {
+                        "staker" => Key::Account(self.owner),
+                        "liquidity_stake_id" => liquidity_stake_id.clone()
+                    },
+                )
+            })
+            .collect()
+    }
+
+    fn active_stake(&self, index: usize) -> Option<StakeView> {
+        if self.stakes.is_empty() {
+            return None;
+        }
+        let stake_id: Vec<u32> = self.stakes[index % self.stakes.len()].clone();
+        let view: StakeView = self.session(
+            self.owner,
+            STAKE_VIEW_V1,
+            runtime_args! {
+                "staker" => Key::Account(self.owner),
+                "stake_id" => stake_id
+            },
+        );
+        Some(view).filter(|view| view.is_active)
+    }
+
+    fn snapshots<T: CLTyped + FromBytes>(&self, entrypoint: &str, today: U256) -> Vec<T> {
+        self.session(
+            self.owner,
+            entrypoint,
+            runtime_args! {
+                "from_day" => U256::from(0),
+                "to_day" => today - 1
+            },
+        )
+    }
+
+    /// takes the snapshots of the days passed since the last step
+    fn sync(&self) {
+        self.wise.call_contract(
+            self.owner,
+            "manual_daily_snapshot",
+            runtime_args! {},
+            self.time,
+        );
+    }
+
+    fn apply(&mut self, action: &Action) {
+        match *action {
+            Action::CreateStake {
+                tenths,
+                lock_days,
+                referred,
+            } => {
+                let staked_amount: U256 = ONEHUNDRED_CSPR / 10 * tenths;
+                if staked_amount > self.balance(&self.wise) {
+                    return;
+                }
+                let (stake_id, _, _): (Vec<u32>, U256, Vec<u32>) = self.session(
+                    self.owner,
+                    CREATE_STAKE,
+                    runtime_args! {
+                        "staked_amount" => staked_amount,
+                        "lock_days" => lock_days,
+                        "referrer" => if referred {
+                            Key::Account(self.referrer)
+                        } else {
+                            account_zero_address()
+                        }
+                    },
+                );
+                self.stakes.push(stake_id);
+            }
+            Action::EndStake { index } => {
+                if let Some(view) = self.active_stake(index) {
+                    let reward_amount: U256 = self.session(
+                        self.owner,
+                        END_STAKE,
+                        runtime_args! {
+                            "stake_id" => view.stake_id
+                        },
+                    );
+                    self.regular_minted += reward_amount;
+                }
+            }
+            Action::ScrapeInterest { index, scrape_days } => {
+                if let Some(view) = self.active_stake(index) {
+                    // scraping past the taken snapshots reverts, those scrape up to today
+                    let starting_day: U256 = if view.scrape_day == 0.into() {
+                        view.start_day.into()
+                    } else {
+                        view.scrape_day
+                    };
+                    let scrape_days: u64 =
+                        if starting_day + scrape_days > self.globals().current_stakeable_day {
+                            0
+                        } else {
+                            scrape_days
+                        };
+                    let ret: Vec<String> = self.session(
+                        self.owner,
+                        SCRAPE_INTEREST,
+                        runtime_args! {
+                            "stake_id" => view.stake_id,
+                            "scrape_days" => scrape_days
+                        },
+                    );
+                    self.regular_minted += U256::from_dec_str(&ret[1]).unwrap();
+                }
+            }
+            Action::CollectReferralInterest => {
+                let (_, reward_amount): (U256, U256) = self.session(
+                    self.referrer,
+                    COLLECT_ALL_REFERRAL_INTEREST,
+                    runtime_args! {
+                        "cursor" => None::<U256>,
+                        "max_links" => U256::from(0)
+                    },
+                );
+                self.referral_minted += reward_amount;
+            }
+            Action::CreateLiquidityStake { twentieths } => {
+                let is_active: bool = self.wise.query_named_key(IS_LIQUIDITY_GUARD_ACTIVE.into());
+                let liquidity_tokens: U256 = self.balance(&self.pair) * twentieths / 20;
+                if !is_active || liquidity_tokens == 0.into() {
+                    return;
+                }
+                self.pair.call_contract(
+                    self.owner,
+                    "approve",
+                    runtime_args! {
+                        "spender" => Key::Hash(self.wise.package_hash()),
+                        "amount" => liquidity_tokens
+                    },
+                    self.time,
+                );
+                let liquidity_stake_id: Vec<u32> = self.session(
+                    self.owner,
+                    CREATE_LIQUIDITY_STAKE,
+                    runtime_args! {
+                        "liquidity_tokens" => liquidity_tokens
+                    },
+                );
+                self.liquidity_stakes.push(liquidity_stake_id);
+            }
+            Action::EndLiquidityStake { index } => {
+                if self.liquidity_stakes.is_empty() {
+                    return;
+                }
+                let liquidity_stake_id: Vec<u32> =
+                    self.liquidity_stakes[index % self.liquidity_stakes.len()].clone();
+                let view: LiquidityStakeView = self.session(
+                    self.owner,
+                    LIQUIDITY_STAKE_VIEW_V1,
+                    runtime_args! {
+                        "staker" => Key::Account(self.owner),
+                        "liquidity_stake_id" => liquidity_stake_id.clone()
+                    },
+                );
+                if !view.is_active {
+                    return;
+                }
+                let reward_amount: U256 = self.session(
+                    self.owner,
+                    END_LIQUIDITY_STAKE,
+                    runtime_args! {
+                        "liquidity_stake_id" => liquidity_stake_id
+                    },
+                );
+                self.liquidity_minted += reward_amount;
+            }
+            Action::Wait { days } => self.time += days * MILLI_SECONDS_IN_DAY,
+        }
+    }
+
+    fn check(&mut self) -> Result<(), TestCaseError> {
+        let globals: Globals = self.globals();
+        let all_stakes: Vec<StakeView> = self.stake_views();
+        let stakes: Vec<StakeView> = all_stakes
+            .iter()
+            .filter(|stake| stake.is_active)
+            .cloned()
+            .collect();
+        let liquidity_stakes: Vec<LiquidityStakeView> = self
+            .liquidity_stake_views()
+            .into_iter()
+            .filter(|stake| stake.is_active)
+            .collect();
+        prop_assert_eq!(
+            globals.total_shares,
+            sum(stakes.iter().map(|stake| stake.stakes_shares)),
+            "Total shares differ from active stakes"
+        );
+        prop_assert_eq!(
+            globals.total_staked,
+            sum(stakes.iter().map(|stake| stake.staked_amount)),
+            "Total staked differs from active principal"
+        );
+        prop_assert_eq!(
+            globals.referral_shares,
+            sum(stakes.iter().map(|stake| stake.referrer_shares)),
+            "Referral shares differ from active stakes"
+        );
+        prop_assert_eq!(
+            globals.liquidity_shares,
+            sum(liquidity_stakes.iter().map(|stake| stake.staked_amount)),
+            "Liquidity shares differ from active liquidity stakes"
+        );
+
+        let today: U256 = globals.current_stakeable_day;
+        let snapshots: Vec<(U256, U256, U256)> = self.snapshots(SNAPSHOT_RANGE, today);
+        let rsnapshots: Vec<(U256, U256, U256)> = self.snapshots(RSNAPSHOT_RANGE, today);
+        let lsnapshots: Vec<(U256, U256)> = self.snapshots(LSNAPSHOT_RANGE, today);
+        if today > self.snapshot_day {
+            // only `Wait` moves time, so the sync took the newest snapshot after every change
+            let day: u64 = today.as_u64() - 1;
+            let ending_later: Vec<&StakeView> = stakes
+                .iter()
+                .filter(|stake| stake.final_day > day)
+                .collect();
+            prop_assert_eq!(
+                snapshots[day as usize].0,
+                sum(ending_later.iter().map(|stake| stake.stakes_shares)),
+                "Snapshot shares differ from total shares minus scheduled ends"
+            );
+            prop_assert_eq!(
+                rsnapshots[day as usize].0,
+                sum(ending_later.iter().map(|stake| stake.referrer_shares)),
+                "Referral snapshot shares differ from referral shares minus scheduled ends"
+            );
+            prop_assert_eq!(
+                lsnapshots[day as usize].0,
+                globals.liquidity_shares,
+                "Liquidity snapshot shares differ from liquidity shares"
+            );
+            self.snapshot_day = today;
+        }
+
+        // days without shares have no inflation by construction, any other day pays out
+        for (day, (total_shares, inflation_amount, _)) in snapshots.iter().enumerate() {
+            prop_assert!(
+                *total_shares == 0.into() || *inflation_amount > 0.into(),
+                "Zero inflation amount on day {}",
+                day
+            );
+        }
+        for stake in stakes.iter() {
+            let calculation_day: u64 = stake.final_day.min(today.as_u64());
+            for day in stake.start_day..calculation_day {
+                prop_assert!(
+                    snapshots[day as usize].1 > 0.into(),
+                    "Stake earns from zero inflation on day {}",
+                    day
+                );
+            }
+        }
+
+        prop_assert!(
+            self.regular_minted
+                <= budget(
+                    snapshots.iter().map(|(total_shares, inflation_amount, _)| (
+                        *total_shares,
+                        *inflation_amount
+                    ))
+                ),
+            "Minted rewards exceed snapshot inflation"
+        );
+        // the referral snapshots split the inflation by referrer shares but links are paid on
+        // the stakes shares of the referred stakes, so the budget is taken in stakes shares
+        prop_assert!(
+            self.referral_minted
+                <= budget(
+                    rsnapshots
+                        .iter()
+                        .enumerate()
+                        .map(|(day, (_, inflation_amount, _))| {
+                            let day: u64 = day as u64;
+                            let referred_shares: U256 = sum(all_stakes
+                                .iter()
+                                .filter(|stake| stake.referrer != account_zero_address())
+                                .filter(|stake| {
+                                    let end_day: u64 = if stake.close_day > 0 {
+                                        stake.close_day
+                                    } else {
+                                        stake.final_day
+                                    };
+                                    stake.start_day <= day && day < end_day
+                                })
+                                .map(|stake| stake.stakes_shares));
+                            (referred_shares, *inflation_amount)
+                        })
+                ),
+            "Referral interest exceeds referral snapshot inflation"
+        );
+        prop_assert!(
+            self.liquidity_minted <= budget(lsnapshots.into_iter()),
+            "Liquidity rewards exceed liquidity snapshot inflation"
+        );
+        Ok(())
+    }
+}
+
+fn run(actions: &[Action]) -> Result<(), TestCaseError> {
+    let mut harness = Harness::new();
+    for action in actions {
+        harness.apply(action);
+        harness.sync();
+        harness.check()?;
+    }
+    Ok(())
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        cases: cases(),
+        max_shrink_iters: MAX_SHRINK_ITERS,
+        ..ProptestConfig::default()
+    })]
+
+    #[test]
+    fn should_keep_stake_accounting_invariants(
+        actions in prop::collection::vec(action(), 1..=MAX_ACTIONS)
+    ) {
+        run(&actions)?;
+    }
+}
+
+#[test]
+fn should_keep_stake_accounting_invariants_for_regressions() {
+    for actions in regressions() {
+        if let Err(error) = run(&actions) {
+            panic!("{} in {:?}", error, actions);
+        }
+    }
+}